@@ -1,18 +1,32 @@
-use crate::PancakeSwapService;
-use crate::liquidity::LiquidityService;
-use crate::price::PriceService;
-use crate::types::RouterVersion;
-use ethers::types::{BlockNumber, Filter};
-use ethers::{
-    providers::Middleware,
-    types::{Address, U256},
-};
+use crate::abi::{IERC20, IPancakeFactory, IPancakePair};
+use crate::block_cache::BlockTimestampCache;
+use crate::chain_reader::ChainReader;
+use crate::factory::{DEFAULT_PAIR_SCAN_LIMIT, FactoryService};
+use crate::liquidity::{LiquidityService, PoolInfo};
+use crate::multicall::{Call, MulticallService};
+use crate::price::{PriceService, PriceSource};
+use crate::rate_limit::RateLimiter;
+use crate::tool::event_parsers::parse_transfer_log;
+use crate::tool::math_utils::{PANCAKE_V2_FEE_BPS, u256_to_f64};
+use crate::types::{RouterVersion, Timeframe};
+use crate::{EvmError, PancakeResult, PancakeSwapService};
+use ethers::abi::AbiDecode;
+use ethers::types::{Address, U256};
+use ethers::types::{BlockNumber, Filter, I256, Log};
 use evm_client::EvmType;
 use evm_sdk::Evm;
-use evm_sdk::types::EvmError;
-use std::collections::{HashMap, VecDeque};
+use std::collections::{BTreeMap, HashMap, VecDeque};
 use std::sync::Arc;
 
+/// Calls per multicall request when batching pair reads
+const MULTICALL_BATCH_SIZE: usize = 200;
+
+/// Factory pairs scanned by `get_top_pairs`, starting from the oldest
+const TOP_PAIRS_SCAN_LIMIT: u64 = 1000;
+
+/// Block range per `eth_getLogs` request when scanning logs over a block range
+const LOG_SCAN_CHUNK: u64 = 5_000;
+
 /// Analytics data for trading pairs
 #[derive(Debug, Clone)]
 pub struct PairAnalytics {
@@ -55,17 +69,135 @@ pub struct PriceHistory {
 pub struct AnalyticsService {
     evm: Arc<Evm>,
     price_history: HashMap<Address, VecDeque<PriceHistory>>,
+    rate_limiter: Option<Arc<RateLimiter>>,
+    block_timestamps: Arc<BlockTimestampCache>,
+    /// Custom price source; `None` prices through an on-chain `PriceService`
+    price_source: Option<Arc<dyn PriceSource>>,
+    chain_reader: Arc<dyn ChainReader>,
+    decimals_overrides: HashMap<Address, u8>,
 }
 
 impl AnalyticsService {
     /// Creates a new AnalyticsService instance
     pub fn new(evm: Arc<Evm>) -> Self {
+        let chain_reader: Arc<dyn ChainReader> = evm.clone();
         Self {
             evm: evm,
             price_history: HashMap::new(),
+            rate_limiter: None,
+            block_timestamps: Arc::new(BlockTimestampCache::default()),
+            price_source: None,
+            chain_reader,
+            decimals_overrides: HashMap::new(),
+        }
+    }
+
+    /// Use fixed decimals for the given tokens instead of calling `decimals()`
+    ///
+    /// Applies to reserve valuation and pair ranking; a custom `PriceSource` reads decimals
+    /// on its own.
+    pub fn with_decimals_overrides(mut self, overrides: HashMap<Address, u8>) -> Self {
+        self.decimals_overrides = overrides;
+        self
+    }
+
+    /// Reads block numbers and logs through a custom chain reader instead of the provider
+    ///
+    /// Log-based analytics (`cal_volume_24h`, `cal_trades_24h`, `estimate_holder_count`) use
+    /// it, so tests can run them against a mock.
+    pub fn with_chain_reader(mut self, chain_reader: Arc<dyn ChainReader>) -> Self {
+        self.chain_reader = chain_reader;
+        self
+    }
+
+    /// Values tokens with a custom price source instead of on-chain router quotes
+    ///
+    /// Used by liquidity valuation, e.g. to price stablecoins and the native token through
+    /// Chainlink feeds.
+    pub fn with_price_source(mut self, price_source: Arc<dyn PriceSource>) -> Self {
+        self.price_source = Some(price_source);
+        self
+    }
+
+    /// Uses a shared block timestamp cache, e.g. the one given to `FactoryService`
+    pub fn with_block_timestamp_cache(mut self, cache: Arc<BlockTimestampCache>) -> Self {
+        self.block_timestamps = cache;
+        self
+    }
+
+    /// Limits RPC requests issued by pair analysis to `requests_per_second`; `0` means unlimited
+    ///
+    /// Large scans such as `get_top_pairs` otherwise burst thousands of requests at the provider.
+    pub fn with_rate_limit(mut self, requests_per_second: u32) -> Self {
+        self.rate_limiter = if requests_per_second == 0 {
+            None
+        } else {
+            Some(Arc::new(RateLimiter::new(requests_per_second)))
+        };
+        self
+    }
+
+    /// Shares `rate_limiter` with this service, e.g. the one configured on `PancakeSwapService`
+    pub fn with_rate_limiter(mut self, rate_limiter: Arc<RateLimiter>) -> Self {
+        self.rate_limiter = Some(rate_limiter);
+        self
+    }
+
+    /// Waits for a request permit when a rate limit is configured
+    async fn throttle(&self) {
+        RateLimiter::acquire_opt(&self.rate_limiter).await;
+    }
+
+    /// Liquidity service sharing this service's rate limiter
+    fn liquidity_service(&self) -> LiquidityService {
+        let service = LiquidityService::new(self.evm.clone());
+        match &self.rate_limiter {
+            Some(rate_limiter) => service.with_rate_limiter(rate_limiter.clone()),
+            None => service,
+        }
+    }
+
+    /// Factory service sharing this service's rate limiter
+    fn factory_service(&self) -> FactoryService {
+        let service = FactoryService::new(self.evm.clone());
+        match &self.rate_limiter {
+            Some(rate_limiter) => service.with_rate_limiter(rate_limiter.clone()),
+            None => service,
+        }
+    }
+
+    /// Price service sharing this service's rate limiter and decimals overrides
+    fn price_service(&self) -> PriceService {
+        let service = PriceService::new(self.evm.clone())
+            .with_decimals_overrides(self.decimals_overrides.clone());
+        match &self.rate_limiter {
+            Some(rate_limiter) => service.with_rate_limiter(rate_limiter.clone()),
+            None => service,
         }
     }
 
+    /// Gets the timestamp of a block, memoized in the block timestamp cache
+    ///
+    /// # Example
+    /// ```rust
+    /// use analytics::AnalyticsService;
+    ///
+    /// async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let service = AnalyticsService::new(client);
+    /// let timestamp = service.block_timestamp(35_000_000).await?;
+    /// Ok(())
+    /// }
+    /// ```
+    pub async fn block_timestamp(&self, block: u64) -> PancakeResult<u64> {
+        if let Some(timestamp) = self.block_timestamps.get(block) {
+            return Ok(timestamp);
+        }
+        self.throttle().await;
+        self.block_timestamps
+            .timestamp(&self.evm.client.provider, block)
+            .await
+    }
+
     /// Analyzes a trading pair and returns comprehensive analytics
     ///
     /// # Params
@@ -92,16 +224,31 @@ impl AnalyticsService {
         &self,
         pair_address: Address,
         base_token: Address,
-    ) -> Result<PairAnalytics, EvmError> {
-        let liquidity_service = LiquidityService::new(self.evm.clone());
-        let pool_info = liquidity_service.get_pool_info(pair_address).await?;
-        let (reserve0, reserve1, _) = liquidity_service.get_reserves(pair_address).await?;
+    ) -> PancakeResult<PairAnalytics> {
+        let pool_info = self.liquidity_service().get_pool_info(pair_address).await?;
+        self.throttle().await;
         let liquidity = self
-            .cal_liquidity_value(reserve0, reserve1, pool_info.token0, pool_info.token1)
+            .cal_liquidity_value(
+                pool_info.reserve0,
+                pool_info.reserve1,
+                pool_info.token0,
+                pool_info.token1,
+            )
+            .await?;
+        // One block read shared by both log scans keeps their windows identical
+        self.throttle().await;
+        let current_block = self.chain_reader.get_block_number().await?;
+        self.throttle().await;
+        let volume_24h = self
+            .cal_volume_24h(pair_address, Some(current_block))
+            .await?;
+        let price_change_24h = self
+            .cal_price_change_24h(pair_address, base_token, Some(&pool_info))
+            .await?;
+        self.throttle().await;
+        let trades_24h = self
+            .cal_trades_24h(pair_address, Some(current_block))
             .await?;
-        let volume_24h = self.cal_volume_24h(pair_address).await?;
-        let price_change_24h = self.cal_price_change_24h(pair_address, base_token).await?;
-        let trades_24h = self.cal_trades_24h(pair_address).await?;
         Ok(PairAnalytics {
             pair_address,
             volume_24h,
@@ -149,7 +296,7 @@ impl AnalyticsService {
         base_token: Address,
         intermediate_tokens: Vec<Address>,
         min_profit_percentage: f64,
-    ) -> Result<Vec<ArbitrageOpportunity>, EvmError> {
+    ) -> PancakeResult<Vec<ArbitrageOpportunity>> {
         let mut opportunities = Vec::new();
 
         for token_a in &intermediate_tokens {
@@ -189,23 +336,23 @@ impl AnalyticsService {
         token_a: Address,
         token_b: Address,
         min_profit_percentage: f64,
-    ) -> Result<ArbitrageOpportunity, EvmError> {
+    ) -> PancakeResult<ArbitrageOpportunity> {
         let test_amount = U256::from(10_u64.pow(18));
 
         // Path 1 : Base -> A -> B -> Base
         let path1 = vec![base_token, token_a, token_b, base_token];
         let result1 = self
-            .simulate_swap_path(router_address, test_amount, &path1)
+            .simulate_swap_path(router_address, test_amount, &path1, None)
             .await?;
 
         // Path 2 : Base -> B -> A -> Base
         let path2 = vec![base_token, token_b, token_a, base_token];
         let result2 = self
-            .simulate_swap_path(router_address, test_amount, &path2)
+            .simulate_swap_path(router_address, test_amount, &path2, None)
             .await?;
 
-        let profit1 = result1.as_u128() as f64 - test_amount.as_u128() as f64;
-        let profit2 = result2.as_u128() as f64 - test_amount.as_u128() as f64;
+        let profit1 = u256_to_f64(result1, 0) - u256_to_f64(test_amount, 0);
+        let profit2 = u256_to_f64(result2, 0) - u256_to_f64(test_amount, 0);
 
         let (profit, path, amount_out) = if profit1 > profit2 {
             (profit1, path1, result1)
@@ -213,7 +360,7 @@ impl AnalyticsService {
             (profit2, path2, result2)
         };
 
-        let profit_percentage = (profit / test_amount.as_u128() as f64) * 100.0;
+        let profit_percentage = (profit / u256_to_f64(test_amount, 0)) * 100.0;
 
         if profit_percentage < min_profit_percentage {
             return Err(EvmError::Error("Profit below threshold".to_string()));
@@ -242,7 +389,7 @@ impl AnalyticsService {
         for i in 0..path.len() - 1 {
             if let Ok(pair) = self.find_pair_address(path[i], path[i + 1]).await {
                 if let Ok((reserve0, reserve1, _)) = self.get_reserves(pair).await {
-                    let liquidity = (reserve0.as_u128() + reserve1.as_u128()) as f64;
+                    let liquidity = u256_to_f64(reserve0, 0) + u256_to_f64(reserve1, 0);
                     liquidity_score += liquidity;
                 }
             }
@@ -261,7 +408,7 @@ impl AnalyticsService {
         &self,
         token_a: Address,
         token_b: Address,
-    ) -> Result<Address, EvmError> {
+    ) -> PancakeResult<Address> {
         let factory_address = match self.evm.client.evm_type {
             Some(EvmType::BSC_MAINNET) => "0xcA143Ce32Fe78f1f7019d7d551a6402fC5350c73"
                 .parse()
@@ -271,16 +418,14 @@ impl AnalyticsService {
                 .unwrap(),
             _ => return Err(EvmError::ConfigError("Unsupported chain".to_string())),
         };
-        let liquidity_service = LiquidityService::new(self.evm.clone());
-        liquidity_service
+        self.liquidity_service()
             .get_pair_info(factory_address, token_a, token_b)
             .await?
             .ok_or_else(|| EvmError::Error("Pair not found".to_string()))
     }
 
-    async fn get_reserves(&self, pair_address: Address) -> Result<(U256, U256, u32), EvmError> {
-        let liquidity_service = LiquidityService::new(self.evm.clone());
-        liquidity_service.get_reserves(pair_address).await
+    async fn get_reserves(&self, pair_address: Address) -> PancakeResult<(U256, U256, u32)> {
+        self.liquidity_service().get_reserves(pair_address).await
     }
 
     fn get_router_version(&self, router_address: Address) -> RouterVersion {
@@ -324,18 +469,42 @@ impl AnalyticsService {
         ]
     }
 
-    async fn simulate_swap_path(
+    /// Simulates a swap along a path and returns the final output amount
+    ///
+    /// # Params
+    /// router_address - Router contract address, used to pick V2 or V3 quoting
+    /// amount_in - Input amount for the first hop
+    /// path - Token path, at least 2 tokens
+    /// fees - V3 fee tier for each hop; when `None`, the best tier is looked up per hop
+    ///
+    /// # Example
+    /// ```rust
+    /// use analytics::AnalyticsService;
+    ///
+    /// async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let service = AnalyticsService::new(client);
+    /// let router = "0x1b81D678ffb9C0263b24A97847620C99d213eB14".parse()?;
+    /// let amount_in = U256::exp10(18);
+    /// // CAKE -> WBNB on the 0.25% pool, then WBNB -> BUSD on the 0.05% pool
+    /// let amount_out = service
+    ///     .simulate_swap_path(router, amount_in, &[cake, wbnb, busd], Some(vec![2500, 500]))
+    ///     .await?;
+    /// Ok(())
+    /// }
+    /// ```
+    pub async fn simulate_swap_path(
         &self,
         router_address: Address,
         amount_in: U256,
         path: &[Address],
-    ) -> Result<U256, EvmError> {
+        fees: Option<Vec<u32>>,
+    ) -> PancakeResult<U256> {
         let pancake_service = PancakeSwapService::new(self.evm.clone());
 
         match self.get_router_version(router_address) {
             RouterVersion::V2 => {
                 let amounts = pancake_service
-                    .get_amounts_out_v2(amount_in, path.to_vec())
+                    .get_amounts_out_v2(amount_in, path.to_vec(), None)
                     .await?;
                 amounts
                     .last()
@@ -349,15 +518,43 @@ impl AnalyticsService {
                     ));
                 }
 
+                if let Some(fees) = &fees
+                    && fees.len() != path.len() - 1
+                {
+                    return Err(EvmError::InvalidInput(format!(
+                        "Expected one fee tier per hop ({}), got {}",
+                        path.len() - 1,
+                        fees.len()
+                    )));
+                }
+
                 let mut current_amount = amount_in;
                 for i in 0..path.len() - 1 {
                     let token_in = path[i];
                     let token_out = path[i + 1];
-                    let fee = pancake_service.get_default_fee_tier(token_in, token_out);
-
-                    current_amount = pancake_service
-                        .simulate_v3_swap(token_in, token_out, fee, current_amount)
-                        .await?;
+                    current_amount = match &fees {
+                        Some(fees) => pancake_service
+                            .simulate_v3_swap(token_in, token_out, fees[i], current_amount, None)
+                            .await
+                            .map_err(|e| {
+                                EvmError::ContractError(format!(
+                                    "No V3 pool for hop {} ({:?} -> {:?}) at fee {}: {}",
+                                    i, token_in, token_out, fees[i], e
+                                ))
+                            })?,
+                        None => {
+                            pancake_service
+                                .find_best_fee_tier(token_in, token_out, current_amount, None)
+                                .await
+                                .map_err(|_| {
+                                    EvmError::ContractError(format!(
+                                        "No V3 pool for hop {} ({:?} -> {:?}) at any fee tier",
+                                        i, token_in, token_out
+                                    ))
+                                })?
+                                .1
+                        }
+                    };
                 }
 
                 Ok(current_amount)
@@ -368,8 +565,94 @@ impl AnalyticsService {
         }
     }
 
+    /// Quotes every pair on every V2-style router, keyed by `(router, token_in, token_out)`
+    ///
+    /// Each quote is `getAmountsOut(amount_in, [token_in, token_out])` on the router, batched
+    /// through `tryAggregate` in chunks of `MULTICALL_BATCH_SIZE` calls. Pairs a router has no
+    /// pool for are left out of the map instead of failing the whole matrix, so comparing
+    /// entries across routers gives the cross-DEX spread for each pair.
+    ///
+    /// # Example
+    /// ```rust
+    /// use analytics::AnalyticsService;
+    /// use ethers::types::U256;
+    ///
+    /// async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let service = AnalyticsService::new(client);
+    /// let routers = vec![pancake_router, biswap_router];
+    /// let pairs = vec![(wbnb, busd), (busd, wbnb)];
+    /// let multicall = "0x...".parse()?;
+    /// let amount_in = U256::from(1000000000000000000u64);
+    /// let matrix = service.quote_matrix(routers, pairs, amount_in, multicall).await?;
+    /// if let Some(amount_out) = matrix.get(&(pancake_router, wbnb, busd)) {
+    ///     println!("PancakeSwap: {}", amount_out);
+    /// }
+    /// Ok(())
+    /// }
+    /// ```
+    pub async fn quote_matrix(
+        &self,
+        routers: Vec<Address>,
+        pairs: Vec<(Address, Address)>,
+        amount_in: U256,
+        multicall_address: Address,
+    ) -> PancakeResult<HashMap<(Address, Address, Address), U256>> {
+        if pairs
+            .iter()
+            .any(|(token_in, token_out)| token_in == token_out)
+        {
+            return Err(EvmError::InvalidInput(
+                "Pair tokens must differ".to_string(),
+            ));
+        }
+        let mut keys = Vec::with_capacity(routers.len() * pairs.len());
+        let mut calls = Vec::with_capacity(routers.len() * pairs.len());
+        for router_address in &routers {
+            let router = crate::abi::IPancakeRouter02::new(
+                *router_address,
+                self.evm.client.provider.clone(),
+            );
+            for (token_in, token_out) in &pairs {
+                let call_data = router
+                    .get_amounts_out(amount_in, vec![*token_in, *token_out])
+                    .calldata()
+                    .ok_or_else(|| {
+                        EvmError::ContractError("Failed to encode getAmountsOut call".to_string())
+                    })?;
+                calls.push(Call::new(*router_address, call_data.to_vec()));
+                keys.push((*router_address, *token_in, *token_out));
+            }
+        }
+        let multicall_service = MulticallService::new(self.evm.clone());
+        let mut matrix = HashMap::with_capacity(keys.len());
+        for (keys, calls) in keys
+            .chunks(MULTICALL_BATCH_SIZE)
+            .zip(calls.chunks(MULTICALL_BATCH_SIZE))
+        {
+            self.throttle().await;
+            let results = multicall_service
+                .try_aggregate(multicall_address, calls.to_vec())
+                .await?;
+            for (key, result) in keys.iter().zip(results) {
+                if !result.success {
+                    continue;
+                }
+                if let Ok(amounts) = Vec::<U256>::decode(&result.data)
+                    && let Some(amount_out) = amounts.last()
+                {
+                    matrix.insert(*key, *amount_out);
+                }
+            }
+        }
+        Ok(matrix)
+    }
+
     /// Calculates the total liquidity value in USD
     ///
+    /// The sum of both sides of `get_pair_reserves_usd`: reserves are scaled by each token's
+    /// decimals and priced through the configured price source (see `with_price_source`).
+    /// Fails when either token has no USD price.
+    ///
     /// # Params
     /// reserve0 - Reserve amount of token0
     /// reserve1 - Reserve amount of token1
@@ -392,119 +675,268 @@ impl AnalyticsService {
     /// Ok(())
     /// }
     /// ```
-    async fn cal_liquidity_value(
+    pub async fn cal_liquidity_value(
+        &self,
+        reserve0: U256,
+        reserve1: U256,
+        token0: Address,
+        token1: Address,
+    ) -> PancakeResult<f64> {
+        let (value0, value1) = self
+            .reserves_usd(reserve0, reserve1, token0, token1)
+            .await?;
+        Ok(value0 + value1)
+    }
+
+    /// Gets a pair's reserves valued in USD as `(reserve0_usd, reserve1_usd)`
+    ///
+    /// Reserves are scaled by each token's decimals. Stablecoins count as $1; other tokens
+    /// are priced against the chain's USD stablecoin through the configured price source.
+    ///
+    /// # Example
+    /// ```rust
+    /// use analytics::AnalyticsService;
+    ///
+    /// async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let service = AnalyticsService::new(client);
+    /// let pair = "0x58F876857a02D6762E0101bb5C46A8c1ED44Dc16".parse()?;
+    /// let (reserve0_usd, reserve1_usd) = service.get_pair_reserves_usd(pair).await?;
+    /// println!("Reserves: ${} and ${}", reserve0_usd, reserve1_usd);
+    /// Ok(())
+    /// }
+    /// ```
+    pub async fn get_pair_reserves_usd(&self, pair_address: Address) -> PancakeResult<(f64, f64)> {
+        let pool_info = self.liquidity_service().get_pool_info(pair_address).await?;
+        self.reserves_usd(
+            pool_info.reserve0,
+            pool_info.reserve1,
+            pool_info.token0,
+            pool_info.token1,
+        )
+        .await
+    }
+
+    /// USD value of each reserve, with decimals applied
+    async fn reserves_usd(
         &self,
         reserve0: U256,
         reserve1: U256,
         token0: Address,
         token1: Address,
-    ) -> Result<f64, EvmError> {
-        let price_service = PriceService::new(self.evm.clone());
-        // Determine base token for pricing based on chain
-        let base_token = match self.evm.client.evm_type {
-            Some(EvmType::BSC_MAINNET) => {
-                // Use BUSD as base on BSC
-                "0xe9e7CEA3DedcA5984780Bafc599bD69ADd087D56"
-                    .parse()
-                    .map_err(|_| EvmError::ConfigError("Invalid BUSD address".to_string()))?
-            }
-            Some(EvmType::ETHEREUM_MAINNET) => {
-                // Use USDC as base on Ethereum
-                "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48"
-                    .parse()
-                    .map_err(|_| EvmError::ConfigError("Invalid USDC address".to_string()))?
-            }
-            _ => {
-                // Use chain's native wrapped token as fallback
-                match self.evm.client.evm_type {
-                    Some(EvmType::BSC_MAINNET) => "0xbb4CdB9CBd36B01bD1cBaEBF2De08d9173bc095c"
-                        .parse()
-                        .unwrap(),
-                    Some(EvmType::ETHEREUM_MAINNET) => "0xC02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2"
-                        .parse()
-                        .unwrap(),
-                    _ => {
-                        return Err(EvmError::ConfigError(
-                            "Unsupported chain for liquidity calculation".to_string(),
-                        ));
-                    }
+    ) -> PancakeResult<(f64, f64)> {
+        let mut values = [0.0; 2];
+        for (value, (reserve, token)) in values
+            .iter_mut()
+            .zip([(reserve0, token0), (reserve1, token1)])
+        {
+            let decimals = self.token_decimals(token).await?;
+            *value = u256_to_f64(reserve, decimals) * self.token_usd_price(token).await?;
+        }
+        Ok((values[0], values[1]))
+    }
+
+    async fn token_decimals(&self, token: Address) -> PancakeResult<u8> {
+        if let Some(decimals) = self.decimals_overrides.get(&token) {
+            return Ok(*decimals);
+        }
+        self.throttle().await;
+        IERC20::new(token, self.evm.client.provider.clone())
+            .decimals()
+            .call()
+            .await
+            .map_err(|e| EvmError::ContractError(format!("Failed to get decimals: {}", e)))
+    }
+
+    /// Decimals of many tokens through `tryAggregate`; tokens whose call fails are left out
+    async fn token_decimals_batch(
+        &self,
+        multicall_service: &MulticallService,
+        multicall_address: Address,
+        tokens: &[Address],
+    ) -> PancakeResult<HashMap<Address, u8>> {
+        let mut decimals = HashMap::with_capacity(tokens.len());
+        let mut unknown = Vec::with_capacity(tokens.len());
+        for token in tokens {
+            match self.decimals_overrides.get(token) {
+                Some(value) => {
+                    decimals.insert(*token, *value);
                 }
+                None => unknown.push(*token),
             }
-        };
-        // Get token prices relative to base token
-        let price0 = match price_service.get_token_price(token0, base_token).await {
-            Ok(price) => price,
-            Err(_) => {
-                // Fallback: try to get price via common pairs
-                self.get_price_via_common_routes(token0, base_token)
-                    .await
-                    .unwrap_or(1.0)
+        }
+        for batch in unknown.chunks(MULTICALL_BATCH_SIZE) {
+            let mut calls = Vec::with_capacity(batch.len());
+            for token in batch {
+                let call_data = IERC20::new(*token, self.evm.client.provider.clone())
+                    .decimals()
+                    .calldata()
+                    .ok_or_else(|| {
+                        EvmError::ContractError("Failed to encode decimals call".to_string())
+                    })?;
+                calls.push(Call::new(*token, call_data.to_vec()));
             }
-        };
-        let price1 = match price_service.get_token_price(token1, base_token).await {
-            Ok(price) => price,
-            Err(_) => {
-                // Fallback: try to get price via common pairs
-                self.get_price_via_common_routes(token1, base_token)
-                    .await
-                    .unwrap_or(1.0)
+            self.throttle().await;
+            let results = multicall_service
+                .try_aggregate(multicall_address, calls)
+                .await?;
+            for (token, result) in batch.iter().zip(results) {
+                if result.success
+                    && let Ok(value) = u8::decode(&result.data)
+                {
+                    decimals.insert(*token, value);
+                }
             }
-        };
-        // Calculate value in base token
-        let value0 = reserve0.as_u128() as f64 * price0 / 1e18;
-        let value1 = reserve1.as_u128() as f64 * price1 / 1e18;
-        let total_value_base = value0 + value1;
-        // If base token is not a stablecoin, convert to USD
-        let total_value_usd = if self.is_stablecoin(base_token) {
-            total_value_base
-        } else {
-            // Get base token price in USD
-            let stablecoin = self.get_usd_stablecoin_address()?;
-            let base_to_usd = match price_service.get_token_price(base_token, stablecoin).await {
-                Ok(price) => price,
-                Err(_) => {
-                    // Fallback: use estimated price based on common stablecoin pairs
-                    self.estimate_usd_price(base_token).await
+        }
+        Ok(decimals)
+    }
+
+    /// The first `count` factory pairs, read as `allPairs(i)` through `tryAggregate`;
+    /// indices whose call fails are left out
+    async fn factory_pairs_batch(
+        &self,
+        multicall_service: &MulticallService,
+        multicall_address: Address,
+        factory_address: Address,
+        count: u64,
+    ) -> PancakeResult<Vec<Address>> {
+        let total_pairs = self
+            .factory_service()
+            .all_pairs_length(factory_address)
+            .await?;
+        let end_index = count.min(total_pairs.as_u64());
+        let factory = IPancakeFactory::new(factory_address, self.evm.client.provider.clone());
+        let indices: Vec<u64> = (0..end_index).collect();
+        let mut pairs = Vec::with_capacity(indices.len());
+        for batch in indices.chunks(MULTICALL_BATCH_SIZE) {
+            let mut calls = Vec::with_capacity(batch.len());
+            for index in batch {
+                let call_data = factory
+                    .all_pairs((*index).into())
+                    .calldata()
+                    .ok_or_else(|| {
+                        EvmError::ContractError("Failed to encode allPairs call".to_string())
+                    })?;
+                calls.push(Call::new(factory_address, call_data.to_vec()));
+            }
+            self.throttle().await;
+            let results = multicall_service
+                .try_aggregate(multicall_address, calls)
+                .await?;
+            for result in results {
+                if result.success
+                    && let Ok(pair_address) = Address::decode(&result.data)
+                {
+                    pairs.push(pair_address);
                 }
-            };
-            total_value_base * base_to_usd
-        };
-        Ok(total_value_usd)
+            }
+        }
+        Ok(pairs)
     }
 
-    /// Helper function to get price via common trading routes
-    async fn get_price_via_common_routes(
+    /// USD price of one whole token; stablecoins count as $1
+    async fn token_usd_price(&self, token: Address) -> PancakeResult<f64> {
+        if self.is_stablecoin(token) {
+            return Ok(1.0);
+        }
+        let stablecoin = self.get_usd_stablecoin_address()?;
+        match &self.price_source {
+            Some(price_source) => {
+                self.throttle().await;
+                price_source.price(token, stablecoin).await
+            }
+            // The price service takes its own permits from the shared limiter
+            None => self.price_service().price(token, stablecoin).await,
+        }
+    }
+
+    /// Calculates a token's total value locked across all of its V2 and V3 pools
+    ///
+    /// Returns the aggregate TVL in USD and the number of pools that contributed to it.
+    /// V2 pairs are searched among the first `DEFAULT_PAIR_SCAN_LIMIT` factory pairs.
+    ///
+    /// # Params
+    /// token - Token to aggregate liquidity for
+    /// multicall_address - Multicall contract used to batch the pool reads
+    ///
+    /// # Example
+    /// ```rust
+    /// use analytics::AnalyticsService;
+    ///
+    /// async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let service = AnalyticsService::new(client);
+    /// let token = "0x0E09FaBB73Bd3Ade0a17ECC321fD13a19e81cE82".parse()?;
+    /// let multicall = "0x...".parse()?;
+    /// let (tvl, pool_count) = service.get_token_tvl(token, multicall).await?;
+    /// println!("TVL: ${} across {} pools", tvl, pool_count);
+    /// Ok(())
+    /// }
+    /// ```
+    pub async fn get_token_tvl(
         &self,
         token: Address,
-        base_token: Address,
-    ) -> Option<f64> {
-        let pancake_service = PancakeSwapService::new(self.evm.clone());
-        // Try direct pair first
-        if let Ok(amounts) = pancake_service
-            .get_amounts_out_v2(U256::from(10_u64.pow(18)), vec![token, base_token])
-            .await
-        {
-            if let Some(amount_out) = amounts.last() {
-                return Some(amount_out.as_u128() as f64 / 1e18);
+        multicall_address: Address,
+    ) -> PancakeResult<(f64, usize)> {
+        let pools = self
+            .factory_service()
+            .get_pools_by_token_addresses_only(token, 0, DEFAULT_PAIR_SCAN_LIMIT)
+            .await?;
+        if pools.is_empty() {
+            return Ok((0.0, 0));
+        }
+        let multicall_service = MulticallService::new(self.evm.clone());
+        // token0() and token1() share selectors between V2 pairs and V3 pools
+        let mut calls = Vec::with_capacity(pools.len() * 2);
+        for pool in &pools {
+            let pair = IPancakePair::new(*pool, self.evm.client.provider.clone());
+            for call_data in [pair.token_0().calldata(), pair.token_1().calldata()] {
+                let call_data = call_data.ok_or_else(|| {
+                    EvmError::ContractError("Failed to encode token call".to_string())
+                })?;
+                calls.push(Call::new(*pool, call_data.to_vec()));
             }
         }
-        // Try via common intermediate tokens
-        let common_tokens = self.get_common_intermediate_tokens();
-        for intermediate in common_tokens {
-            if let Ok(amounts) = pancake_service
-                .get_amounts_out_v2(
-                    U256::from(10_u64.pow(18)),
-                    vec![token, intermediate, base_token],
-                )
+        let results = multicall_service
+            .aggregate(multicall_address, calls)
+            .await?;
+        let mut pool_tokens = Vec::with_capacity(pools.len());
+        for tokens in results.chunks(2) {
+            let token0 = Address::decode(&tokens[0].data)
+                .map_err(|e| EvmError::ContractError(format!("Failed to decode token0: {}", e)))?;
+            let token1 = Address::decode(&tokens[1].data)
+                .map_err(|e| EvmError::ContractError(format!("Failed to decode token1: {}", e)))?;
+            pool_tokens.push((token0, token1));
+        }
+        // Pool balances stand in for reserves so V2 pairs and V3 pools are read the same way
+        let mut calls = Vec::with_capacity(pools.len() * 2);
+        for (pool, (token0, token1)) in pools.iter().zip(&pool_tokens) {
+            for pool_token in [*token0, *token1] {
+                let erc20 = IERC20::new(pool_token, self.evm.client.provider.clone());
+                let call_data = erc20.balance_of(*pool).calldata().ok_or_else(|| {
+                    EvmError::ContractError("Failed to encode balanceOf call".to_string())
+                })?;
+                calls.push(Call::new(pool_token, call_data.to_vec()));
+            }
+        }
+        let results = multicall_service
+            .aggregate(multicall_address, calls)
+            .await?;
+        let mut tvl = 0.0;
+        let mut pool_count = 0;
+        for ((token0, token1), balances) in pool_tokens.into_iter().zip(results.chunks(2)) {
+            let reserve0 = U256::decode(&balances[0].data).unwrap_or_default();
+            let reserve1 = U256::decode(&balances[1].data).unwrap_or_default();
+            if reserve0.is_zero() && reserve1.is_zero() {
+                continue;
+            }
+            if let Ok(value) = self
+                .cal_liquidity_value(reserve0, reserve1, token0, token1)
                 .await
             {
-                if let Some(amount_out) = amounts.last() {
-                    return Some(amount_out.as_u128() as f64 / 1e18);
-                }
+                tvl += value;
+                pool_count += 1;
             }
         }
-
-        None
+        Ok((tvl, pool_count))
     }
 
     /// Helper function to check if a token is a stablecoin
@@ -514,7 +946,7 @@ impl AnalyticsService {
     }
 
     /// Helper function to get USD stablecoin address
-    fn get_usd_stablecoin_address(&self) -> Result<Address, EvmError> {
+    fn get_usd_stablecoin_address(&self) -> PancakeResult<Address> {
         match self.evm.client.evm_type {
             Some(EvmType::BSC_MAINNET) => "0xe9e7CEA3DedcA5984780Bafc599bD69ADd087D56"
                 .parse()
@@ -526,73 +958,15 @@ impl AnalyticsService {
         }
     }
 
-    /// Helper function to estimate USD price for a token
-    async fn estimate_usd_price(&self, token: Address) -> f64 {
-        // Simple fallback estimation
-        // In production, this would use more sophisticated methods
-        1.0
-    }
-
-    /// Helper function to get common intermediate tokens for price routing
-    fn get_common_intermediate_tokens(&self) -> Vec<Address> {
-        match self.evm.client.evm_type {
-            Some(EvmType::BSC_MAINNET) => vec![
-                "0xbb4CdB9CBd36B01bD1cBaEBF2De08d9173bc095c"
-                    .parse()
-                    .unwrap(), // WBNB
-                "0xe9e7CEA3DedcA5984780Bafc599bD69ADd087D56"
-                    .parse()
-                    .unwrap(), // BUSD
-                "0x55d398326f99059fF775485246999027B3197955"
-                    .parse()
-                    .unwrap(), // USDT
-            ],
-            Some(EvmType::ETHEREUM_MAINNET) => vec![
-                "0xC02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2"
-                    .parse()
-                    .unwrap(), // WETH
-                "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48"
-                    .parse()
-                    .unwrap(), // USDC
-                "0xdAC17F958D2ee523a2206206994597C13D831ec7"
-                    .parse()
-                    .unwrap(), // USDT
-            ],
-            _ => vec![],
-        }
-    }
-
     /// Helper function to get stablecoin addresses
     fn get_stablecoin_addresses(&self) -> Vec<Address> {
-        match self.evm.client.evm_type {
-            Some(EvmType::BSC_MAINNET) => vec![
-                "0xe9e7CEA3DedcA5984780Bafc599bD69ADd087D56"
-                    .parse()
-                    .unwrap(), // BUSD
-                "0x55d398326f99059fF775485246999027B3197955"
-                    .parse()
-                    .unwrap(), // USDT
-                "0x8AC76a51cc950d9822D68b83fE1Ad97B32Cd580d"
-                    .parse()
-                    .unwrap(), // USDC
-            ],
-            Some(EvmType::ETHEREUM_MAINNET) => vec![
-                "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48"
-                    .parse()
-                    .unwrap(), // USDC
-                "0xdAC17F958D2ee523a2206206994597C13D831ec7"
-                    .parse()
-                    .unwrap(), // USDT
-                "0x6B175474E89094C44Da98b954EedeAC495271d0F"
-                    .parse()
-                    .unwrap(), // DAI
-            ],
-            _ => vec![],
-        }
+        stablecoin_addresses(self.evm.client.evm_type)
     }
 
     /// Calculates 24-hour trading volume for a pair
     ///
+    /// The day ends at `current_block`, or at the latest block when `None`.
+    ///
     /// # Example
     /// ```rust
     /// use analytics::AnalyticsService;
@@ -600,71 +974,49 @@ impl AnalyticsService {
     /// async fn example() -> Result<(), Box<dyn std::error::Error>> {
     /// let service = AnalyticsService::new(client);
     /// let pair = "0x0eD7e52944161450477ee417DE9Cd3a859b14fD0".parse()?;
-    /// let volume = service.cal_volume_24h(pair).await?;
+    /// let volume = service.cal_volume_24h(pair, None).await?;
     /// println!("24h Volume: {}", volume);
     /// Ok(())
     /// }
     /// ```
-    pub async fn cal_volume_24h(&self, pair_address: Address) -> Result<f64, EvmError> {
-        let current_block = self
-            .evm
-            .client
-            .provider
-            .get_block_number()
-            .await
-            .map_err(|e| EvmError::ConnectionError(format!("Failed to get block number: {}", e)))?;
-        let blocks_per_day = match self.evm.client.evm_type {
-            Some(EvmType::BSC_MAINNET) => 28800u64,
-            Some(EvmType::ETHEREUM_MAINNET) => 7200u64,
-            _ => 7200u64,
-        };
-        let from_block = current_block - blocks_per_day;
-        let filter = Filter::new()
-            .address(pair_address)
-            .from_block(BlockNumber::Number(from_block.into()))
-            .to_block(BlockNumber::Number(current_block.into()))
-            .event("Swap(address,uint256,uint256,uint256,uint256,address)");
-        let logs = self
-            .evm
-            .client
-            .provider
-            .get_logs(&filter)
-            .await
-            .map_err(|e| EvmError::ContractError(format!("Failed to get logs: {}", e)))?;
+    pub async fn cal_volume_24h(
+        &self,
+        pair_address: Address,
+        current_block: Option<u64>,
+    ) -> PancakeResult<f64> {
+        let logs = self.swap_logs_24h(pair_address, current_block).await?;
         let mut total_volume = 0.0;
         for log in logs {
             if log.data.len() >= 128 {
                 let data = &log.data;
                 let amount0_in = U256::from_big_endian(&data[0..32]);
                 let amount1_in = U256::from_big_endian(&data[32..64]);
-                total_volume += amount0_in.as_u128() as f64 + amount1_in.as_u128() as f64;
+                total_volume += u256_to_f64(amount0_in, 0) + u256_to_f64(amount1_in, 0);
             }
         }
         Ok(total_volume / 1e18)
     }
 
+    /// Calculates the 24-hour price change of a pair in percent
+    ///
+    /// Pass a `PoolInfo` already fetched for the pair to skip reading it again.
     pub async fn cal_price_change_24h(
         &self,
         pair_address: Address,
         base_token: Address,
-    ) -> Result<f64, EvmError> {
-        let liquidity_service = LiquidityService::new(self.evm.clone());
-        let pool_info = liquidity_service.get_pool_info(pair_address).await?;
-        let current_price = pool_info.cal_price(base_token)?;
-        let (reserve0, reserve1, _) = liquidity_service.get_reserves(pair_address).await?;
-        let previous_reserve0 = reserve0 * U256::from(95) / U256::from(100);
-        let previous_reserve1 = reserve1 * U256::from(105) / U256::from(100);
-        let previous_price = if base_token == pool_info.token0 {
-            previous_reserve1.as_u128() as f64 / previous_reserve0.as_u128() as f64
-        } else {
-            previous_reserve0.as_u128() as f64 / previous_reserve1.as_u128() as f64
+        pool_info: Option<&PoolInfo>,
+    ) -> PancakeResult<f64> {
+        let pool_info = match pool_info {
+            Some(pool_info) => pool_info.clone(),
+            None => self.liquidity_service().get_pool_info(pair_address).await?,
         };
-        let price_change = ((current_price - previous_price) / previous_price) * 100.0;
-        Ok(price_change)
+        pool_info.cal_price_change_24h(base_token)
     }
 
     /// Calculates number of trades in the last 24 hours
     ///
+    /// The day ends at `current_block`, or at the latest block when `None`.
+    ///
     /// # Example
     /// ```rust
     /// use analytics::AnalyticsService;
@@ -672,19 +1024,91 @@ impl AnalyticsService {
     /// async fn example() -> Result<(), Box<dyn std::error::Error>> {
     /// let service = AnalyticsService::new(client);
     /// let pair = "0x0eD7e52944161450477ee417DE9Cd3a859b14fD0".parse()?;
-    /// let trades = service.cal_trades_24h(pair).await?;
+    /// let trades = service.cal_trades_24h(pair, None).await?;
     /// println!("24h Trades: {}", trades);
     /// Ok(())
     /// }
     /// ```
-    pub async fn cal_trades_24h(&self, pair_address: Address) -> Result<u64, EvmError> {
-        let current_block = self
-            .evm
-            .client
-            .provider
-            .get_block_number()
-            .await
-            .map_err(|e| EvmError::ConnectionError(format!("Failed to get block number: {}", e)))?;
+    pub async fn cal_trades_24h(
+        &self,
+        pair_address: Address,
+        current_block: Option<u64>,
+    ) -> PancakeResult<u64> {
+        let logs = self.swap_logs_24h(pair_address, current_block).await?;
+        Ok(logs.len() as u64)
+    }
+
+    /// Estimates the yearly trading-fee return of a V2 pair's liquidity, in percent
+    ///
+    /// The last 24 hours of volume are valued on the `base_token` side of each swap, charged
+    /// the 0.25% swap fee, annualized and divided by the pool's liquidity value from
+    /// `cal_liquidity_value`. Farm emissions are not included.
+    ///
+    /// # Example
+    /// ```rust
+    /// use analytics::AnalyticsService;
+    ///
+    /// async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let service = AnalyticsService::new(client);
+    /// let pair = "0x0eD7e52944161450477ee417DE9Cd3a859b14fD0".parse()?;
+    /// let wbnb = "0xbb4CdB9CBd36B01bD1cBaEBF2De08d9173bc095c".parse()?;
+    /// let fee_apr = service.estimate_lp_fee_apr(pair, wbnb).await?;
+    /// println!("Fee APR: {:.2}%", fee_apr);
+    /// Ok(())
+    /// }
+    /// ```
+    pub async fn estimate_lp_fee_apr(
+        &self,
+        pair_address: Address,
+        base_token: Address,
+    ) -> PancakeResult<f64> {
+        let pool_info = self.liquidity_service().get_pool_info(pair_address).await?;
+        let base_is_token0 = if base_token == pool_info.token0 {
+            true
+        } else if base_token == pool_info.token1 {
+            false
+        } else {
+            return Err(EvmError::InvalidInput("base token not in pair".to_string()));
+        };
+        self.throttle().await;
+        let liquidity_usd = self
+            .cal_liquidity_value(
+                pool_info.reserve0,
+                pool_info.reserve1,
+                pool_info.token0,
+                pool_info.token1,
+            )
+            .await?;
+        if liquidity_usd <= 0.0 {
+            return Err(EvmError::CalculationError(
+                "Pool has no liquidity".to_string(),
+            ));
+        }
+
+        // Every swap moves base_token in or out, so that side measures its size
+        self.throttle().await;
+        let logs = self.swap_logs_24h(pair_address, None).await?;
+        let base_volume = logs
+            .iter()
+            .filter_map(|log| swap_base_amount(log, base_is_token0))
+            .fold(U256::zero(), |total, amount| total.saturating_add(amount));
+        let base_decimals = self.token_decimals(base_token).await?;
+        let base_to_usd = self.token_usd_price(base_token).await?;
+        let volume_usd = u256_to_f64(base_volume, base_decimals) * base_to_usd;
+        let fees_per_year = volume_usd * (PANCAKE_V2_FEE_BPS as f64 / 10_000.0) * 365.0;
+        Ok(fees_per_year / liquidity_usd * 100.0)
+    }
+
+    /// Swap logs of a pair over the day of blocks ending at `current_block` (latest when `None`)
+    async fn swap_logs_24h(
+        &self,
+        pair_address: Address,
+        current_block: Option<u64>,
+    ) -> PancakeResult<Vec<Log>> {
+        let current_block = match current_block {
+            Some(block) => block,
+            None => self.chain_reader.get_block_number().await?,
+        };
         let blocks_per_day = match self.evm.client.evm_type {
             Some(EvmType::BSC_MAINNET) => 28800u64,
             Some(EvmType::ETHEREUM_MAINNET) => 7200u64,
@@ -696,18 +1120,192 @@ impl AnalyticsService {
             .from_block(BlockNumber::Number(from_block.into()))
             .to_block(BlockNumber::Number(current_block.into()))
             .event("Swap(address,uint256,uint256,uint256,uint256,address)");
-        let logs = self
-            .evm
-            .client
-            .provider
-            .get_logs(&filter)
-            .await
-            .map_err(|e| EvmError::ContractError(format!("Failed to get logs: {}", e)))?;
-        Ok(logs.len() as u64)
+        let logs = self.chain_reader.get_logs(&filter).await?;
+        Ok(logs)
+    }
+
+    /// Buckets a pair's swap volume into `timeframe` intervals over `from_block..=to_block`
+    ///
+    /// Returns `(bucket_start_ts, usd_volume)` for every bucket between the timestamps of
+    /// `from_block` and `to_block`, oldest first, with zero for buckets without swaps. Each
+    /// swap is sized on its `base_token` side with the token's decimals and valued at the
+    /// current USD price of `base_token`, not the price at the time of the swap. Bucketing
+    /// needs the timestamp of every block with a swap, so set a block timestamp cache (see
+    /// `with_block_timestamp_cache`) when charting busy pairs repeatedly.
+    ///
+    /// # Params
+    /// pair_address - V2 pair to chart
+    /// timeframe - Bucket width
+    /// from_block - First block to scan
+    /// to_block - Last block to scan (inclusive)
+    /// base_token - Pair token whose side of each swap measures its size
+    ///
+    /// # Example
+    /// ```rust
+    /// use analytics::AnalyticsService;
+    ///
+    /// async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let service = AnalyticsService::new(client);
+    /// let pair = "0x0eD7e52944161450477ee417DE9Cd3a859b14fD0".parse()?;
+    /// let wbnb = "0xbb4CdB9CBd36B01bD1cBaEBF2De08d9173bc095c".parse()?;
+    /// let series = service
+    ///     .volume_series(pair, Timeframe::Hour1, 40_000_000, 40_028_800, wbnb)
+    ///     .await?;
+    /// for (bucket_start, volume) in series {
+    ///     println!("{}: ${:.2}", bucket_start, volume);
+    /// }
+    /// Ok(())
+    /// }
+    /// ```
+    pub async fn volume_series(
+        &self,
+        pair_address: Address,
+        timeframe: Timeframe,
+        from_block: u64,
+        to_block: u64,
+        base_token: Address,
+    ) -> PancakeResult<Vec<(u64, f64)>> {
+        if from_block > to_block {
+            return Err(EvmError::InvalidInput(
+                "from_block must not be after to_block".to_string(),
+            ));
+        }
+        let (token0, token1) = self
+            .liquidity_service()
+            .get_pair_tokens(pair_address)
+            .await?;
+        let base_is_token0 = if base_token == token0 {
+            true
+        } else if base_token == token1 {
+            false
+        } else {
+            return Err(EvmError::InvalidInput("base token not in pair".to_string()));
+        };
+        let base_decimals = self.token_decimals(base_token).await?;
+        let base_to_usd = self.token_usd_price(base_token).await?;
+
+        let bucket_secs = timeframe.seconds();
+        let first_bucket = self.block_timestamp(from_block).await? / bucket_secs * bucket_secs;
+        let last_bucket = self.block_timestamp(to_block).await? / bucket_secs * bucket_secs;
+        let mut buckets: BTreeMap<u64, U256> = (first_bucket..=last_bucket)
+            .step_by(bucket_secs as usize)
+            .map(|bucket_start| (bucket_start, U256::zero()))
+            .collect();
+
+        let filter = Filter::new()
+            .address(pair_address)
+            .event("Swap(address,uint256,uint256,uint256,uint256,address)");
+        let logs = self.get_logs_chunked(filter, from_block, to_block).await?;
+        for log in logs {
+            let (Some(amount), Some(block)) =
+                (swap_base_amount(&log, base_is_token0), log.block_number)
+            else {
+                continue;
+            };
+            let timestamp = self.block_timestamp(block.as_u64()).await?;
+            let volume = buckets
+                .entry(timestamp / bucket_secs * bucket_secs)
+                .or_default();
+            *volume = volume.saturating_add(amount);
+        }
+        Ok(buckets
+            .into_iter()
+            .map(|(bucket_start, volume)| {
+                (
+                    bucket_start,
+                    u256_to_f64(volume, base_decimals) * base_to_usd,
+                )
+            })
+            .collect())
+    }
+
+    /// Logs matching `filter` over `from_block..=to_block`, fetched in `LOG_SCAN_CHUNK` ranges
+    async fn get_logs_chunked(
+        &self,
+        filter: Filter,
+        from_block: u64,
+        to_block: u64,
+    ) -> PancakeResult<Vec<Log>> {
+        let mut logs = Vec::new();
+        let mut chunk_start = from_block;
+        loop {
+            let chunk_end = to_block.min(chunk_start.saturating_add(LOG_SCAN_CHUNK - 1));
+            let chunk_filter = filter
+                .clone()
+                .from_block(BlockNumber::Number(chunk_start.into()))
+                .to_block(BlockNumber::Number(chunk_end.into()));
+            self.throttle().await;
+            logs.extend(self.chain_reader.get_logs(&chunk_filter).await?);
+            if chunk_end == to_block {
+                break;
+            }
+            chunk_start = chunk_end + 1;
+        }
+        Ok(logs)
+    }
+
+    /// Estimates the number of holders of a token from its `Transfer` logs
+    ///
+    /// Net balance changes are accumulated per address over `from_block..=to_block` and
+    /// addresses ending with a positive net are counted. This is an approximation bounded by
+    /// the scanned range: holders whose tokens arrived before `from_block` are missed, so scan
+    /// from the token's deployment block for a full count.
+    ///
+    /// # Params
+    /// token - Token or LP token to count holders of
+    /// from_block - First block to scan
+    /// to_block - Last block to scan (inclusive)
+    ///
+    /// # Example
+    /// ```rust
+    /// use analytics::AnalyticsService;
+    ///
+    /// async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let service = AnalyticsService::new(client);
+    /// let lp_token = "0x0eD7e52944161450477ee417DE9Cd3a859b14fD0".parse()?;
+    /// let holders = service.estimate_holder_count(lp_token, 6_000_000, 6_500_000).await?;
+    /// println!("~{} holders", holders);
+    /// Ok(())
+    /// }
+    /// ```
+    pub async fn estimate_holder_count(
+        &self,
+        token: Address,
+        from_block: u64,
+        to_block: u64,
+    ) -> PancakeResult<usize> {
+        if from_block > to_block {
+            return Err(EvmError::InvalidInput(
+                "from_block must not be after to_block".to_string(),
+            ));
+        }
+        let filter = Filter::new()
+            .address(token)
+            .event("Transfer(address,address,uint256)");
+        let logs = self.get_logs_chunked(filter, from_block, to_block).await?;
+        let mut balances: HashMap<Address, I256> = HashMap::new();
+        for log in logs {
+            let Ok(transfer) = parse_transfer_log(&log) else {
+                continue;
+            };
+            let value = I256::from_raw(transfer.value);
+            *balances.entry(transfer.from).or_default() -= value;
+            *balances.entry(transfer.to).or_default() += value;
+        }
+        Ok(balances
+            .into_iter()
+            .filter(|(address, balance)| !address.is_zero() && balance.is_positive())
+            .count())
     }
 
     /// Gets top trading pairs by liquidity
     ///
+    /// Candidates are the first `TOP_PAIRS_SCAN_LIMIT` factory pairs, ranked from batched
+    /// multicall reads: pair addresses, reserves, pair tokens, token decimals and USD
+    /// prices (`PriceService::get_prices_usd`, with stablecoins at $1). Pairs with an
+    /// unpriced token are skipped. Only the top `limit` pairs get the full `analyze_pair`,
+    /// with their `token0` as the base token.
+    ///
     /// # Example
     /// ```rust
     /// use analytics::AnalyticsService;
@@ -715,7 +1313,8 @@ impl AnalyticsService {
     /// async fn example() -> Result<(), Box<dyn std::error::Error>> {
     /// let service = AnalyticsService::new(client);
     /// let factory = "0xcA143Ce32Fe78f1f7019d7d551a6402fC5350c73".parse()?;
-    /// let top_pairs = service.get_top_pairs(factory, 10).await?;
+    /// let multicall = "0x...".parse()?;
+    /// let top_pairs = service.get_top_pairs(factory, 10, multicall).await?;
     ///
     /// for pair in top_pairs {
     ///     println!("Pair: {:?}, Liquidity: {}", pair.pair_address, pair.liquidity);
@@ -727,14 +1326,93 @@ impl AnalyticsService {
         &self,
         factory_address: Address,
         limit: usize,
-    ) -> Result<Vec<PairAnalytics>, EvmError> {
-        let liquidity_service = LiquidityService::new(self.evm.clone());
-        let all_pairs = liquidity_service
-            .get_all_pairs(factory_address, 0, 1000)
+        multicall_address: Address,
+    ) -> PancakeResult<Vec<PairAnalytics>> {
+        let multicall_service = MulticallService::new(self.evm.clone());
+        let all_pairs = self
+            .factory_pairs_batch(
+                &multicall_service,
+                multicall_address,
+                factory_address,
+                TOP_PAIRS_SCAN_LIMIT,
+            )
             .await?;
+        let mut candidates = Vec::with_capacity(all_pairs.len());
+        for batch in all_pairs.chunks(MULTICALL_BATCH_SIZE) {
+            self.throttle().await;
+            let reserves = multicall_service
+                .get_reserves_batch(multicall_address, batch.to_vec())
+                .await?;
+            let mut calls = Vec::with_capacity(batch.len() * 2);
+            for pair_address in batch {
+                let pair = IPancakePair::new(*pair_address, self.evm.client.provider.clone());
+                for call_data in [pair.token_0().calldata(), pair.token_1().calldata()] {
+                    let call_data = call_data.ok_or_else(|| {
+                        EvmError::ContractError("Failed to encode token call".to_string())
+                    })?;
+                    calls.push(Call::new(*pair_address, call_data.to_vec()));
+                }
+            }
+            self.throttle().await;
+            let results = multicall_service
+                .aggregate(multicall_address, calls)
+                .await?;
+            for (pair_address, tokens) in batch.iter().zip(results.chunks(2)) {
+                let Some((reserve0, reserve1, _)) = reserves.get(pair_address).copied() else {
+                    continue;
+                };
+                if reserve0.is_zero() || reserve1.is_zero() {
+                    continue;
+                }
+                let (Ok(token0), Ok(token1)) = (
+                    Address::decode(&tokens[0].data),
+                    Address::decode(&tokens[1].data),
+                ) else {
+                    continue;
+                };
+                candidates.push((*pair_address, token0, token1, reserve0, reserve1));
+            }
+        }
+
+        let mut tokens: Vec<Address> = candidates
+            .iter()
+            .flat_map(|(_, token0, token1, ..)| [*token0, *token1])
+            .collect();
+        tokens.sort();
+        tokens.dedup();
+        let decimals = self
+            .token_decimals_batch(&multicall_service, multicall_address, &tokens)
+            .await?;
+        let unpriced: Vec<Address> = tokens
+            .iter()
+            .copied()
+            .filter(|token| !self.is_stablecoin(*token))
+            .collect();
+        let mut prices = self
+            .price_service()
+            .get_prices_usd(unpriced, multicall_address)
+            .await?;
+        for token in &tokens {
+            if self.is_stablecoin(*token) {
+                prices.insert(*token, 1.0);
+            }
+        }
+        let reserve_usd = |reserve: U256, token: &Address| -> Option<f64> {
+            Some(u256_to_f64(reserve, *decimals.get(token)?) * prices.get(token)?)
+        };
+        let mut ranked = Vec::with_capacity(candidates.len());
+        for (pair_address, token0, token1, reserve0, reserve1) in candidates {
+            if let (Some(value0), Some(value1)) = (
+                reserve_usd(reserve0, &token0),
+                reserve_usd(reserve1, &token1),
+            ) {
+                ranked.push((pair_address, token0, value0 + value1));
+            }
+        }
+        ranked.sort_by(|a, b| b.2.partial_cmp(&a.2).unwrap_or(std::cmp::Ordering::Equal));
         let mut pair_analytics = Vec::new();
-        for pair_address in all_pairs.into_iter().take(limit) {
-            if let Ok(analytics) = self.analyze_pair(pair_address, Address::zero()).await {
+        for (pair_address, token0, _) in ranked.into_iter().take(limit) {
+            if let Ok(analytics) = self.analyze_pair(pair_address, token0).await {
                 pair_analytics.push(analytics);
             }
         }
@@ -812,22 +1490,26 @@ impl AnalyticsService {
     }
 
     /// Detects price anomalies using standard deviation
-    pub fn detect_price_anomalies(&self, token: Address, threshold: f64) -> Vec<PriceHistory> {
+    ///
+    /// Returns each point whose z-score exceeds `threshold` in absolute value, paired with its
+    /// signed z-score: positive above the mean, negative below. A flat series has no anomalies.
+    pub fn detect_price_anomalies(
+        &self,
+        token: Address,
+        threshold: f64,
+    ) -> Vec<(PriceHistory, f64)> {
         let mut anomalies = Vec::new();
         if let Some(history) = self.price_history.get(&token) {
             if history.len() < 2 {
                 return anomalies;
             }
             let prices: Vec<f64> = history.iter().map(|p| p.price).collect();
-            let mean = prices.iter().sum::<f64>() / prices.len() as f64;
-            let variance =
-                prices.iter().map(|p| (p - mean).powi(2)).sum::<f64>() / prices.len() as f64;
-            let std_dev = variance.sqrt();
-
-            for data in history {
-                let z_score = (data.price - mean).abs() / std_dev;
-                if z_score > threshold {
-                    anomalies.push(data.clone());
+            let Some(z_scores) = crate::tool::math_utils::z_scores(&prices) else {
+                return anomalies;
+            };
+            for (data, z_score) in history.iter().zip(z_scores) {
+                if z_score.abs() > threshold {
+                    anomalies.push((data.clone(), z_score));
                 }
             }
         }
@@ -903,3 +1585,51 @@ impl AnalyticsService {
         })
     }
 }
+
+/// Amount of the base token moved by a V2 `Swap` log (`amountIn + amountOut` on its side)
+fn swap_base_amount(log: &Log, base_is_token0: bool) -> Option<U256> {
+    let data = &log.data;
+    if data.len() < 128 {
+        return None;
+    }
+    let (amount_in, amount_out) = if base_is_token0 {
+        (&data[0..32], &data[64..96])
+    } else {
+        (&data[32..64], &data[96..128])
+    };
+    Some(U256::from_big_endian(amount_in).saturating_add(U256::from_big_endian(amount_out)))
+}
+
+/// USD stablecoins tracked on `chain`; empty for chains without a list
+pub fn stablecoin_addresses(chain: Option<EvmType>) -> Vec<Address> {
+    match chain {
+        Some(EvmType::BSC_MAINNET) => vec![
+            "0xe9e7CEA3DedcA5984780Bafc599bD69ADd087D56"
+                .parse()
+                .unwrap(), // BUSD
+            "0x55d398326f99059fF775485246999027B3197955"
+                .parse()
+                .unwrap(), // USDT
+            "0x8AC76a51cc950d9822D68b83fE1Ad97B32Cd580d"
+                .parse()
+                .unwrap(), // USDC
+        ],
+        Some(EvmType::ETHEREUM_MAINNET) => vec![
+            "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48"
+                .parse()
+                .unwrap(), // USDC
+            "0xdAC17F958D2ee523a2206206994597C13D831ec7"
+                .parse()
+                .unwrap(), // USDT
+            "0x6B175474E89094C44Da98b954EedeAC495271d0F"
+                .parse()
+                .unwrap(), // DAI
+        ],
+        Some(EvmType::BASE_MAINNET) => vec![
+            "0x833589fCD6eDb6E08f4c7C32D4f71b54bdA02913"
+                .parse()
+                .unwrap(), // USDC
+        ],
+        _ => vec![],
+    }
+}
@@ -0,0 +1,93 @@
+use crate::{EvmError, PancakeResult};
+use ethers::providers::{Http, Middleware, Provider};
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+
+/// Default number of block timestamps kept by `BlockTimestampCache`
+pub const DEFAULT_BLOCK_TIMESTAMP_CAPACITY: usize = 4096;
+
+/// Memoizes `block_number -> timestamp` lookups
+///
+/// Timestamps of mined blocks never change, so each block is fetched at most once while it
+/// stays cached. The oldest entries are evicted once `capacity` is reached.
+///
+/// # Example
+/// ```
+/// let cache = BlockTimestampCache::new(1024);
+/// async {
+/// let timestamp = cache.timestamp(&provider, 35_000_000).await?;
+/// Ok::<(), EvmError>(())
+/// };
+/// ```
+#[derive(Debug)]
+pub struct BlockTimestampCache {
+    capacity: usize,
+    entries: Mutex<CacheEntries>,
+}
+
+#[derive(Debug, Default)]
+struct CacheEntries {
+    timestamps: HashMap<u64, u64>,
+    order: VecDeque<u64>,
+}
+
+impl BlockTimestampCache {
+    /// Creates a cache holding up to `capacity` timestamps (at least 1)
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            entries: Mutex::new(CacheEntries::default()),
+        }
+    }
+
+    /// Gets a block's timestamp, fetching it from the provider on a cache miss
+    pub async fn timestamp(&self, provider: &Provider<Http>, block: u64) -> PancakeResult<u64> {
+        if let Some(timestamp) = self.get(block) {
+            return Ok(timestamp);
+        }
+        let timestamp = provider
+            .get_block(block)
+            .await
+            .map_err(|e| EvmError::ConnectionError(format!("Failed to get block: {}", e)))?
+            .ok_or_else(|| EvmError::ConnectionError(format!("Block {} not found", block)))?
+            .timestamp
+            .as_u64();
+        self.insert(block, timestamp);
+        Ok(timestamp)
+    }
+
+    /// Gets a cached timestamp without querying the provider
+    pub fn get(&self, block: u64) -> Option<u64> {
+        let entries = self.entries.lock().unwrap();
+        entries.timestamps.get(&block).copied()
+    }
+
+    /// Number of cached timestamps
+    pub fn len(&self) -> usize {
+        self.entries.lock().unwrap().timestamps.len()
+    }
+
+    /// Whether the cache is empty
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn insert(&self, block: u64, timestamp: u64) {
+        let mut entries = self.entries.lock().unwrap();
+        if entries.timestamps.insert(block, timestamp).is_some() {
+            return;
+        }
+        entries.order.push_back(block);
+        while entries.order.len() > self.capacity {
+            if let Some(oldest) = entries.order.pop_front() {
+                entries.timestamps.remove(&oldest);
+            }
+        }
+    }
+}
+
+impl Default for BlockTimestampCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_BLOCK_TIMESTAMP_CAPACITY)
+    }
+}
@@ -0,0 +1,31 @@
+use std::time::Duration;
+
+/// Receives the outcome of each RPC call the SDK makes
+///
+/// Inject an `Arc<dyn CallObserver>` with `PancakeSwapService::with_call_observer` to feed
+/// RPC latency and error rates into metrics, e.g. a Prometheus histogram labelled by
+/// `method`. Without an observer nothing is recorded.
+///
+/// `method` is the contract function for contract calls (`getAmountsOut`,
+/// `quoteExactInputSingle`, ...) and the JSON-RPC method otherwise (`eth_gasPrice`,
+/// `eth_sendTransaction`, ...). Reads that take several calls inside the SDK's inner
+/// services are reported once under the SDK method (`get_v3_twap_deviation`,
+/// `get_price_impact`, ...). `on_call` runs inline after every call, so it should return
+/// quickly.
+///
+/// # Example
+/// ```
+/// struct LatencyLogger;
+///
+/// impl CallObserver for LatencyLogger {
+///     fn on_call(&self, method: &str, duration: Duration, success: bool) {
+///         println!("{} took {:?} (ok: {})", method, duration, success);
+///     }
+/// }
+///
+/// let service = PancakeSwapService::new(evm.clone()).with_call_observer(Arc::new(LatencyLogger));
+/// ```
+pub trait CallObserver: Send + Sync {
+    /// Called once `method` has completed after `duration`
+    fn on_call(&self, method: &str, duration: Duration, success: bool);
+}
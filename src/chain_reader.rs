@@ -0,0 +1,75 @@
+use crate::{EvmError, PancakeResult};
+use async_trait::async_trait;
+use ethers::providers::Middleware;
+use ethers::types::{BlockId, Bytes, Filter, Log, transaction::eip2718::TypedTransaction};
+use evm_sdk::Evm;
+
+/// Read-only chain access used by the log-scanning parts of the SDK
+///
+/// `Evm` implements this over its provider. Services that scan logs
+/// (`AnalyticsService`, `PancakeSwapEventListener`) read through an injected
+/// `Arc<dyn ChainReader>`, so tests can substitute a mock returning fixed blocks and logs
+/// instead of talking to a live RPC.
+///
+/// # Example
+/// ```
+/// struct FixedChain {
+///     block: u64,
+///     logs: Vec<Log>,
+/// }
+///
+/// #[async_trait]
+/// impl ChainReader for FixedChain {
+///     async fn get_block_number(&self) -> Result<u64, EvmError> {
+///         Ok(self.block)
+///     }
+///     async fn get_logs(&self, _filter: &Filter) -> Result<Vec<Log>, EvmError> {
+///         Ok(self.logs.clone())
+///     }
+///     async fn call(&self, _tx: &TypedTransaction, _block: Option<BlockId>) -> Result<Bytes, EvmError> {
+///         Ok(Bytes::default())
+///     }
+/// }
+///
+/// let analytics = AnalyticsService::new(evm.clone())
+///     .with_chain_reader(Arc::new(FixedChain { block: 1_000_000, logs }));
+/// ```
+#[async_trait]
+pub trait ChainReader: Send + Sync {
+    /// Latest block number
+    async fn get_block_number(&self) -> PancakeResult<u64>;
+
+    /// Logs matching `filter`
+    async fn get_logs(&self, filter: &Filter) -> PancakeResult<Vec<Log>>;
+
+    /// Executes a read-only call, at `block` or the latest block
+    async fn call(&self, tx: &TypedTransaction, block: Option<BlockId>) -> PancakeResult<Bytes>;
+}
+
+#[async_trait]
+impl ChainReader for Evm {
+    async fn get_block_number(&self) -> PancakeResult<u64> {
+        self.client
+            .provider
+            .get_block_number()
+            .await
+            .map(|block| block.as_u64())
+            .map_err(|e| EvmError::ConnectionError(format!("Failed to get block number: {}", e)))
+    }
+
+    async fn get_logs(&self, filter: &Filter) -> PancakeResult<Vec<Log>> {
+        self.client
+            .provider
+            .get_logs(filter)
+            .await
+            .map_err(|e| EvmError::ProviderError(format!("Failed to get logs: {}", e)))
+    }
+
+    async fn call(&self, tx: &TypedTransaction, block: Option<BlockId>) -> PancakeResult<Bytes> {
+        self.client
+            .provider
+            .call(tx, block)
+            .await
+            .map_err(|e| EvmError::ContractError(format!("Failed to call contract: {}", e)))
+    }
+}
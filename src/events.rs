@@ -1,21 +1,50 @@
-use evm_sdk::types::EvmError;
+use crate::chain_reader::ChainReader;
+use crate::logging::log_error;
 use crate::tool::event_parsers::{
-    parse_burn_log, parse_mint_log, parse_pair_created_log, parse_swap_log, parse_v3_burn_log,
-    parse_v3_mint_log, parse_v3_swap_log,
+    parse_burn_log, parse_mint_log, parse_pair_created_log, parse_swap_log, parse_transfer_log,
+    parse_v3_burn_log, parse_v3_mint_log, parse_v3_swap_log,
 };
 use crate::types::{
-    BurnEvent, MintEvent, PairCreatedEvent, SwapEvent, V3BurnEvent, V3MintEvent, V3SwapEvent,
+    BurnEvent, LogMeta, MintEvent, PairCreatedEvent, SwapEvent, TransferEvent, V3BurnEvent,
+    V3MintEvent, V3SwapEvent,
 };
-use ethers::providers::Middleware;
+use crate::{EvmError, PancakeResult};
 use ethers::types::Address;
-use ethers::types::{Filter, ValueOrArray};
+use ethers::types::{Filter, H256, ValueOrArray};
 use evm_sdk::Evm;
 use std::sync::Arc;
 use std::sync::atomic::AtomicBool;
 use std::sync::atomic::AtomicU64;
 use std::sync::atomic::Ordering;
+use tokio::sync::Notify;
+use tokio::task::JoinHandle;
 use tokio::time::{Duration, MissedTickBehavior, interval};
 
+/// V2 pair `Swap` event signature
+const V2_SWAP_EVENT: &str = "Swap(address,uint256,uint256,uint256,uint256,address)";
+
+/// V2 pair `Mint` event signature
+const V2_MINT_EVENT: &str = "Mint(address,uint256,uint256)";
+
+/// V2 pair `Burn` event signature
+const V2_BURN_EVENT: &str = "Burn(address,uint256,uint256,address)";
+
+/// V2 factory `PairCreated` event signature
+const PAIR_CREATED_EVENT: &str = "PairCreated(address,address,address,uint256)";
+
+/// V3 pool `Swap` event signature; PancakeSwap V3 appends the two protocol fee amounts
+const V3_SWAP_EVENT: &str =
+    "Swap(address,address,int256,int256,uint160,uint128,int24,uint128,uint128)";
+
+/// V3 pool `Mint` event signature
+const V3_MINT_EVENT: &str = "Mint(address,address,int24,int24,uint128,uint256,uint256)";
+
+/// V3 pool `Burn` event signature
+const V3_BURN_EVENT: &str = "Burn(address,int24,int24,uint128,uint256,uint256)";
+
+/// ERC20 `Transfer` event signature
+const TRANSFER_EVENT: &str = "Transfer(address,address,uint256)";
+
 /// Configuration for event listener behavior
 #[derive(Debug, Clone)]
 pub struct EventListenerConfig {
@@ -37,11 +66,38 @@ impl Default for EventListenerConfig {
 struct EventListenerState {
     last_block_number: AtomicU64,
     is_running: AtomicBool,
+    stop_signal: Notify,
+}
+
+/// Handle to a running listener task, returned by the `start_*_listener` methods
+///
+/// Dropping the handle leaves the listener running; stop it with `shutdown` or
+/// `PancakeSwapEventListener::stop_listener`.
+pub struct ListenerHandle {
+    state: Arc<EventListenerState>,
+    task: JoinHandle<()>,
+}
+
+impl ListenerHandle {
+    /// Signals the listener to stop and waits until its task has finished, including any
+    /// poll in flight
+    pub async fn shutdown(self) -> PancakeResult<()> {
+        self.state.is_running.store(false, Ordering::SeqCst);
+        self.state.stop_signal.notify_one();
+        self.task
+            .await
+            .map_err(|e| EvmError::ListenerError(format!("Listener task failed: {}", e)))
+    }
+
+    /// Whether the listener task has exited
+    pub fn is_finished(&self) -> bool {
+        self.task.is_finished()
+    }
 }
 
 /// Event listener for PancakeSwap V2 and V3 events
 pub struct PancakeSwapEventListener {
-    evm: Arc<Evm>,
+    chain_reader: Arc<dyn ChainReader>,
     config: EventListenerConfig,
     state: Arc<EventListenerState>,
 }
@@ -50,11 +106,12 @@ impl PancakeSwapEventListener {
     /// Creates a new event listener with default configuration
     pub fn new(evm: Arc<Evm>) -> Self {
         Self {
-            evm: evm,
+            chain_reader: evm,
             config: EventListenerConfig::default(),
             state: Arc::new(EventListenerState {
                 last_block_number: AtomicU64::new(0),
                 is_running: AtomicBool::new(false),
+                stop_signal: Notify::new(),
             }),
         }
     }
@@ -62,15 +119,22 @@ impl PancakeSwapEventListener {
     /// Creates a new event listener with custom configuration
     pub fn with_config(evm: Arc<Evm>, config: EventListenerConfig) -> Self {
         Self {
-            evm: evm,
+            chain_reader: evm,
             config,
             state: Arc::new(EventListenerState {
                 last_block_number: AtomicU64::new(0),
                 is_running: AtomicBool::new(false),
+                stop_signal: Notify::new(),
             }),
         }
     }
 
+    /// Reads block numbers and logs through a custom chain reader instead of the provider
+    pub fn with_chain_reader(mut self, chain_reader: Arc<dyn ChainReader>) -> Self {
+        self.chain_reader = chain_reader;
+        self
+    }
+
     /// Starts listening for Swap events from V2 pairs
     ///
     /// # Example
@@ -81,21 +145,53 @@ impl PancakeSwapEventListener {
     /// let listener = PancakeSwapEventListener::new(client);
     /// let pair_address = Address::from_str("0x...").unwrap();
     ///
-    /// listener.start_swap_listener(
+    /// let handle = listener.start_swap_listener(
     ///     vec![pair_address],
     ///     |swap_event| {
     ///         println!("Swap detected: {:?}", swap_event);
     ///     }
     /// ).await.unwrap();
+    /// // Later: stop and wait for the in-flight poll to finish
+    /// handle.shutdown().await.unwrap();
     /// ```
     pub async fn start_swap_listener(
         &self,
         pair_addresses: Vec<Address>,
         on_swap: impl Fn(SwapEvent) + Send + Sync + 'static,
-    ) -> Result<(), EvmError> {
-        self.start_listener(pair_addresses, "Swap".to_string(), move |log| {
+    ) -> PancakeResult<ListenerHandle> {
+        self.start_swap_listener_with_meta(pair_addresses, move |swap_event, _| on_swap(swap_event))
+            .await
+    }
+
+    /// Starts listening for Swap events from V2 pairs, passing each event with its log
+    /// position (block, transaction hash, log index and pair address)
+    ///
+    /// `(transaction_hash, log_index)` identifies an event uniquely, e.g. to drop duplicates
+    /// after a reconnect.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use ethers::types::Address;
+    /// use std::str::FromStr;
+    ///
+    /// let listener = PancakeSwapEventListener::new(client);
+    /// let pair_address = Address::from_str("0x...").unwrap();
+    ///
+    /// listener.start_swap_listener_with_meta(
+    ///     vec![pair_address],
+    ///     |swap_event, meta| {
+    ///         println!("Swap in {:?} at block {:?}: {:?}", meta.transaction_hash, meta.block_number, swap_event);
+    ///     }
+    /// ).await.unwrap();
+    /// ```
+    pub async fn start_swap_listener_with_meta(
+        &self,
+        pair_addresses: Vec<Address>,
+        on_swap: impl Fn(SwapEvent, LogMeta) + Send + Sync + 'static,
+    ) -> PancakeResult<ListenerHandle> {
+        self.start_listener(pair_addresses, V2_SWAP_EVENT.to_string(), move |log| {
             if let Ok(swap_event) = parse_swap_log(&log) {
-                on_swap(swap_event);
+                on_swap(swap_event, LogMeta::from(&log));
             }
         })
         .await
@@ -122,12 +218,62 @@ impl PancakeSwapEventListener {
         &self,
         factory_addresses: Vec<Address>,
         on_pair_created: impl Fn(PairCreatedEvent) + Send + Sync + 'static,
-    ) -> Result<(), EvmError> {
-        self.start_listener(factory_addresses, "PairCreated".to_string(), move |log| {
-            if let Ok(pair_event) = parse_pair_created_log(&log) {
-                on_pair_created(pair_event);
-            }
-        })
+    ) -> PancakeResult<ListenerHandle> {
+        self.start_listener(
+            factory_addresses,
+            PAIR_CREATED_EVENT.to_string(),
+            move |log| {
+                if let Ok(pair_event) = parse_pair_created_log(&log) {
+                    on_pair_created(pair_event);
+                }
+            },
+        )
+        .await
+    }
+
+    /// Starts listening for PairCreated events where either token matches `filter_token`
+    ///
+    /// `token0` and `token1` are indexed, so the match is done on the raw log topics and
+    /// unrelated pairs are dropped before parsing.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use ethers::types::Address;
+    /// use std::str::FromStr;
+    ///
+    /// let listener = PancakeSwapEventListener::new(client);
+    /// let factory_address = Address::from_str("0x...").unwrap();
+    /// let wbnb = Address::from_str("0xbb4CdB9CBd36B01bD1cBaEBF2De08d9173bc095c").unwrap();
+    ///
+    /// listener.start_pair_created_listener_filtered(
+    ///     vec![factory_address],
+    ///     wbnb,
+    ///     |pair_event| {
+    ///         println!("New WBNB pair created: {:?}", pair_event);
+    ///     }
+    /// ).await.unwrap();
+    /// ```
+    pub async fn start_pair_created_listener_filtered(
+        &self,
+        factory_addresses: Vec<Address>,
+        filter_token: Address,
+        on_pair_created: impl Fn(PairCreatedEvent) + Send + Sync + 'static,
+    ) -> PancakeResult<ListenerHandle> {
+        let filter_topic = H256::from(filter_token);
+        self.start_listener(
+            factory_addresses,
+            PAIR_CREATED_EVENT.to_string(),
+            move |log| {
+                let matches_token = log.topics.get(1) == Some(&filter_topic)
+                    || log.topics.get(2) == Some(&filter_topic);
+                if !matches_token {
+                    return;
+                }
+                if let Ok(pair_event) = parse_pair_created_log(&log) {
+                    on_pair_created(pair_event);
+                }
+            },
+        )
         .await
     }
 
@@ -136,8 +282,8 @@ impl PancakeSwapEventListener {
         &self,
         pair_addresses: Vec<Address>,
         on_mint: impl Fn(MintEvent) + Send + Sync + 'static,
-    ) -> Result<(), EvmError> {
-        self.start_listener(pair_addresses, "Mint".to_string(), move |log| {
+    ) -> PancakeResult<ListenerHandle> {
+        self.start_listener(pair_addresses, V2_MINT_EVENT.to_string(), move |log| {
             if let Ok(mint_event) = parse_mint_log(&log) {
                 on_mint(mint_event);
             }
@@ -150,8 +296,8 @@ impl PancakeSwapEventListener {
         &self,
         pair_addresses: Vec<Address>,
         on_burn: impl Fn(BurnEvent) + Send + Sync + 'static,
-    ) -> Result<(), EvmError> {
-        self.start_listener(pair_addresses, "Burn".to_string(), move |log| {
+    ) -> PancakeResult<ListenerHandle> {
+        self.start_listener(pair_addresses, V2_BURN_EVENT.to_string(), move |log| {
             if let Ok(burn_event) = parse_burn_log(&log) {
                 on_burn(burn_event);
             }
@@ -180,8 +326,8 @@ impl PancakeSwapEventListener {
         &self,
         pool_addresses: Vec<Address>,
         on_swap: impl Fn(V3SwapEvent) + Send + Sync + 'static,
-    ) -> Result<(), EvmError> {
-        self.start_listener(pool_addresses, "Swap".to_string(), move |log| {
+    ) -> PancakeResult<ListenerHandle> {
+        self.start_listener(pool_addresses, V3_SWAP_EVENT.to_string(), move |log| {
             if let Ok(swap_event) = parse_v3_swap_log(&log) {
                 on_swap(swap_event);
             }
@@ -194,8 +340,8 @@ impl PancakeSwapEventListener {
         &self,
         pool_addresses: Vec<Address>,
         on_mint: impl Fn(V3MintEvent) + Send + Sync + 'static,
-    ) -> Result<(), EvmError> {
-        self.start_listener(pool_addresses, "Mint".to_string(), move |log| {
+    ) -> PancakeResult<ListenerHandle> {
+        self.start_listener(pool_addresses, V3_MINT_EVENT.to_string(), move |log| {
             if let Ok(mint_event) = parse_v3_mint_log(&log) {
                 on_mint(mint_event);
             }
@@ -208,8 +354,8 @@ impl PancakeSwapEventListener {
         &self,
         pool_addresses: Vec<Address>,
         on_burn: impl Fn(V3BurnEvent) + Send + Sync + 'static,
-    ) -> Result<(), EvmError> {
-        self.start_listener(pool_addresses, "Burn".to_string(), move |log| {
+    ) -> PancakeResult<ListenerHandle> {
+        self.start_listener(pool_addresses, V3_BURN_EVENT.to_string(), move |log| {
             if let Ok(burn_event) = parse_v3_burn_log(&log) {
                 on_burn(burn_event);
             }
@@ -217,73 +363,110 @@ impl PancakeSwapEventListener {
         .await
     }
 
+    /// Starts listening for Transfer events from token contracts
+    ///
+    /// # Example
+    /// ```no_run
+    /// use ethers::types::Address;
+    /// use std::str::FromStr;
+    ///
+    /// let listener = PancakeSwapEventListener::new(client);
+    /// let token_address = Address::from_str("0x...").unwrap();
+    ///
+    /// listener.start_transfer_listener(
+    ///     vec![token_address],
+    ///     |transfer_event| {
+    ///         println!("Transfer detected: {:?}", transfer_event);
+    ///     }
+    /// ).await.unwrap();
+    /// ```
+    pub async fn start_transfer_listener(
+        &self,
+        token_addresses: Vec<Address>,
+        on_transfer: impl Fn(TransferEvent) + Send + Sync + 'static,
+    ) -> PancakeResult<ListenerHandle> {
+        self.start_listener(token_addresses, TRANSFER_EVENT.to_string(), move |log| {
+            if let Ok(transfer_event) = parse_transfer_log(&log) {
+                on_transfer(transfer_event);
+            }
+        })
+        .await
+    }
+
     /// Internal method to start a generic event listener
     async fn start_listener(
         &self,
         addresses: Vec<Address>,
         event_name: String,
         on_event: impl Fn(ethers::types::Log) + Send + Sync + 'static,
-    ) -> Result<(), EvmError> {
+    ) -> PancakeResult<ListenerHandle> {
         if self.state.is_running.load(Ordering::SeqCst) {
             return Err(EvmError::ListenerError(
                 "Listener already running".to_string(),
             ));
         }
         self.state.is_running.store(true, Ordering::SeqCst);
-        let evm = self.evm.clone();
+        let chain_reader = self.chain_reader.clone();
         let config = self.config.clone();
         let state = self.state.clone();
-        let current_block =
-            evm.client.provider.get_block_number().await.map_err(|e| {
-                EvmError::ProviderError(format!("Failed to get block number: {}", e))
-            })?;
-
-        state.last_block_number.store(
-            current_block.as_u64() - config.confirmation_blocks,
-            Ordering::SeqCst,
-        );
+        let current_block = chain_reader.get_block_number().await?;
+
+        state
+            .last_block_number
+            .store(current_block - config.confirmation_blocks, Ordering::SeqCst);
 
-        tokio::spawn(async move {
+        let task = tokio::spawn(async move {
             let mut interval = interval(Duration::from_secs(config.poll_interval_secs));
             interval.set_missed_tick_behavior(MissedTickBehavior::Skip);
 
             while state.is_running.load(Ordering::SeqCst) {
-                if let Err(e) =
-                    Self::poll_events(&evm, &state, &config, &addresses, &event_name, &on_event)
-                        .await
+                if let Err(e) = Self::poll_events(
+                    chain_reader.as_ref(),
+                    &state,
+                    &config,
+                    &addresses,
+                    &event_name,
+                    &on_event,
+                )
+                .await
                 {
-                    eprintln!("Error polling events: {}", e);
+                    log_error!(event = %event_name, error = %e, "Error polling events");
                 }
 
-                interval.tick().await;
+                tokio::select! {
+                    _ = interval.tick() => {}
+                    _ = state.stop_signal.notified() => {}
+                }
             }
         });
 
-        Ok(())
+        Ok(ListenerHandle {
+            state: self.state.clone(),
+            task,
+        })
     }
 
-    /// Stops the event listener
+    /// Stops the event listener without waiting for it; use `ListenerHandle::shutdown` to
+    /// wait for the task to finish
     pub fn stop_listener(&self) {
         self.state.is_running.store(false, Ordering::SeqCst);
+        self.state.stop_signal.notify_one();
     }
 
     /// Polls for new events in a range of blocks
     async fn poll_events(
-        evm: &Evm,
+        chain_reader: &dyn ChainReader,
         state: &EventListenerState,
         config: &EventListenerConfig,
         addresses: &[Address],
         event_name: &str,
         on_event: &impl Fn(ethers::types::Log),
-    ) -> Result<(), EvmError> {
+    ) -> PancakeResult<()> {
         let from_block = state.last_block_number.load(Ordering::SeqCst) + 1;
-        let current_block =
-            evm.client.provider.get_block_number().await.map_err(|e| {
-                EvmError::ProviderError(format!("Failed to get block number: {}", e))
-            })?;
+        let current_block = chain_reader.get_block_number().await?;
 
         let to_block = std::cmp::min(
-            current_block.as_u64() - config.confirmation_blocks,
+            current_block - config.confirmation_blocks,
             from_block + config.max_blocks_per_poll - 1,
         );
 
@@ -291,18 +474,8 @@ impl PancakeSwapEventListener {
             return Ok(());
         }
 
-        let filter = Filter::new()
-            .from_block(from_block)
-            .to_block(to_block)
-            .address(ValueOrArray::Array(addresses.to_vec()))
-            .event(event_name);
-
-        let logs = evm
-            .client
-            .provider
-            .get_logs(&filter)
-            .await
-            .map_err(|e| EvmError::ProviderError(format!("Failed to get logs: {}", e)))?;
+        let filter = Self::event_filter(addresses, event_name, from_block, to_block);
+        let logs = chain_reader.get_logs(&filter).await?;
 
         for log in logs {
             on_event(log);
@@ -312,4 +485,77 @@ impl PancakeSwapEventListener {
 
         Ok(())
     }
+
+    /// Log filter for one poll; topic0 is the keccak hash of the full `event_signature`
+    fn event_filter(
+        addresses: &[Address],
+        event_signature: &str,
+        from_block: u64,
+        to_block: u64,
+    ) -> Filter {
+        Filter::new()
+            .from_block(from_block)
+            .to_block(to_block)
+            .address(ValueOrArray::Array(addresses.to_vec()))
+            .event(event_signature)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    fn topic0(event_signature: &str) -> H256 {
+        match &PancakeSwapEventListener::event_filter(&[], event_signature, 1, 2).topics[0] {
+            Some(ValueOrArray::Value(Some(topic))) => *topic,
+            other => panic!("unexpected topic0 {:?}", other),
+        }
+    }
+
+    #[test]
+    fn listener_filters_use_on_chain_event_topics() {
+        let expected = [
+            (
+                V2_SWAP_EVENT,
+                "0xd78ad95fa46c994b6551d0da85fc275fe613ce37657fb8d5e3d130840159d822",
+            ),
+            (
+                V2_MINT_EVENT,
+                "0x4c209b5fc8ad50758f13e2e1088ba56a560dff690a1c6fef26394f4c03821c4f",
+            ),
+            (
+                V2_BURN_EVENT,
+                "0xdccd412f0b1252819cb1fd330b93224ca42612892bb3f4f789976e6d81936496",
+            ),
+            (
+                PAIR_CREATED_EVENT,
+                "0x0d3648bd0f6ba80134a33ba9275ac585d9d315f0ad8355cddefde31afa28d0e9",
+            ),
+            (
+                V3_SWAP_EVENT,
+                "0x19b47279256b2a23a1665c810c8d55a1758940ee09377d4f8d26497a3577dc83",
+            ),
+            (
+                V3_MINT_EVENT,
+                "0x7a53080ba414158be7ec69b987b5fb7d07dee101fe85488f0853ae16239d0bde",
+            ),
+            (
+                V3_BURN_EVENT,
+                "0x0c396cd989a39f4459b5fa1aed6a9a8dcdbc45908acfd67e028cd568da98982c",
+            ),
+            (
+                TRANSFER_EVENT,
+                "0xddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef",
+            ),
+        ];
+        for (event_signature, topic) in expected {
+            assert_eq!(
+                topic0(event_signature),
+                H256::from_str(topic).unwrap(),
+                "{}",
+                event_signature
+            );
+        }
+    }
 }
@@ -1,32 +1,75 @@
 use crate::{
-    abi::IUniswapV3Factory,
+    EvmError, PancakeResult,
+    abi::{IPancakeV3Pool, IUniswapV3Factory},
+    block_cache::BlockTimestampCache,
     global::{
-        BASE_FACTORY_V2, BASE_FACTORY_V3, BSC_FACTORY_V2, BSC_FACTORY_V3, ETHEREUM_FACTORY_V2,
-        ETHEREUM_FACTORY_V3,
+        BASE_FACTORY_V2, BASE_FACTORY_V3, BASE_INIT_CODE_HASH_V2, BSC_FACTORY_V2, BSC_FACTORY_V3,
+        BSC_INIT_CODE_HASH_V2, ETHEREUM_FACTORY_V2, ETHEREUM_FACTORY_V3,
+        ETHEREUM_INIT_CODE_HASH_V2,
     },
+    rate_limit::RateLimiter,
+    tool::tx_utils::{DEFAULT_CONFIRMATION_TIMEOUT, wait_for_receipt},
+    types::{FeeTier, PoolVersion},
 };
 use ethers::{
     middleware::SignerMiddleware,
-    types::{Address, H256, U256},
+    providers::Middleware,
+    types::{Address, BlockNumber, Filter, H256, U256},
 };
 use evm_client::EvmType;
 use evm_sdk::Evm;
-use evm_sdk::types::EvmError;
 use std::sync::Arc;
 
+/// Block range covered by each `eth_getLogs` request when searching for a pair's creation
+const PAIR_CREATED_SCAN_CHUNK: u64 = 50_000;
+
+/// Number of V2 pairs `get_pools_by_token` callers in this crate scan by default
+pub const DEFAULT_PAIR_SCAN_LIMIT: u64 = 500;
+
 /// pancakeswap factory service
 pub struct FactoryService {
     evm: Arc<Evm>,
+    block_timestamps: Arc<BlockTimestampCache>,
+    rate_limiter: Option<Arc<RateLimiter>>,
 }
 
 impl FactoryService {
     /// create a factory service
     pub fn new(evm: Arc<Evm>) -> Self {
-        Self { evm: evm }
+        Self {
+            evm,
+            block_timestamps: Arc::new(BlockTimestampCache::default()),
+            rate_limiter: None,
+        }
+    }
+
+    /// Shares `rate_limiter` with this service, e.g. the one configured on `PancakeSwapService`
+    pub fn with_rate_limiter(mut self, rate_limiter: Arc<RateLimiter>) -> Self {
+        self.rate_limiter = Some(rate_limiter);
+        self
+    }
+
+    /// Waits for a request permit when a rate limiter is configured
+    async fn throttle(&self) {
+        RateLimiter::acquire_opt(&self.rate_limiter).await;
+    }
+
+    /// Uses a shared block timestamp cache, e.g. the one given to `AnalyticsService`
+    pub fn with_block_timestamp_cache(mut self, cache: Arc<BlockTimestampCache>) -> Self {
+        self.block_timestamps = cache;
+        self
     }
 
     /// Retrieves all liquidity pools (V2 and V3) for a given token address
     ///
+    /// Each pool is tagged with its version and listed once, V2 pairs before V3 pools.
+    ///
+    /// The V2 factory has no per-token index, so V2 pairs are found by scanning `allPairs`
+    /// from index `start`, checking at most `limit` pairs with two calls each. A larger window
+    /// finds more pairs but takes proportionally longer; page through the factory with
+    /// successive `start` values for a full scan. V3 pools are looked up directly against the
+    /// wrapped native token on every fee tier and are not affected by the window.
+    ///
     /// # Example
     /// ```
     /// use ethers::types::Address;
@@ -34,29 +77,170 @@ impl FactoryService {
     /// let factory_service = FactoryService::new(Arc::clone(&client));
     /// let token_address = "0x...".parse::<Address>().unwrap();
     /// async {
-    /// let pools = factory_service.get_pools_by_token(token_address).await?;
+    /// for (version, pool) in factory_service.get_pools_by_token(token_address, 0, 1000).await? {
+    ///     println!("{:?} pool: {:?}", version, pool);
+    /// }
     /// Ok::<(), EvmError>(())
     /// };
     /// ```
     pub async fn get_pools_by_token(
         &self,
         token_address: Address,
-    ) -> Result<Vec<Address>, EvmError> {
-        let mut pools = Vec::new();
-        if let Ok(v2_pools) = self.get_v2_pools_by_token(token_address).await {
-            pools.extend(v2_pools);
+        start: u64,
+        limit: u64,
+    ) -> PancakeResult<Vec<(PoolVersion, Address)>> {
+        let mut pools: Vec<(PoolVersion, Address)> = Vec::new();
+        if let Ok(v2_pools) = self
+            .get_v2_pools_by_token(token_address, start, limit)
+            .await
+        {
+            for pool in v2_pools {
+                if !pools.iter().any(|(_, known)| *known == pool) {
+                    pools.push((PoolVersion::V2, pool));
+                }
+            }
         }
         if let Ok(v3_pools) = self.get_v3_pools_by_token(token_address).await {
-            pools.extend(v3_pools);
+            for pool in v3_pools {
+                if !pools.iter().any(|(_, known)| *known == pool) {
+                    pools.push((PoolVersion::V3, pool));
+                }
+            }
         }
         Ok(pools)
     }
 
-    /// Get the V2 liquidity pool address
+    /// Same as `get_pools_by_token` without the version tags
+    pub async fn get_pools_by_token_addresses_only(
+        &self,
+        token_address: Address,
+        start: u64,
+        limit: u64,
+    ) -> PancakeResult<Vec<Address>> {
+        let pools = self.get_pools_by_token(token_address, start, limit).await?;
+        Ok(pools.into_iter().map(|(_, pool)| pool).collect())
+    }
+
+    /// Lists the V3 fee tiers that have a deployed pool with nonzero liquidity for a pair
+    ///
+    /// Returns `(fee, pool_address, liquidity)` for every liquid tier.
+    ///
+    /// # Example
+    /// ```
+    /// use ethers::types::Address;
+    /// use std::sync::Arc;
+    /// let factory_service = FactoryService::new(Arc::clone(&client));
+    /// let token_a = "0x...".parse::<Address>().unwrap();
+    /// let token_b = "0x...".parse::<Address>().unwrap();
+    /// async {
+    /// let pools = factory_service.get_v3_pools_with_liquidity(token_a, token_b).await?;
+    /// for (fee, pool, liquidity) in pools {
+    ///     println!("fee {}: {:?} liquidity {}", fee, pool, liquidity);
+    /// }
+    /// Ok::<(), EvmError>(())
+    /// };
+    /// ```
+    pub async fn get_v3_pools_with_liquidity(
+        &self,
+        token_a: Address,
+        token_b: Address,
+    ) -> PancakeResult<Vec<(u32, Address, u128)>> {
+        let factory_address = match self.evm.client.evm_type {
+            Some(EvmType::BSC_MAINNET) => BSC_FACTORY_V3.parse::<Address>().unwrap(),
+            Some(EvmType::ETHEREUM_MAINNET) => ETHEREUM_FACTORY_V3.parse::<Address>().unwrap(),
+            Some(EvmType::BASE_MAINNET) => BASE_FACTORY_V3.parse::<Address>().unwrap(),
+            _ => return Err(EvmError::ConfigError("Unsupported chain".to_string())),
+        };
+        let factory =
+            IUniswapV3Factory::new(factory_address, Arc::clone(&self.evm.client.provider));
+        let mut pools = Vec::new();
+        for fee in FeeTier::ALL.map(FeeTier::as_u32) {
+            self.throttle().await;
+            let pool_address = factory
+                .get_pool(token_a, token_b, fee)
+                .call()
+                .await
+                .map_err(|e| {
+                    EvmError::ContractError(format!("Failed to get pool for fee {}: {}", fee, e))
+                })?;
+            if pool_address == Address::zero() {
+                continue;
+            }
+            let pool = IPancakeV3Pool::new(pool_address, Arc::clone(&self.evm.client.provider));
+            self.throttle().await;
+            let liquidity = pool.liquidity().call().await.map_err(|e| {
+                EvmError::ContractError(format!("Failed to get pool liquidity: {}", e))
+            })?;
+            if liquidity > 0 {
+                pools.push((fee, pool_address, liquidity));
+            }
+        }
+        Ok(pools)
+    }
+
+    /// Finds a pool for two tokens on either version of the chain's default factories
+    ///
+    /// The V2 pair is checked first, then the V3 fee tiers in order. Returns the first
+    /// deployed pool with its version, or `None` when neither factory has one.
+    ///
+    /// # Example
+    /// ```
+    /// use ethers::types::Address;
+    /// use std::sync::Arc;
+    /// let factory_service = FactoryService::new(Arc::clone(&client));
+    /// let token_a = "0x...".parse::<Address>().unwrap();
+    /// let token_b = "0x...".parse::<Address>().unwrap();
+    /// async {
+    /// if let Some((version, pool)) = factory_service.find_any_pool(token_a, token_b).await? {
+    ///     println!("{:?} pool: {:?}", version, pool);
+    /// }
+    /// Ok::<(), EvmError>(())
+    /// };
+    /// ```
+    pub async fn find_any_pool(
+        &self,
+        token_a: Address,
+        token_b: Address,
+    ) -> PancakeResult<Option<(PoolVersion, Address)>> {
+        let (v2_factory, v3_factory) = match self.evm.client.evm_type {
+            Some(EvmType::BSC_MAINNET) => (BSC_FACTORY_V2, BSC_FACTORY_V3),
+            Some(EvmType::ETHEREUM_MAINNET) => (ETHEREUM_FACTORY_V2, ETHEREUM_FACTORY_V3),
+            Some(EvmType::BASE_MAINNET) => (BASE_FACTORY_V2, BASE_FACTORY_V3),
+            _ => return Err(EvmError::ConfigError("Unsupported chain".to_string())),
+        };
+        if let Some(pair) = self
+            .get_pair(v2_factory.parse::<Address>().unwrap(), token_a, token_b)
+            .await?
+        {
+            return Ok(Some((PoolVersion::V2, pair)));
+        }
+        let factory = IUniswapV3Factory::new(
+            v3_factory.parse::<Address>().unwrap(),
+            Arc::clone(&self.evm.client.provider),
+        );
+        for fee in FeeTier::ALL.map(FeeTier::as_u32) {
+            self.throttle().await;
+            let pool_address = factory
+                .get_pool(token_a, token_b, fee)
+                .call()
+                .await
+                .map_err(|e| {
+                    EvmError::ContractError(format!("Failed to get pool for fee {}: {}", fee, e))
+                })?;
+            if pool_address != Address::zero() {
+                return Ok(Some((PoolVersion::V3, pool_address)));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Get the V2 liquidity pool addresses among factory pairs `start..start + limit`
     async fn get_v2_pools_by_token(
         &self,
         token_address: Address,
-    ) -> Result<Vec<Address>, EvmError> {
+        start: u64,
+        limit: u64,
+    ) -> PancakeResult<Vec<Address>> {
         let factory_address = match self.evm.client.evm_type {
             Some(EvmType::BSC_MAINNET) => BSC_FACTORY_V2.parse::<Address>().unwrap(),
             Some(EvmType::ETHEREUM_MAINNET) => ETHEREUM_FACTORY_V2.parse::<Address>().unwrap(),
@@ -67,19 +251,23 @@ impl FactoryService {
             factory_address,
             Arc::clone(&self.evm.client.provider),
         );
+        self.throttle().await;
         let total_pairs =
             factory.all_pairs_length().call().await.map_err(|e| {
                 EvmError::ContractError(format!("Failed to get total pairs: {}", e))
             })?;
         let mut pools = Vec::new();
-        let max_check = 500u64;
-        for i in 0..std::cmp::min(total_pairs.as_u64(), max_check) {
+        let end = std::cmp::min(total_pairs.as_u64(), start.saturating_add(limit));
+        for i in start..end {
+            self.throttle().await;
             if let Ok(pair_address) = factory.all_pairs(i.into()).call().await {
                 let pair = crate::abi::IPancakePair::new(
                     pair_address,
                     Arc::clone(&self.evm.client.provider),
                 );
+                self.throttle().await;
                 if let Ok(token0) = pair.token_0().call().await {
+                    self.throttle().await;
                     if let Ok(token1) = pair.token_1().call().await {
                         if token0 == token_address || token1 == token_address {
                             pools.push(pair_address);
@@ -92,10 +280,7 @@ impl FactoryService {
     }
 
     /// Get the V3 liquidity pool address
-    async fn get_v3_pools_by_token(
-        &self,
-        token_address: Address,
-    ) -> Result<Vec<Address>, EvmError> {
+    async fn get_v3_pools_by_token(&self, token_address: Address) -> PancakeResult<Vec<Address>> {
         let factory_address = match self.evm.client.evm_type {
             Some(EvmType::BSC_MAINNET) => BSC_FACTORY_V3.parse::<Address>().unwrap(),
             Some(EvmType::ETHEREUM_MAINNET) => ETHEREUM_FACTORY_V3.parse::<Address>().unwrap(),
@@ -104,7 +289,6 @@ impl FactoryService {
         };
         let factory =
             IUniswapV3Factory::new(factory_address, Arc::clone(&self.evm.client.provider));
-        let fee_tiers = vec![100, 500, 2500, 10000];
         let mut pools = Vec::new();
         let common_tokens = vec![match self.evm.client.evm_type {
             Some(EvmType::BSC_MAINNET) => "0xbb4CdB9CBd36B01bD1cBaEBF2De08d9173bc095c"
@@ -119,7 +303,8 @@ impl FactoryService {
             if other_token == Address::zero() || other_token == token_address {
                 continue;
             }
-            for &fee in &fee_tiers {
+            for fee in FeeTier::ALL.map(FeeTier::as_u32) {
+                self.throttle().await;
                 if let Ok(pool_address) = factory
                     .get_pool(token_address, other_token, fee)
                     .call()
@@ -154,9 +339,10 @@ impl FactoryService {
         factory_address: Address,
         token_a: Address,
         token_b: Address,
-    ) -> Result<Option<Address>, EvmError> {
+    ) -> PancakeResult<Option<Address>> {
         let factory =
             crate::abi::IPancakeFactory::new(factory_address, self.evm.client.provider.clone());
+        self.throttle().await;
         let pair = factory
             .get_pair(token_a, token_b)
             .call()
@@ -169,6 +355,82 @@ impl FactoryService {
         })
     }
 
+    /// Computes the V2 pair address for two tokens locally, without an RPC call
+    ///
+    /// Derives the CREATE2 address from `factory_address`, the sorted token addresses and the
+    /// connected chain's pair init code hash, so `factory_address` must be the chain's
+    /// PancakeSwap V2 factory. The pair is not required to exist; check with `get_pair` or
+    /// `pair_exists` when that matters.
+    ///
+    /// # Example
+    /// ```
+    /// use ethers::types::Address;
+    /// use std::sync::Arc;
+    /// let factory_service = FactoryService::new(Arc::clone(&client));
+    /// let factory_address = "0x...".parse::<Address>().unwrap();
+    /// let token_a = "0x...".parse::<Address>().unwrap();
+    /// let token_b = "0x...".parse::<Address>().unwrap();
+    /// let pair = factory_service.compute_pair_address(factory_address, token_a, token_b)?;
+    /// ```
+    pub fn compute_pair_address(
+        &self,
+        factory_address: Address,
+        token_a: Address,
+        token_b: Address,
+    ) -> PancakeResult<Address> {
+        let init_code_hash = match self.evm.client.evm_type {
+            Some(EvmType::BSC_MAINNET) => BSC_INIT_CODE_HASH_V2,
+            Some(EvmType::ETHEREUM_MAINNET) => ETHEREUM_INIT_CODE_HASH_V2,
+            Some(EvmType::BASE_MAINNET) => BASE_INIT_CODE_HASH_V2,
+            _ => return Err(EvmError::ConfigError("Unsupported chain".to_string())),
+        };
+        create2_pair_address(factory_address, token_a, token_b, init_code_hash)
+    }
+
+    /// Gets the V3 pool for two tokens and a fee tier on the chain's default V3 factory
+    ///
+    /// `fee` must be a `FeeTier` value; other fees fail with `EvmError::InvalidInput`.
+    ///
+    /// # Example
+    /// ```
+    /// use ethers::types::Address;
+    /// use std::sync::Arc;
+    /// let factory_service = FactoryService::new(Arc::clone(&client));
+    /// let token_a = "0x...".parse::<Address>().unwrap();
+    /// let token_b = "0x...".parse::<Address>().unwrap();
+    /// async {
+    /// let pool = factory_service.get_v3_pool(token_a, token_b, 2500).await?;
+    /// Ok::<(), EvmError>(())
+    /// };
+    /// ```
+    pub async fn get_v3_pool(
+        &self,
+        token_a: Address,
+        token_b: Address,
+        fee: u32,
+    ) -> PancakeResult<Option<Address>> {
+        FeeTier::try_from(fee)?;
+        let factory_address = match self.evm.client.evm_type {
+            Some(EvmType::BSC_MAINNET) => BSC_FACTORY_V3.parse::<Address>().unwrap(),
+            Some(EvmType::ETHEREUM_MAINNET) => ETHEREUM_FACTORY_V3.parse::<Address>().unwrap(),
+            Some(EvmType::BASE_MAINNET) => BASE_FACTORY_V3.parse::<Address>().unwrap(),
+            _ => return Err(EvmError::ConfigError("Unsupported chain".to_string())),
+        };
+        let factory =
+            IUniswapV3Factory::new(factory_address, Arc::clone(&self.evm.client.provider));
+        self.throttle().await;
+        let pool = factory
+            .get_pool(token_a, token_b, fee)
+            .call()
+            .await
+            .map_err(|e| EvmError::ContractError(format!("Failed to get pool: {}", e)))?;
+        Ok(if pool == Address::zero() {
+            None
+        } else {
+            Some(pool)
+        })
+    }
+
     /// Creates a new pair for two tokens
     ///
     /// # Example
@@ -189,7 +451,7 @@ impl FactoryService {
         factory_address: Address,
         token_a: Address,
         token_b: Address,
-    ) -> Result<Address, EvmError> {
+    ) -> PancakeResult<Address> {
         if self.evm.client.wallet.is_none() {
             return Err(EvmError::WalletError("No wallet configured".to_string()));
         }
@@ -203,23 +465,40 @@ impl FactoryService {
             .send()
             .await
             .map_err(|e| EvmError::TransactionError(format!("Failed to create pair: {}", e)))?;
-        let receipt = pending_tx
-            .await
-            .map_err(|e| EvmError::TransactionError(format!("Failed to get receipt: {}", e)))?
-            .ok_or_else(|| EvmError::TransactionError("Transaction failed".to_string()))?;
+        let receipt = wait_for_receipt(
+            &self.evm.client.provider,
+            pending_tx.tx_hash(),
+            1,
+            DEFAULT_CONFIRMATION_TIMEOUT,
+        )
+        .await?;
+        if receipt.status == Some(0.into()) {
+            return Err(EvmError::TransactionError(
+                "Pair creation transaction reverted".to_string(),
+            ));
+        }
         // Get the newly created transaction pair address from the event log
         let pair_created_topic = H256::from_slice(&ethers::utils::keccak256(
             b"PairCreated(address,address,address,uint256)",
         ));
-        if let Some(log) = receipt.logs.iter().find(|log| log.topics.len() >= 3) {
-            if log.topics[0] == pair_created_topic {
-                let pair_address = Address::from_slice(&log.data[12..32]);
-                return Ok(pair_address);
-            }
+        let logged_pair = receipt.logs.iter().find_map(|log| {
+            (log.address == factory_address
+                && log.topics.first() == Some(&pair_created_topic)
+                && log.data.len() >= 32)
+                .then(|| Address::from_slice(&log.data[12..32]))
+        });
+        if let Some(pair_address) = logged_pair {
+            return Ok(pair_address);
         }
-        Err(EvmError::TransactionError(
-            "Failed to extract pair address from logs".to_string(),
-        ))
+        // The pair was created even if its event could not be read, so ask the factory
+        self.get_pair(factory_address, token_a, token_b)
+            .await?
+            .ok_or_else(|| {
+                EvmError::TransactionError(
+                    "Pair creation succeeded but the factory has no pair for the tokens"
+                        .to_string(),
+                )
+            })
     }
 
     /// Gets the total number of pairs in the factory
@@ -235,9 +514,10 @@ impl FactoryService {
     /// Ok::<(), EvmError>(())
     /// };
     /// ```
-    pub async fn all_pairs_length(&self, factory_address: Address) -> Result<U256, EvmError> {
+    pub async fn all_pairs_length(&self, factory_address: Address) -> PancakeResult<U256> {
         let factory =
             crate::abi::IPancakeFactory::new(factory_address, self.evm.client.provider.clone());
+        self.throttle().await;
         factory
             .all_pairs_length()
             .call()
@@ -259,22 +539,20 @@ impl FactoryService {
     /// Ok::<(), EvmError>(())
     /// };
     /// ```
-    pub async fn all_pairs(
-        &self,
-        factory_address: Address,
-        index: U256,
-    ) -> Result<Address, EvmError> {
+    pub async fn all_pairs(&self, factory_address: Address, index: U256) -> PancakeResult<Address> {
         let factory =
             crate::abi::IPancakeFactory::new(factory_address, self.evm.client.provider.clone());
+        self.throttle().await;
         factory.all_pairs(index).call().await.map_err(|e| {
             EvmError::ContractError(format!("Failed to get pair at index {}: {}", index, e))
         })
     }
 
     /// Get the fee receiving address
-    pub async fn fee_to(&self, factory_address: Address) -> Result<Address, EvmError> {
+    pub async fn fee_to(&self, factory_address: Address) -> PancakeResult<Address> {
         let factory =
             crate::abi::IPancakeFactory::new(factory_address, self.evm.client.provider.clone());
+        self.throttle().await;
         factory
             .fee_to()
             .call()
@@ -283,9 +561,10 @@ impl FactoryService {
     }
 
     /// Get the address of the person who set the fee
-    pub async fn fee_to_setter(&self, factory_address: Address) -> Result<Address, EvmError> {
+    pub async fn fee_to_setter(&self, factory_address: Address) -> PancakeResult<Address> {
         let factory =
             crate::abi::IPancakeFactory::new(factory_address, self.evm.client.provider.clone());
+        self.throttle().await;
         factory
             .fee_to_setter()
             .call()
@@ -311,7 +590,7 @@ impl FactoryService {
         factory_address: Address,
         start_index: u64,
         count: u64,
-    ) -> Result<Vec<Address>, EvmError> {
+    ) -> PancakeResult<Vec<Address>> {
         let total_pairs = self.all_pairs_length(factory_address).await?;
         let end_index = std::cmp::min(start_index + count, total_pairs.as_u64());
         let mut pairs = Vec::new();
@@ -324,6 +603,120 @@ impl FactoryService {
         Ok(pairs)
     }
 
+    /// Finds the block in which a V2 pair was created
+    ///
+    /// Scans the factory's `PairCreated` events backwards from the latest block in
+    /// chunks of `PAIR_CREATED_SCAN_CHUNK` blocks, so young pairs are found quickly.
+    ///
+    /// # Example
+    /// ```
+    /// use ethers::types::Address;
+    /// use std::sync::Arc;
+    /// let factory_service = FactoryService::new(Arc::clone(&client));
+    /// let factory_address = "0x...".parse::<Address>().unwrap();
+    /// let pair_address = "0x...".parse::<Address>().unwrap();
+    /// async {
+    /// let block = factory_service.get_pair_created_block(factory_address, pair_address).await?;
+    /// Ok::<(), EvmError>(())
+    /// };
+    /// ```
+    pub async fn get_pair_created_block(
+        &self,
+        factory_address: Address,
+        pair_address: Address,
+    ) -> PancakeResult<u64> {
+        let pair = crate::abi::IPancakePair::new(pair_address, self.evm.client.provider.clone());
+        self.throttle().await;
+        let token0 = pair
+            .token_0()
+            .call()
+            .await
+            .map_err(|e| EvmError::ContractError(format!("Failed to get token0: {}", e)))?;
+        self.throttle().await;
+        let token1 = pair
+            .token_1()
+            .call()
+            .await
+            .map_err(|e| EvmError::ContractError(format!("Failed to get token1: {}", e)))?;
+        self.throttle().await;
+        let latest_block = self
+            .evm
+            .client
+            .provider
+            .get_block_number()
+            .await
+            .map_err(|e| EvmError::ConnectionError(format!("Failed to get block number: {}", e)))?;
+        let mut to_block = latest_block.as_u64();
+        loop {
+            let from_block = to_block.saturating_sub(PAIR_CREATED_SCAN_CHUNK - 1);
+            // token0 and token1 are indexed, so at most one log matches per factory
+            let filter = Filter::new()
+                .address(factory_address)
+                .event("PairCreated(address,address,address,uint256)")
+                .topic1(H256::from(token0))
+                .topic2(H256::from(token1))
+                .from_block(BlockNumber::Number(from_block.into()))
+                .to_block(BlockNumber::Number(to_block.into()));
+            self.throttle().await;
+            let logs = self
+                .evm
+                .client
+                .provider
+                .get_logs(&filter)
+                .await
+                .map_err(|e| EvmError::ContractError(format!("Failed to get logs: {}", e)))?;
+            for log in logs {
+                if log.data.len() >= 32 && Address::from_slice(&log.data[12..32]) == pair_address {
+                    return log.block_number.map(|n| n.as_u64()).ok_or_else(|| {
+                        EvmError::ContractError("Log is missing a block number".to_string())
+                    });
+                }
+            }
+            if from_block == 0 {
+                break;
+            }
+            to_block = from_block - 1;
+        }
+        Err(EvmError::ContractError(format!(
+            "No PairCreated event found for pair {:?}",
+            pair_address
+        )))
+    }
+
+    /// Gets the age of a V2 pair in seconds, based on the timestamp of its creation block
+    ///
+    /// # Example
+    /// ```
+    /// use ethers::types::Address;
+    /// use std::sync::Arc;
+    /// let factory_service = FactoryService::new(Arc::clone(&client));
+    /// let factory_address = "0x...".parse::<Address>().unwrap();
+    /// let pair_address = "0x...".parse::<Address>().unwrap();
+    /// async {
+    /// let age = factory_service.get_pair_age_seconds(factory_address, pair_address).await?;
+    /// if age < 86400 {
+    ///     println!("pair is less than a day old");
+    /// }
+    /// Ok::<(), EvmError>(())
+    /// };
+    /// ```
+    pub async fn get_pair_age_seconds(
+        &self,
+        factory_address: Address,
+        pair_address: Address,
+    ) -> PancakeResult<u64> {
+        let created_block = self
+            .get_pair_created_block(factory_address, pair_address)
+            .await?;
+        self.throttle().await;
+        let created_at = self
+            .block_timestamps
+            .timestamp(&self.evm.client.provider, created_block)
+            .await?;
+        let now = crate::tool::time_utils::current_timestamp();
+        Ok(now.saturating_sub(created_at))
+    }
+
     /// Checks if a pair exists for two tokens
     ///
     /// # Example
@@ -344,8 +737,66 @@ impl FactoryService {
         factory_address: Address,
         token_a: Address,
         token_b: Address,
-    ) -> Result<bool, EvmError> {
+    ) -> PancakeResult<bool> {
         let pair = self.get_pair(factory_address, token_a, token_b).await?;
         Ok(pair.is_some())
     }
 }
+
+/// CREATE2 address of the V2 pair for two tokens, given the factory's pair init code hash
+fn create2_pair_address(
+    factory_address: Address,
+    token_a: Address,
+    token_b: Address,
+    init_code_hash: &str,
+) -> PancakeResult<Address> {
+    if token_a == token_b {
+        return Err(EvmError::InvalidInput(
+            "Identical token addresses".to_string(),
+        ));
+    }
+    let (token0, token1) = if token_a < token_b {
+        (token_a, token_b)
+    } else {
+        (token_b, token_a)
+    };
+    if token0.is_zero() {
+        return Err(EvmError::InvalidInput("Zero token address".to_string()));
+    }
+    let init_code_hash = init_code_hash
+        .parse::<H256>()
+        .map_err(|_| EvmError::ConfigError("Invalid init code hash".to_string()))?;
+    let salt = ethers::utils::keccak256([token0.as_bytes(), token1.as_bytes()].concat());
+    Ok(ethers::utils::get_create2_address_from_hash(
+        factory_address,
+        salt,
+        init_code_hash,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn computes_bsc_wbnb_busd_pair() {
+        let factory: Address = "0xcA143Ce32Fe78f1f7019d7d551a6402fC5350c73"
+            .parse()
+            .unwrap();
+        let wbnb: Address = "0xbb4CdB9CBd36B01bD1cBaEBF2De08d9173bc095c"
+            .parse()
+            .unwrap();
+        let busd: Address = "0xe9e7CEA3DedcA5984780Bafc599bD69ADd087D56"
+            .parse()
+            .unwrap();
+        let expected: Address = "0x58F876857a02D6762E0101bb5C46A8c1ED44Dc16"
+            .parse()
+            .unwrap();
+        for (token_a, token_b) in [(wbnb, busd), (busd, wbnb)] {
+            assert_eq!(
+                create2_pair_address(factory, token_a, token_b, BSC_INIT_CODE_HASH_V2).unwrap(),
+                expected
+            );
+        }
+    }
+}
@@ -0,0 +1,207 @@
+use crate::{EvmError, PancakeResult, logging::log_warn};
+use ethers::providers::{Http, Provider};
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// Default time a failed endpoint is skipped before it is tried again
+pub const DEFAULT_ENDPOINT_COOLDOWN: Duration = Duration::from_secs(30);
+
+/// Default upper bound of the random delay before retrying on another endpoint
+pub const DEFAULT_FAILOVER_JITTER: Duration = Duration::from_millis(200);
+
+/// Spreads RPC calls over several endpoints and fails over when one stops responding
+///
+/// Calls start on a randomly chosen endpoint. When a call fails with
+/// `EvmError::ConnectionError` or `EvmError::ProviderError`, that endpoint is marked
+/// unhealthy for the cooldown period and the call is retried on the next healthy endpoint
+/// after a random jitter delay. Other errors (reverts, bad input) are returned unchanged.
+///
+/// Limitation: services do not fail over transparently. They hold the single
+/// `Provider<Http>` of their `Evm`, and `EvmClient` fixes that provider's transport to
+/// `Http`, so a failover `JsonRpcClient` cannot be swapped in underneath them. Route calls
+/// that must survive an endpoint outage through `call`, or rebuild the `Evm` from
+/// `provider()` to move the services to a healthy endpoint.
+///
+/// # Example
+/// ```
+/// let failover = FailoverProvider::new(vec![
+///     "https://bsc-dataseed.binance.org".to_string(),
+///     "https://bsc-dataseed1.defibit.io".to_string(),
+/// ])?
+/// .with_cooldown(std::time::Duration::from_secs(60));
+/// async {
+/// let block = failover
+///     .call(|provider| async move {
+///         provider
+///             .get_block_number()
+///             .await
+///             .map_err(|e| EvmError::ConnectionError(format!("Failed to get block number: {}", e)))
+///     })
+///     .await?;
+/// Ok::<(), EvmError>(())
+/// };
+/// ```
+#[derive(Debug)]
+pub struct FailoverProvider {
+    endpoints: Vec<Endpoint>,
+    state: Mutex<FailoverState>,
+    cooldown: Duration,
+    jitter: Duration,
+}
+
+#[derive(Debug)]
+struct Endpoint {
+    url: String,
+    provider: Arc<Provider<Http>>,
+}
+
+#[derive(Debug)]
+struct FailoverState {
+    current: usize,
+    unhealthy_until: Vec<Option<Instant>>,
+}
+
+impl FailoverProvider {
+    /// Creates a failover provider over the given RPC URLs
+    pub fn new(urls: Vec<String>) -> PancakeResult<Self> {
+        if urls.is_empty() {
+            return Err(EvmError::ConfigError(
+                "At least one RPC endpoint is required".to_string(),
+            ));
+        }
+        let mut endpoints = Vec::with_capacity(urls.len());
+        for url in urls {
+            let provider = Provider::<Http>::try_from(url.as_str()).map_err(|e| {
+                EvmError::ConfigError(format!("Invalid RPC endpoint {}: {}", url, e))
+            })?;
+            endpoints.push(Endpoint {
+                url,
+                provider: Arc::new(provider),
+            });
+        }
+        let state = FailoverState {
+            current: (pseudo_random() % endpoints.len() as u64) as usize,
+            unhealthy_until: vec![None; endpoints.len()],
+        };
+        Ok(Self {
+            endpoints,
+            state: Mutex::new(state),
+            cooldown: DEFAULT_ENDPOINT_COOLDOWN,
+            jitter: DEFAULT_FAILOVER_JITTER,
+        })
+    }
+
+    /// Sets how long a failed endpoint is skipped
+    pub fn with_cooldown(mut self, cooldown: Duration) -> Self {
+        self.cooldown = cooldown;
+        self
+    }
+
+    /// Sets the upper bound of the random delay before a retry; zero retries immediately
+    pub fn with_jitter(mut self, jitter: Duration) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    /// Provider for the current healthy endpoint
+    pub fn provider(&self) -> Arc<Provider<Http>> {
+        let index = self.select_endpoint();
+        self.endpoints[index].provider.clone()
+    }
+
+    /// URL of the current healthy endpoint
+    pub fn current_url(&self) -> String {
+        let index = self.select_endpoint();
+        self.endpoints[index].url.clone()
+    }
+
+    /// Number of endpoints not currently in cooldown
+    pub fn healthy_count(&self) -> usize {
+        let state = self.state.lock().unwrap();
+        let now = Instant::now();
+        state
+            .unhealthy_until
+            .iter()
+            .filter(|until| until.is_none_or(|until| until <= now))
+            .count()
+    }
+
+    /// Runs `f` against the current endpoint, failing over to the others on connection and
+    /// provider errors
+    ///
+    /// Each endpoint is tried at most once per call; the last connection error is returned
+    /// when all of them fail.
+    pub async fn call<T, F, Fut>(&self, f: F) -> PancakeResult<T>
+    where
+        F: Fn(Arc<Provider<Http>>) -> Fut,
+        Fut: Future<Output = PancakeResult<T>>,
+    {
+        let mut last_error = None;
+        for attempt in 0..self.endpoints.len() {
+            if attempt > 0 && !self.jitter.is_zero() {
+                tokio::time::sleep(self.jitter_delay()).await;
+            }
+            let index = self.select_endpoint();
+            match f(self.endpoints[index].provider.clone()).await {
+                Ok(value) => return Ok(value),
+                Err(e @ (EvmError::ConnectionError(_) | EvmError::ProviderError(_))) => {
+                    log_warn!(
+                        endpoint = %self.endpoints[index].url,
+                        error = %e,
+                        "RPC endpoint failed, failing over"
+                    );
+                    self.mark_unhealthy(index);
+                    last_error = Some(e);
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        Err(last_error
+            .unwrap_or_else(|| EvmError::ConnectionError("No RPC endpoint available".to_string())))
+    }
+
+    /// Index of the first endpoint at or after the current one that is not in cooldown
+    ///
+    /// When every endpoint is in cooldown, the one that recovers soonest is used.
+    fn select_endpoint(&self) -> usize {
+        let mut state = self.state.lock().unwrap();
+        let now = Instant::now();
+        let count = self.endpoints.len();
+        let healthy = (0..count)
+            .map(|offset| (state.current + offset) % count)
+            .find(|index| state.unhealthy_until[*index].is_none_or(|until| until <= now));
+        let index = healthy.unwrap_or_else(|| {
+            (0..count)
+                .min_by_key(|index| state.unhealthy_until[*index])
+                .unwrap_or(state.current)
+        });
+        if state.unhealthy_until[index].is_some_and(|until| until <= now) {
+            state.unhealthy_until[index] = None;
+        }
+        state.current = index;
+        index
+    }
+
+    /// Puts an endpoint in cooldown and moves on to the next one
+    fn mark_unhealthy(&self, index: usize) {
+        let mut state = self.state.lock().unwrap();
+        state.unhealthy_until[index] = Some(Instant::now() + self.cooldown);
+        if state.current == index {
+            state.current = (index + 1) % self.endpoints.len();
+        }
+    }
+
+    fn jitter_delay(&self) -> Duration {
+        let max_nanos = self.jitter.as_nanos().min(u64::MAX as u128) as u64;
+        Duration::from_nanos(pseudo_random() % max_nanos.max(1))
+    }
+}
+
+/// Cheap randomness for endpoint selection and jitter; not suitable for anything else
+fn pseudo_random() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.subsec_nanos() as u64)
+        .unwrap_or(0)
+}
@@ -1,10 +1,18 @@
-use evm_sdk::types::EvmError;
-use crate::abi::{IMasterChefV2, IPancakePair, ISmartChefFactory, ISmartChefInitializable};
+use crate::abi::{
+    IMasterChefV2, IMasterChefV2Emissions, IPancakePair, ISmartChefFactory, ISmartChefInitializable,
+};
+use crate::logging::{log_error, log_warn};
+use crate::multicall::{Call, MulticallService};
+use crate::{EvmError, PancakeResult};
+use ethers::abi::AbiDecode;
 use ethers::middleware::SignerMiddleware;
 use ethers::types::{Address, U256};
 use evm_sdk::Evm;
 use std::sync::Arc;
 
+/// Calls per multicall request when scanning farm pools
+const FARM_MULTICALL_BATCH_SIZE: usize = 200;
+
 /// Farm pool information
 #[derive(Debug, Clone)]
 pub struct FarmInfo {
@@ -76,7 +84,7 @@ impl FarmingService {
     /// println!("Total pools: {}", pool_count);
     /// }
     /// ```
-    pub async fn pool_length(&self, master_chef_address: Address) -> Result<U256, EvmError> {
+    pub async fn pool_length(&self, master_chef_address: Address) -> PancakeResult<U256> {
         let master_chef = IMasterChefV2::new(master_chef_address, self.evm.client.provider.clone());
         master_chef
             .pool_length()
@@ -103,13 +111,15 @@ impl FarmingService {
     pub async fn get_all_farms(
         &self,
         master_chef_address: Address,
-    ) -> Result<Vec<FarmInfo>, EvmError> {
+    ) -> PancakeResult<Vec<FarmInfo>> {
         let pool_length = self.pool_length(master_chef_address).await?;
         let mut farms = Vec::new();
         for pid in 0..pool_length.as_u64() {
             match self.get_farm_info(master_chef_address, pid).await {
                 Ok(farm_info) => farms.push(farm_info),
-                Err(e) => eprintln!("Failed to get farm info for PID {}: {}", pid, e),
+                Err(e) => {
+                    log_warn!(pid = pid, error = %e, "Failed to get farm info")
+                }
             }
         }
         Ok(farms)
@@ -132,7 +142,11 @@ impl FarmingService {
         &self,
         master_chef_address: Address,
         pid: u64,
-    ) -> Result<FarmInfo, EvmError> {
+    ) -> PancakeResult<FarmInfo> {
+        if let Ok(farm_info) = self.get_farm_info_v2(master_chef_address, pid).await {
+            return Ok(farm_info);
+        }
+        // Legacy MasterChef without the regular/special split: every pool is regular
         let master_chef = IMasterChefV2::new(master_chef_address, self.evm.client.provider.clone());
         let pool_info = master_chef
             .pool_info(pid.into())
@@ -161,10 +175,91 @@ impl FarmingService {
             acc_cake_per_share: pool_info.3,
             total_lp,
             reward_per_block,
-            is_regular: pid < 100,
+            is_regular: true,
+        })
+    }
+
+    /// Reads a farm from MasterChef V2, whose emissions are split between regular and
+    /// special pools with separate alloc point totals
+    async fn get_farm_info_v2(
+        &self,
+        master_chef_address: Address,
+        pid: u64,
+    ) -> PancakeResult<FarmInfo> {
+        let master_chef =
+            IMasterChefV2Emissions::new(master_chef_address, self.evm.client.provider.clone());
+        let (acc_cake_per_share, last_reward_block, alloc_point, _, is_regular) = master_chef
+            .pool_info(pid.into())
+            .call()
+            .await
+            .map_err(|e| EvmError::ContractError(format!("Failed to get pool info: {}", e)))?;
+        let lp_token = master_chef
+            .lp_token(pid.into())
+            .call()
+            .await
+            .map_err(|e| EvmError::ContractError(format!("Failed to get lp token: {}", e)))?;
+        let total_alloc_point = if is_regular {
+            master_chef.total_regular_alloc_point().call().await
+        } else {
+            master_chef.total_special_alloc_point().call().await
+        }
+        .map_err(|e| EvmError::ContractError(format!("Failed to get total alloc point: {}", e)))?;
+        let cake_per_block = master_chef
+            .cake_per_block(is_regular)
+            .call()
+            .await
+            .map_err(|e| EvmError::ContractError(format!("Failed to get cake per block: {}", e)))?;
+        let reward_per_block = if total_alloc_point.is_zero() {
+            U256::zero()
+        } else {
+            cake_per_block * alloc_point / total_alloc_point
+        };
+        let lp = IPancakePair::new(lp_token, self.evm.client.provider.clone());
+        let total_lp = lp.total_supply().call().await.unwrap_or(U256::zero());
+        Ok(FarmInfo {
+            pid,
+            lp_token,
+            alloc_point,
+            last_reward_block,
+            acc_cake_per_share,
+            total_lp,
+            reward_per_block,
+            is_regular,
         })
     }
 
+    /// Gets the share of CAKE emissions going to regular farms
+    ///
+    /// The rate is scaled by 1e12 (`CAKE_RATE_TOTAL_PRECISION`), so `62.5%` reads as
+    /// `625_000_000_000`.
+    ///
+    /// # Example
+    /// ```rust
+    /// use ethers::types::Address;
+    /// use std::sync::Arc;
+    /// use crate::FarmingService;
+    /// use crate::tool::math_utils::u256_to_f64;
+    /// async fn example(service: Arc<FarmingService>) {
+    /// let master_chef = Address::zero(); // Replace with actual address
+    /// let rate = service.get_cake_rate_to_regular(master_chef).await.unwrap();
+    /// println!("Regular farm share: {}%", u256_to_f64(rate, 10));
+    /// }
+    /// ```
+    pub async fn get_cake_rate_to_regular(
+        &self,
+        master_chef_address: Address,
+    ) -> PancakeResult<U256> {
+        let master_chef =
+            IMasterChefV2Emissions::new(master_chef_address, self.evm.client.provider.clone());
+        master_chef
+            .cake_rate_to_regular_farm()
+            .call()
+            .await
+            .map_err(|e| {
+                EvmError::ContractError(format!("Failed to get cake rate to regular: {}", e))
+            })
+    }
+
     /// Gets user-specific information for a farm pool
     ///
     /// # Example
@@ -185,7 +280,7 @@ impl FarmingService {
         master_chef_address: Address,
         pid: u64,
         user_address: Address,
-    ) -> Result<UserFarmInfo, EvmError> {
+    ) -> PancakeResult<UserFarmInfo> {
         let master_chef = IMasterChefV2::new(master_chef_address, self.evm.client.provider.clone());
         let user_info = master_chef
             .user_info(pid.into(), user_address)
@@ -219,8 +314,144 @@ impl FarmingService {
         })
     }
 
+    /// Lists every farm pool where `user_address` has LP tokens staked
+    ///
+    /// `userInfo` is batched over all pids through multicall, and pending CAKE, LP token and
+    /// wallet LP balance are then read only for the pools with a nonzero stake.
+    ///
+    /// # Example
+    /// ```rust
+    /// use ethers::types::Address;
+    /// use std::sync::Arc;
+    /// use crate::FarmingService;
+    /// async fn example(service: Arc<FarmingService>, multicall: Address) {
+    /// let master_chef = Address::zero(); // Replace with actual address
+    /// let user = Address::zero(); // Replace with user address
+    /// let farms = service.get_user_active_farms(master_chef, multicall, user).await.unwrap();
+    /// for farm in farms {
+    ///     println!("pid {}: staked {}, pending {}", farm.pid, farm.amount, farm.pending_rewards);
+    /// }
+    /// }
+    /// ```
+    pub async fn get_user_active_farms(
+        &self,
+        master_chef_address: Address,
+        multicall_address: Address,
+        user_address: Address,
+    ) -> PancakeResult<Vec<UserFarmInfo>> {
+        let pool_length = self.pool_length(master_chef_address).await?.as_u64();
+        let master_chef = IMasterChefV2::new(master_chef_address, self.evm.client.provider.clone());
+        let emissions =
+            IMasterChefV2Emissions::new(master_chef_address, self.evm.client.provider.clone());
+        let multicall_service = MulticallService::new(self.evm.clone());
+
+        let pids: Vec<u64> = (0..pool_length).collect();
+        let mut staked = Vec::new();
+        for batch in pids.chunks(FARM_MULTICALL_BATCH_SIZE) {
+            let mut calls = Vec::with_capacity(batch.len());
+            for pid in batch {
+                let call_data = master_chef
+                    .user_info((*pid).into(), user_address)
+                    .calldata()
+                    .ok_or_else(|| {
+                        EvmError::ContractError("Failed to encode userInfo call".to_string())
+                    })?;
+                calls.push(Call::new(master_chef_address, call_data.to_vec()));
+            }
+            let results = multicall_service
+                .aggregate(multicall_address, calls)
+                .await?;
+            for (pid, result) in batch.iter().zip(results) {
+                let (amount, reward_debt) = <(U256, U256)>::decode(&result.data).map_err(|e| {
+                    EvmError::ContractError(format!("Failed to decode user info: {}", e))
+                })?;
+                if !amount.is_zero() {
+                    staked.push((*pid, amount, reward_debt));
+                }
+            }
+        }
+        if staked.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut farms = Vec::with_capacity(staked.len());
+        let mut lp_tokens = Vec::with_capacity(staked.len());
+        for batch in staked.chunks(FARM_MULTICALL_BATCH_SIZE / 2) {
+            let mut calls = Vec::with_capacity(batch.len() * 2);
+            for (pid, _, _) in batch {
+                let pending_call = master_chef.pending_cake((*pid).into(), user_address);
+                let lp_token_call = emissions.lp_token((*pid).into());
+                for call_data in [pending_call.calldata(), lp_token_call.calldata()] {
+                    let call_data = call_data.ok_or_else(|| {
+                        EvmError::ContractError("Failed to encode farm call".to_string())
+                    })?;
+                    calls.push(Call::new(master_chef_address, call_data.to_vec()));
+                }
+            }
+            let results = multicall_service
+                .aggregate(multicall_address, calls)
+                .await?;
+            for ((pid, amount, reward_debt), pool_results) in batch.iter().zip(results.chunks(2)) {
+                let pending_rewards = U256::decode(&pool_results[0].data).map_err(|e| {
+                    EvmError::ContractError(format!("Failed to decode pending rewards: {}", e))
+                })?;
+                let lp_token = Address::decode(&pool_results[1].data).map_err(|e| {
+                    EvmError::ContractError(format!("Failed to decode LP token: {}", e))
+                })?;
+                lp_tokens.push(lp_token);
+                farms.push(UserFarmInfo {
+                    pid: *pid,
+                    amount: *amount,
+                    reward_debt: *reward_debt,
+                    pending_rewards,
+                    lp_balance: U256::zero(),
+                });
+            }
+        }
+
+        let mut unique_lp_tokens = lp_tokens.clone();
+        unique_lp_tokens.sort();
+        unique_lp_tokens.dedup();
+        let lp_balances = multicall_service
+            .get_token_balances(multicall_address, unique_lp_tokens, user_address)
+            .await?;
+        for (farm, lp_token) in farms.iter_mut().zip(&lp_tokens) {
+            farm.lp_balance = lp_balances.get(lp_token).copied().unwrap_or_default();
+        }
+        Ok(farms)
+    }
+
+    /// Fetches syrup pool info for a caller-supplied list of SmartChef addresses concurrently
+    ///
+    /// Skips pool discovery entirely, so this is the reliable way to load syrup pools until
+    /// factory enumeration in `get_all_syrup_pools` is implemented. Pools that fail to load
+    /// are logged and left out of the result.
+    ///
+    /// # Example
+    /// ```rust
+    /// use ethers::types::Address;
+    /// use std::sync::Arc;
+    /// use crate::FarmingService;
+    /// async fn example(service: Arc<FarmingService>) {
+    /// let addresses = vec!["0x...".parse::<Address>().unwrap(), "0x...".parse().unwrap()];
+    /// let pools = service.get_syrup_pools_from_addresses(addresses).await;
+    /// for pool in pools {
+    ///     println!("Pool: {:?}, Reward: {} per second", pool.pool_address, pool.reward_per_second);
+    /// }
+    /// }
+    /// ```
+    pub async fn get_syrup_pools_from_addresses(
+        &self,
+        addresses: Vec<Address>,
+    ) -> Vec<SyrupPoolInfo> {
+        self.get_pools_info(addresses).await
+    }
+
     // Retrieves all syrup pools using multiple strategies
     ///
+    /// Discovery often finds nothing; prefer `get_syrup_pools_from_addresses` when the pool
+    /// addresses are known.
+    ///
     /// # Example
     /// ```rust
     /// use ethers::types::Address;
@@ -237,7 +468,7 @@ impl FarmingService {
     pub async fn get_all_syrup_pools(
         &self,
         smart_chef_factory_address: Address,
-    ) -> Result<Vec<SyrupPoolInfo>, EvmError> {
+    ) -> PancakeResult<Vec<SyrupPoolInfo>> {
         let factory =
             ISmartChefFactory::new(smart_chef_factory_address, self.evm.client.provider.clone());
         // Strategy 1: Try to get the pool list through the factory contract method
@@ -259,14 +490,14 @@ impl FarmingService {
             }
         }
         // All strategies fail, returning an empty vector but logging a warning
-        eprintln!("Warning: All strategies failed to get syrup pools, returning empty list");
+        log_warn!("All strategies failed to get syrup pools, returning empty list");
         Ok(Vec::new())
     }
 
     async fn get_pools_via_factory_methods(
         &self,
         factory: &ISmartChefFactory<ethers::providers::Provider<ethers::providers::Http>>,
-    ) -> Result<Vec<Address>, EvmError> {
+    ) -> PancakeResult<Vec<Address>> {
         let mut pools = Vec::new();
         let method_names = [
             "getPools",
@@ -301,7 +532,7 @@ impl FarmingService {
         &self,
         factory: &ISmartChefFactory<ethers::providers::Provider<ethers::providers::Http>>,
         method: &str,
-    ) -> Result<Vec<Address>, EvmError> {
+    ) -> PancakeResult<Vec<Address>> {
         match method {
             "getPools" => Ok(Vec::new()),
             "pools" => Ok(Vec::new()),
@@ -312,7 +543,7 @@ impl FarmingService {
     async fn get_pool_count_via_factory(
         &self,
         factory: &ISmartChefFactory<ethers::providers::Provider<ethers::providers::Http>>,
-    ) -> Result<u64, EvmError> {
+    ) -> PancakeResult<u64> {
         let count_methods = ["poolCount", "totalPools", "poolLength", "getPoolCount"];
         for method in count_methods {
             if method == "poolCount" {
@@ -331,7 +562,7 @@ impl FarmingService {
         &self,
         factory: &ISmartChefFactory<ethers::providers::Provider<ethers::providers::Http>>,
         index: u64,
-    ) -> Result<Address, EvmError> {
+    ) -> PancakeResult<Address> {
         let index_u256 = U256::from(index);
         if let Ok(address) = factory.get_pool(index_u256).call().await {
             if address != Address::zero() {
@@ -374,10 +605,7 @@ impl FarmingService {
         )))
     }
 
-    async fn get_pools_via_events(
-        &self,
-        factory_address: Address,
-    ) -> Result<Vec<Address>, EvmError> {
+    async fn get_pools_via_events(&self, factory_address: Address) -> PancakeResult<Vec<Address>> {
         use ethers::providers::Middleware;
         use ethers::types::{BlockNumber, Filter, H256};
         use ethers::utils::keccak256;
@@ -419,7 +647,7 @@ impl FarmingService {
                     }
                 }
                 Err(e) => {
-                    eprintln!("Failed to get logs for event {:?}: {}", event_hash, e);
+                    log_warn!(event = ?event_hash, error = %e, "Failed to get logs");
                     continue;
                 }
             }
@@ -473,7 +701,7 @@ impl FarmingService {
         None
     }
 
-    async fn get_pools_via_known_list(&self) -> Result<Vec<Address>, EvmError> {
+    async fn get_pools_via_known_list(&self) -> PancakeResult<Vec<Address>> {
         let known_pools: Vec<Address> = std::env::var("KNOWN_SYRUP_POOLS")
             .ok()
             .and_then(|s| s.split(',').map(|addr| addr.trim().parse().ok()).collect())
@@ -501,8 +729,12 @@ impl FarmingService {
         for (pool_address, task) in tasks {
             match task.await {
                 Ok(Ok(pool_info)) => syrup_pools.push(pool_info),
-                Ok(Err(e)) => eprintln!("Failed to get pool info for {}: {}", pool_address, e),
-                Err(e) => eprintln!("Task failed for {}: {}", pool_address, e),
+                Ok(Err(e)) => {
+                    log_warn!(pool = ?pool_address, error = %e, "Failed to get pool info")
+                }
+                Err(e) => {
+                    log_error!(pool = ?pool_address, error = %e, "Pool info task failed")
+                }
             }
         }
         syrup_pools
@@ -522,10 +754,7 @@ impl FarmingService {
     /// println!("Total staked: {}", pool_info.total_staked);
     /// }
     /// ```
-    pub async fn get_syrup_pool_info(
-        &self,
-        pool_address: Address,
-    ) -> Result<SyrupPoolInfo, EvmError> {
+    pub async fn get_syrup_pool_info(&self, pool_address: Address) -> PancakeResult<SyrupPoolInfo> {
         let pool = ISmartChefInitializable::new(pool_address, self.evm.client.provider.clone());
         let staked_token =
             pool.staked_token().call().await.map_err(|e| {
@@ -616,7 +845,7 @@ impl FarmingService {
         &self,
         pool_address: Address,
         user_address: Address,
-    ) -> Result<UserSyrupPoolInfo, EvmError> {
+    ) -> PancakeResult<UserSyrupPoolInfo> {
         let pool = ISmartChefInitializable::new(pool_address, self.evm.client.provider.clone());
         let user_info = pool
             .user_info(user_address)
@@ -636,6 +865,45 @@ impl FarmingService {
         })
     }
 
+    /// Projects the rewards a user will earn in a syrup pool over the next `seconds_ahead`
+    ///
+    /// Uses the pool's `reward_per_second` and the user's share of `total_staked`; the window
+    /// starts now (or at `start_timestamp` if later) and is clamped at `end_timestamp`.
+    /// Rewards already pending are not included.
+    ///
+    /// # Example
+    /// ```rust
+    /// use ethers::types::Address;
+    /// use std::sync::Arc;
+    /// use crate::FarmingService;
+    /// async fn example(service: Arc<FarmingService>) {
+    /// let pool_address = Address::zero(); // Replace with pool address
+    /// let user_address = Address::zero(); // Replace with user address
+    /// let pool = service.get_syrup_pool_info(pool_address).await.unwrap();
+    /// let user = service.get_user_syrup_pool_info(pool_address, user_address).await.unwrap();
+    /// let next_day = service.project_syrup_rewards(&pool, &user, 86400);
+    /// println!("Rewards in the next 24h: {}", next_day);
+    /// }
+    /// ```
+    pub fn project_syrup_rewards(
+        &self,
+        pool: &SyrupPoolInfo,
+        user: &UserSyrupPoolInfo,
+        seconds_ahead: u64,
+    ) -> U256 {
+        if pool.total_staked.is_zero() || user.staked_amount.is_zero() {
+            return U256::zero();
+        }
+        let now = crate::tool::time_utils::current_timestamp();
+        let window_start = now.max(pool.start_timestamp);
+        let window_end = now.saturating_add(seconds_ahead).min(pool.end_timestamp);
+        if window_end <= window_start {
+            return U256::zero();
+        }
+        let pool_rewards = pool.reward_per_second * U256::from(window_end - window_start);
+        pool_rewards * user.staked_amount / pool.total_staked
+    }
+
     /// Deposits tokens into a farm pool
     ///
     /// # Example
@@ -655,7 +923,7 @@ impl FarmingService {
         master_chef_address: Address,
         pid: u64,
         amount: U256,
-    ) -> Result<ethers::types::H256, EvmError> {
+    ) -> PancakeResult<ethers::types::H256> {
         let wallet = self
             .evm
             .client
@@ -693,7 +961,7 @@ impl FarmingService {
         master_chef_address: Address,
         pid: u64,
         amount: U256,
-    ) -> Result<ethers::types::H256, EvmError> {
+    ) -> PancakeResult<ethers::types::H256> {
         let wallet = self
             .evm
             .client
@@ -727,7 +995,7 @@ impl FarmingService {
         &self,
         master_chef_address: Address,
         pid: u64,
-    ) -> Result<ethers::types::H256, EvmError> {
+    ) -> PancakeResult<ethers::types::H256> {
         let wallet = self
             .evm
             .client
@@ -16,6 +16,13 @@ pub const ETHEREUM_ROUTER_V2: &str = "0xEfF92A263d31888d860bD50809A8D171709b7b1c
 // Base V2
 pub const BASE_FACTORY_V2: &str = "0x02a84c1b3BBD7401a5f7fa98a384EBC70bB5749E";
 pub const BASE_ROUTER_V2: &str = "0x1b02dA8Cb0d097eB8D57A175b88c7D8b47997506";
+// V2 pair init code hashes, for computing pair addresses with CREATE2
+pub const BSC_INIT_CODE_HASH_V2: &str =
+    "0x00fb7f630766e6a796048ea87d01acd3068e8ff67d078148a3fa3f4a84f69bd5";
+pub const ETHEREUM_INIT_CODE_HASH_V2: &str =
+    "0x57224589c67f3f30a6b0d7a1b54cf3153ab84563bc609ef41dfb34f8b2974d2d";
+pub const BASE_INIT_CODE_HASH_V2: &str =
+    "0x57224589c67f3f30a6b0d7a1b54cf3153ab84563bc609ef41dfb34f8b2974d2d";
 // Base V3
 pub const BASE_FACTORY_V3: &str = "0x0BFbCF9fa4f9C56B0F40a671Ad40E0805A091865";
 pub const BASE_ROUTER_V3: &str = "0x13f4EA83D0bd40E75C8222255bc855a974568Dd4";
@@ -31,6 +38,28 @@ pub const BSC_QUOTER: &str = "0xB048Bbc1Ee6b733FFfCFb9e9CeF7375518e25997";
 pub const ETHEREUM_QUOTER: &str = "0xb27308f9F90D607463bb33eA1BeBb41C27CE5AB6";
 // Base Quoter
 pub const BASE_QUOTER: &str = "0x672b7Be0bC5334b342F306Aaa6D812E6f39c353B";
+// Arbitrum One Quoter
+pub const ARBITRUM_QUOTER: &str = "0xB048Bbc1Ee6b733FFfCFb9e9CeF7375518e25997";
+// CAKE token
+pub const BSC_CAKE: &str = "0x0E09FaBB73Bd3Ade0a17ECC321fD13a19e81cE82";
+pub const ETHEREUM_CAKE: &str = "0x152649eA73beAb28c5b49B26eb48f7EAD6d4c898";
+pub const BASE_CAKE: &str = "0x3055913c90Fcc1A6CE9a358911721eEb942013A1";
+// Wrapped native token
+pub const BSC_WBNB: &str = "0xbb4CdB9CBd36B01bD1cBaEBF2De08d9173bc095c";
+pub const ETHEREUM_WETH: &str = "0xC02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2";
+pub const BASE_WETH: &str = "0x4200000000000000000000000000000000000006";
+pub const ARBITRUM_WETH: &str = "0x82aF49447D8a07e3bd95BD0d56f35241523fBab1";
+pub const POLYGON_WMATIC: &str = "0x0d500B1d8E8eF31E21C99d1Db9A6444d3ADf1270";
+// Universal Router (same address on BSC, Ethereum and Base)
+pub const UNIVERSAL_ROUTER: &str = "0x1A0A18AC4BECDDbd6389559687d1A73d8927E416";
+// PancakeSwap Permit2 (same address on BSC, Ethereum, Arbitrum and Base)
+pub const PANCAKE_PERMIT2: &str = "0x31c2F6fcFf4F8759b3Bd5Bf0e1084A055615c768";
+// V3 tick range
+pub const MIN_TICK: i32 = -887272;
+pub const MAX_TICK: i32 = 887272;
+// Typical gas used by single-hop swaps, used when a live estimate is unavailable
+pub const V2_SWAP_GAS_ESTIMATE: u64 = 150_000;
+pub const V3_SWAP_GAS_ESTIMATE: u64 = 180_000;
 pub const BSC_STABLE_SWAP_FACTORY: &str = "0x36bBb66e7E7Ef21b42608C17Ef7D68A6c6dFB3b7";
 pub const BSC_STABLE_SWAP_ROUTER: &str = "0x1698a2220f472A2d18e8D0f268F8e277B21c8F68";
 pub const BSC_MASTERCHEF_V2: &str = "0xa5f8C5Dbd5F286960b9d90548680aE5ebFf07652";
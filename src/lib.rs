@@ -1,42 +1,69 @@
 /// This module is the pancakeswap service entry module.
 pub mod abi;
 pub mod analytics;
+pub mod block_cache;
+pub mod call_observer;
+pub mod chain_reader;
 pub mod events;
 pub mod factory;
+pub mod failover;
 pub mod farm;
 pub mod global;
 pub mod limit_order;
 pub mod liquidity;
 pub mod multicall;
 pub mod price;
+pub mod rate_limit;
 pub mod router;
+pub mod stable_swap;
+pub mod token;
 pub mod tool;
+pub mod transaction_sender;
 pub mod types;
+pub mod universal_router;
 pub mod v3_position;
+pub mod v3_route;
+
+mod logging;
 
 use ethers::{
-    providers::{Http, Provider},
-    signers::{LocalWallet, Signer},
-    types::{Address, U256},
+    abi::Detokenize,
+    contract::ContractCall,
+    providers::Middleware,
+    signers::Signer,
+    types::{Address, BlockNumber, Bytes, U256},
 };
 use evm_client::EvmType;
 use evm_sdk::Evm;
+use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::Instant;
 
 use crate::{
-    abi::IQuoter,
-    analytics::AnalyticsService,
+    abi::{ExactInputParams, ExactInputSingleParams, IERC20Permit, IPancakeSmartRouter, IQuoter},
+    analytics::{AnalyticsService, stablecoin_addresses},
+    call_observer::CallObserver,
     factory::FactoryService,
     global::{
-        BASE_QUOTER, BASE_ROUTER_V3, BSC_QUOTER, BSC_ROUTER_V2, BSC_ROUTER_V3, ETHEREUM_QUOTER,
-        ETHEREUM_ROUTER_V2, ETHEREUM_ROUTER_V3,
+        ARBITRUM_QUOTER, ARBITRUM_WETH, BASE_CAKE, BASE_QUOTER, BASE_ROUTER_V3, BASE_WETH,
+        BSC_CAKE, BSC_QUOTER, BSC_ROUTER_V2, BSC_ROUTER_V3, BSC_STABLE_SWAP_FACTORY, BSC_WBNB,
+        ETHEREUM_CAKE, ETHEREUM_QUOTER, ETHEREUM_ROUTER_V2, ETHEREUM_ROUTER_V3, ETHEREUM_WETH,
+        POLYGON_WMATIC, V2_SWAP_GAS_ESTIMATE, V3_SWAP_GAS_ESTIMATE,
     },
     liquidity::LiquidityService,
     price::PriceService,
+    rate_limit::RateLimiter,
     router::RouterService,
-    types::PriceInfo,
+    stable_swap::StableSwapService,
+    tool::math_utils::u256_to_f64,
+    transaction_sender::TransactionSender,
+    types::{FeeTier, PoolVersion, PriceInfo, RouteInfo, TradePreview},
 };
-use evm_sdk::types::EvmError;
+pub use evm_sdk::types::EvmError;
+
+/// Result type returned by the SDK's fallible methods
+pub type PancakeResult<T> = Result<T, EvmError>;
+
 /// PancakeSwap Service for interacting with PancakeSwap protocols
 pub struct PancakeSwapService {
     evm: Arc<Evm>,
@@ -45,6 +72,18 @@ pub struct PancakeSwapService {
     liquidity: Arc<LiquidityService>,
     price: Arc<PriceService>,
     analytics: Arc<AnalyticsService>,
+    stable_swap: Arc<StableSwapService>,
+    validate_tokens: bool,
+    preflight_tokens: bool,
+    chain_deadlines: bool,
+    decimals_overrides: HashMap<Address, u8>,
+    weth_override: Option<Address>,
+    gas_limit_multiplier: f64,
+    /// `(window_secs, max_deviation_percent)` of the V3 spot-vs-TWAP check
+    twap_guard: Option<(u32, f64)>,
+    transaction_sender: Option<Arc<dyn TransactionSender>>,
+    call_observer: Option<Arc<dyn CallObserver>>,
+    rate_limiter: Option<Arc<RateLimiter>>,
 }
 
 impl PancakeSwapService {
@@ -57,11 +96,306 @@ impl PancakeSwapService {
             liquidity: Arc::new(LiquidityService::new(evm.clone())),
             price: Arc::new(PriceService::new(evm.clone())),
             analytics: Arc::new(AnalyticsService::new(evm.clone())),
+            stable_swap: Arc::new(StableSwapService::new(evm.clone())),
+            validate_tokens: false,
+            preflight_tokens: false,
+            chain_deadlines: false,
+            decimals_overrides: HashMap::new(),
+            weth_override: None,
+            gas_limit_multiplier: 1.0,
+            twap_guard: None,
+            transaction_sender: None,
+            call_observer: None,
+            rate_limiter: None,
+        }
+    }
+
+    /// Limit outgoing RPC requests to `requests_per_second`; `0` means unlimited (the default)
+    ///
+    /// The limiter is shared with the inner factory, liquidity, price and analytics services,
+    /// so their calls draw from the same budget.
+    ///
+    /// # Example
+    /// ```
+    /// let service = PancakeSwapService::new(std::sync::Arc::new(client)).with_rate_limit(10);
+    /// ```
+    pub fn with_rate_limit(mut self, requests_per_second: u32) -> Self {
+        self.rate_limiter = if requests_per_second == 0 {
+            None
+        } else {
+            Some(Arc::new(RateLimiter::new(requests_per_second)))
+        };
+        self.rebuild_services();
+        self
+    }
+
+    /// Verify that token addresses are contracts before sending swaps
+    ///
+    /// Swapping against an EOA fails with confusing router errors; with validation
+    /// enabled such calls return `EvmError::InvalidInput` up front instead.
+    ///
+    /// # Example
+    /// ```
+    /// let service = PancakeSwapService::new(std::sync::Arc::new(client)).with_token_validation(true);
+    /// ```
+    pub fn with_token_validation(mut self, enabled: bool) -> Self {
+        self.validate_tokens = enabled;
+        self
+    }
+
+    /// Run `preflight_token` on the input token before sending swaps
+    ///
+    /// With the guard enabled, swaps of tokens whose transfers appear blocked for the
+    /// wallet (paused tokens, blacklisted holders) fail with `EvmError::InvalidInput`.
+    ///
+    /// # Example
+    /// ```
+    /// let service = PancakeSwapService::new(std::sync::Arc::new(client)).with_token_preflight(true);
+    /// ```
+    pub fn with_token_preflight(mut self, enabled: bool) -> Self {
+        self.preflight_tokens = enabled;
+        self
+    }
+
+    /// Base swap deadlines on the latest block timestamp instead of the system clock
+    ///
+    /// Use this when the host clock drifts from chain time; otherwise swaps can revert with
+    /// an expired deadline before the window has really passed. Costs one extra RPC call
+    /// per swap.
+    ///
+    /// # Example
+    /// ```
+    /// let service = PancakeSwapService::new(std::sync::Arc::new(client)).with_chain_deadlines(true);
+    /// ```
+    pub fn with_chain_deadlines(mut self, enabled: bool) -> Self {
+        self.chain_deadlines = enabled;
+        self
+    }
+
+    /// Use fixed decimals for the given tokens instead of calling `decimals()`
+    ///
+    /// For proxy or rebasing tokens that report misleading decimals or revert on the call.
+    /// The overrides also apply to the service's price and analytics helpers.
+    ///
+    /// # Example
+    /// ```
+    /// use std::collections::HashMap;
+    ///
+    /// let overrides = HashMap::from([(weird_token, 9u8)]);
+    /// let service = PancakeSwapService::new(std::sync::Arc::new(client))
+    ///     .with_decimals_overrides(overrides);
+    /// ```
+    pub fn with_decimals_overrides(mut self, overrides: HashMap<Address, u8>) -> Self {
+        self.decimals_overrides = overrides;
+        self.rebuild_services();
+        self
+    }
+
+    /// Recreate the inner services so they pick up the current decimals overrides and share
+    /// the rate limiter
+    fn rebuild_services(&mut self) {
+        let mut factory = FactoryService::new(self.evm.clone());
+        let mut liquidity = LiquidityService::new(self.evm.clone());
+        let mut price = PriceService::new(self.evm.clone())
+            .with_decimals_overrides(self.decimals_overrides.clone());
+        let mut analytics = AnalyticsService::new(self.evm.clone())
+            .with_decimals_overrides(self.decimals_overrides.clone());
+        if let Some(rate_limiter) = &self.rate_limiter {
+            factory = factory.with_rate_limiter(rate_limiter.clone());
+            liquidity = liquidity.with_rate_limiter(rate_limiter.clone());
+            price = price.with_rate_limiter(rate_limiter.clone());
+            analytics = analytics.with_rate_limiter(rate_limiter.clone());
+        }
+        let price = Arc::new(price);
+        self.analytics = Arc::new(analytics.with_price_source(price.clone()));
+        self.factory = Arc::new(factory);
+        self.liquidity = Arc::new(liquidity);
+        self.price = price;
+    }
+
+    /// Use `weth` as the wrapped native token instead of the chain default
+    ///
+    /// Native-swap helpers otherwise use `PancakeSwapConfig::weth_address`, or ask the V2
+    /// router's `WETH()` on chains without a known address. Set this for custom deployments.
+    ///
+    /// # Example
+    /// ```
+    /// let service = PancakeSwapService::new(std::sync::Arc::new(client))
+    ///     .with_weth_override(custom_wrapped_native);
+    /// ```
+    pub fn with_weth_override(mut self, weth: Address) -> Self {
+        self.weth_override = Some(weth);
+        self
+    }
+
+    /// Multiply the estimated gas of swap transactions by `multiplier` before sending
+    ///
+    /// Estimates can fall short for fee-on-transfer tokens and multi-hop routes, which then
+    /// revert out of gas; a multiplier such as `1.2` adds a safety margin. The default `1.0`
+    /// leaves gas estimation to the provider; values below `1.0` are treated as `1.0`.
+    ///
+    /// # Example
+    /// ```
+    /// let service = PancakeSwapService::new(std::sync::Arc::new(client))
+    ///     .with_gas_limit_multiplier(1.2);
+    /// ```
+    pub fn with_gas_limit_multiplier(mut self, multiplier: f64) -> Self {
+        self.gas_limit_multiplier = multiplier.max(1.0);
+        self
+    }
+
+    /// Abort V3 swaps when the pool's spot price deviates from its TWAP by more than
+    /// `max_deviation_percent`
+    ///
+    /// Before sending, `swap_v3` and `swap_v3_with_price_limit` compare the pool's spot price
+    /// with its time-weighted average over the last `window_secs` seconds and fail with
+    /// `EvmError::CalculationError` when the price looks manipulated. Pools whose oracle
+    /// history is shorter than the window fail the check as well.
+    ///
+    /// # Example
+    /// ```
+    /// // Refuse to swap when spot is more than 2% off the 10 minute TWAP
+    /// let service = PancakeSwapService::new(std::sync::Arc::new(client))
+    ///     .with_twap_guard(600, 2.0);
+    /// ```
+    pub fn with_twap_guard(mut self, window_secs: u32, max_deviation_percent: f64) -> Self {
+        self.twap_guard = Some((window_secs, max_deviation_percent));
+        self
+    }
+
+    /// Send swap transactions through `sender` instead of the wallet's signer
+    ///
+    /// Use this to relay swaps through a private mempool or a bundler; see
+    /// `TransactionSender`. Quotes and other reads still go through the provider.
+    ///
+    /// # Example
+    /// ```
+    /// let private_rpc = Provider::<Http>::try_from("https://rpc.flashbots.net")?;
+    /// let sender = SignerMiddleware::new(private_rpc, wallet.clone());
+    /// let service = PancakeSwapService::new(std::sync::Arc::new(client))
+    ///     .with_transaction_sender(std::sync::Arc::new(sender));
+    /// ```
+    pub fn with_transaction_sender(mut self, sender: Arc<dyn TransactionSender>) -> Self {
+        self.transaction_sender = Some(sender);
+        self
+    }
+
+    /// Report every RPC call the service makes to `observer`
+    ///
+    /// Covers the calls issued by `PancakeSwapService` itself (quotes, gas estimates, sends);
+    /// see `CallObserver`.
+    ///
+    /// # Example
+    /// ```
+    /// let service = PancakeSwapService::new(std::sync::Arc::new(client))
+    ///     .with_call_observer(std::sync::Arc::new(LatencyLogger));
+    /// ```
+    pub fn with_call_observer(mut self, observer: Arc<dyn CallObserver>) -> Self {
+        self.call_observer = Some(observer);
+        self
+    }
+
+    /// Check that the configured wallet signs for the same chain as the provider
+    ///
+    /// A wallet built for another chain id produces signatures the node rejects with opaque
+    /// errors; this fails early with `EvmError::WalletError("wallet chain id mismatch")`.
+    /// Services without a wallet pass unchanged.
+    ///
+    /// # Example
+    /// ```
+    /// let service = PancakeSwapService::new(std::sync::Arc::new(client))
+    ///     .with_wallet_chain_check()
+    ///     .await?;
+    /// ```
+    pub async fn with_wallet_chain_check(self) -> PancakeResult<Self> {
+        if let Some(wallet) = &self.evm.client.wallet {
+            let provider_chain_id = self
+                .observe("eth_chainId", self.evm.client.provider.get_chainid())
+                .await
+                .map_err(|e| EvmError::ProviderError(format!("Failed to get chain id: {}", e)))?;
+            if provider_chain_id != U256::from(wallet.chain_id()) {
+                return Err(EvmError::WalletError(
+                    "wallet chain id mismatch".to_string(),
+                ));
+            }
+        }
+        Ok(self)
+    }
+
+    /// Calculate a deadline `minutes` after the latest block's timestamp
+    ///
+    /// Unlike `time_utils::calculate_deadline`, this is immune to drift between the host
+    /// clock and chain time.
+    ///
+    /// # Example
+    /// ```
+    /// let deadline = service.calculate_deadline_from_chain(30).await?;
+    /// ```
+    pub async fn calculate_deadline_from_chain(&self, minutes: u64) -> PancakeResult<u64> {
+        let block = self
+            .observe(
+                "eth_getBlockByNumber",
+                self.evm.client.provider.get_block(BlockNumber::Latest),
+            )
+            .await
+            .map_err(|e| EvmError::ConnectionError(format!("Failed to get latest block: {}", e)))?
+            .ok_or_else(|| EvmError::ConnectionError("Latest block not found".to_string()))?;
+        Ok(block.timestamp.as_u64() + minutes * 60)
+    }
+
+    /// Get a token's decimals, preferring the configured overrides over the chain
+    ///
+    /// # Example
+    /// ```
+    /// let decimals = service.get_token_decimals(busd).await?;
+    /// ```
+    pub async fn get_token_decimals(&self, token: Address) -> PancakeResult<u8> {
+        if let Some(decimals) = self.decimals_overrides.get(&token) {
+            return Ok(*decimals);
         }
+        self.throttle().await;
+        let erc20 = crate::abi::IERC20::new(token, self.evm.client.provider.clone());
+        self.observe("decimals", erc20.decimals().call())
+            .await
+            .map_err(|e| EvmError::ContractError(format!("Failed to get decimals: {}", e)))
+    }
+
+    /// Get the USD price of one CAKE on the connected chain
+    ///
+    /// Quotes CAKE against the chain's USD stablecoin (USDT, or USDC on Base) through
+    /// `PriceService` on the V2 router.
+    ///
+    /// # Example
+    /// ```
+    /// let cake_price = service.cake_price_usd().await?;
+    /// println!("CAKE: ${:.4}", cake_price);
+    /// ```
+    pub async fn cake_price_usd(&self) -> PancakeResult<f64> {
+        let chain = self
+            .evm
+            .client
+            .evm_type
+            .ok_or_else(|| EvmError::ConfigError("Unsupported chain".to_string()))?;
+        let cake = PancakeSwapConfig::cake_address(chain)?;
+        let usd = PancakeSwapConfig::usd_stablecoin_address(chain)?;
+        let router_address = PancakeSwapConfig::v2_router_address(chain)?;
+        // CAKE has 18 decimals everywhere; the stablecoin may not
+        let amount_out = self
+            .observe(
+                "getAmountsOut",
+                self.price
+                    .get_price(router_address, cake, usd, U256::exp10(18)),
+            )
+            .await?;
+        let usd_decimals = self.get_token_decimals(usd).await?;
+        Ok(u256_to_f64(amount_out, usd_decimals))
     }
 
     /// Get amounts out for a swap (V2)
     ///
+    /// Pass `block` to quote against the state of that block instead of the latest one, so
+    /// every quote of a scan sees the same reserves.
+    ///
     /// # Example
     /// ```
     /// use pancake_swap_sdk::{PancakeSwapService, EvmClient, EvmType};
@@ -78,7 +412,7 @@ impl PancakeSwapService {
     ///         "0xe9e7CEA3DedcA5984780Bafc599bD69ADd087D56".parse()?, // BUSD
     ///     ];
     ///     
-    ///     let amounts = service.get_amounts_out_v2(amount_in, path).await?;
+    ///     let amounts = service.get_amounts_out_v2(amount_in, path, None).await?;
     ///     println!("Output amounts: {:?}", amounts);
     ///     Ok(())
     /// }
@@ -87,13 +421,98 @@ impl PancakeSwapService {
         &self,
         amount_in: U256,
         path: Vec<Address>,
-    ) -> Result<Vec<U256>, EvmError> {
+        block: Option<BlockNumber>,
+    ) -> PancakeResult<Vec<U256>> {
         let router_address =
             PancakeSwapConfig::v2_router_address(self.evm.client.evm_type.unwrap())?;
+        self.get_amounts_out_v2_at(router_address, amount_in, path, block)
+            .await
+    }
+
+    /// Get amounts out for a swap (V2), each paired with its human-readable decimal string
+    ///
+    /// Every amount is formatted with the decimals of its token in `path`, as given by
+    /// `get_token_decimals` (so `with_decimals_overrides` applies); each distinct token's
+    /// decimals are looked up once.
+    ///
+    /// # Example
+    /// ```
+    /// use ethers::types::U256;
+    ///
+    /// let amount_in = U256::from(1000000000000000000u64); // 1 WBNB
+    /// for (amount, formatted) in service
+    ///     .get_amounts_out_v2_formatted(amount_in, vec![wbnb, busd])
+    ///     .await?
+    /// {
+    ///     println!("{} ({})", formatted, amount);
+    /// }
+    /// ```
+    pub async fn get_amounts_out_v2_formatted(
+        &self,
+        amount_in: U256,
+        path: Vec<Address>,
+    ) -> PancakeResult<Vec<(U256, String)>> {
+        let amounts = self
+            .get_amounts_out_v2(amount_in, path.clone(), None)
+            .await?;
+        let mut decimals: HashMap<Address, u8> = HashMap::new();
+        let mut formatted = Vec::with_capacity(amounts.len());
+        for (token, amount) in path.iter().zip(amounts) {
+            let token_decimals = match decimals.get(token) {
+                Some(token_decimals) => *token_decimals,
+                None => {
+                    let token_decimals = self.get_token_decimals(*token).await?;
+                    decimals.insert(*token, token_decimals);
+                    token_decimals
+                }
+            };
+            formatted.push((
+                amount,
+                crate::tool::math_utils::format_units(amount, token_decimals),
+            ));
+        }
+        Ok(formatted)
+    }
+
+    /// Get amounts out for a swap on an explicit V2-compatible router
+    ///
+    /// Useful for testnet deployments or other V2 forks on the same chain.
+    ///
+    /// # Example
+    /// ```
+    /// use ethers::types::U256;
+    ///
+    /// let biswap_router = "0x3a6d8cA21D1CF76F653A67577FA0D27453350dD8".parse()?;
+    /// let amount_in = U256::from(1000000000000000000u64);
+    /// let amounts = service
+    ///     .get_amounts_out_v2_on(biswap_router, amount_in, vec![wbnb, busd])
+    ///     .await?;
+    /// ```
+    pub async fn get_amounts_out_v2_on(
+        &self,
+        router_address: Address,
+        amount_in: U256,
+        path: Vec<Address>,
+    ) -> PancakeResult<Vec<U256>> {
+        self.get_amounts_out_v2_at(router_address, amount_in, path, None)
+            .await
+    }
+
+    /// Get amounts out on `router_address`, at `block` or the latest block
+    async fn get_amounts_out_v2_at(
+        &self,
+        router_address: Address,
+        amount_in: U256,
+        path: Vec<Address>,
+        block: Option<BlockNumber>,
+    ) -> PancakeResult<Vec<U256>> {
         let router = self.router.v2_router(router_address);
-        router
-            .get_amounts_out(amount_in, path)
-            .call()
+        let mut call = router.get_amounts_out(amount_in, path);
+        if let Some(block) = block {
+            call = call.block(block);
+        }
+        self.throttle().await;
+        self.observe("getAmountsOut", call.call())
             .await
             .map_err(|e| EvmError::ContractError(format!("Failed to get amounts out: {}", e)))
     }
@@ -103,19 +522,70 @@ impl PancakeSwapService {
         &self,
         amount_out: U256,
         path: Vec<Address>,
-    ) -> Result<Vec<U256>, EvmError> {
+    ) -> PancakeResult<Vec<U256>> {
         let router_address =
             PancakeSwapConfig::v2_router_address(self.evm.client.evm_type.unwrap())?;
         let router = self.router.v2_router(router_address);
-        router
-            .get_amounts_in(amount_out, path)
-            .call()
-            .await
-            .map_err(|e| EvmError::ContractError(format!("Failed to get amounts in: {}", e)))
+        self.throttle().await;
+        self.observe(
+            "getAmountsIn",
+            router.get_amounts_in(amount_out, path).call(),
+        )
+        .await
+        .map_err(|e| EvmError::ContractError(format!("Failed to get amounts in: {}", e)))
+    }
+
+    /// Quote a V2 swap locally from known reserves, without any RPC call
+    ///
+    /// Applies the PancakeSwap V2 fee of 0.25%, so the result matches the router's
+    /// `getAmountsOut` for the same reserves.
+    ///
+    /// # Example
+    /// ```
+    /// use ethers::types::U256;
+    ///
+    /// let reserve_in = U256::from(1000000000000000000000u128); // 1000 tokens
+    /// let reserve_out = U256::from(500000000000000000000u128); // 500 tokens
+    /// let amount_in = U256::from(1000000000000000000u64); // 1 token
+    /// let amount_out = service.quote_from_reserves(reserve_in, reserve_out, amount_in)?;
+    /// ```
+    pub fn quote_from_reserves(
+        &self,
+        reserve_in: U256,
+        reserve_out: U256,
+        amount_in: U256,
+    ) -> PancakeResult<U256> {
+        crate::tool::math_utils::calculate_amount_out(
+            amount_in,
+            reserve_in,
+            reserve_out,
+            crate::tool::math_utils::PANCAKE_V2_FEE_BPS,
+        )
+        .map_err(|e| EvmError::CalculationError(format!("Failed to quote from reserves: {}", e)))
+    }
+
+    /// Quote a multi-hop V2 swap locally, given the `(reserve_in, reserve_out)` of each hop in order
+    pub fn quote_path_from_reserves(
+        &self,
+        reserves: Vec<(U256, U256)>,
+        amount_in: U256,
+    ) -> PancakeResult<U256> {
+        if reserves.is_empty() {
+            return Err(EvmError::InvalidInput(
+                "Path must contain at least 1 hop".to_string(),
+            ));
+        }
+        let mut current_amount = amount_in;
+        for (reserve_in, reserve_out) in reserves {
+            current_amount = self.quote_from_reserves(reserve_in, reserve_out, current_amount)?;
+        }
+        Ok(current_amount)
     }
 
     /// execute V2 swap
     ///
+    /// Output is sent to `recipient`, or to the wallet when it is `None`.
+    ///
     /// # Example
     /// ```
     /// use pancake_swap_sdk::{PancakeSwapService, EvmClient, EvmType};
@@ -132,7 +602,8 @@ impl PancakeSwapService {
     ///     let amount_in = U256::from(1000000000000000000u64); // 1 BNB
     ///     let slippage_percent = 1.0; // 1% slippage
     ///     
-    ///     let tx_hash = service.swap_v2(token_in, token_out, amount_in, slippage_percent).await?;
+    ///     // Output goes to the wallet; pass Some(address) to send it elsewhere
+    ///     let tx_hash = service.swap_v2(token_in, token_out, amount_in, slippage_percent, None).await?;
     ///     println!("Transaction hash: {:?}", tx_hash);
     ///     Ok(())
     /// }
@@ -143,18 +614,23 @@ impl PancakeSwapService {
         token_out: Address,
         amount_in: U256,
         slippage_percent: f64,
-    ) -> Result<ethers::types::H256, EvmError> {
+        recipient: Option<Address>,
+    ) -> PancakeResult<ethers::types::H256> {
         if self.evm.client.wallet.is_none() {
             return Err(EvmError::WalletError("No wallet configured".to_string()));
         }
 
+        self.ensure_contracts(&[token_in, token_out]).await?;
+        self.ensure_transferable(token_in).await?;
+        let recipient = self.resolve_recipient(recipient)?;
+
         let router_address =
             PancakeSwapConfig::v2_router_address(self.evm.client.evm_type.unwrap())?;
-        let deadline = crate::tool::time_utils::calculate_deadline(30); // 30 minutes
+        let deadline = self.swap_deadline(30).await?; // 30 minutes
 
         // Get expected output
         let amounts = self
-            .get_amounts_out_v2(amount_in, vec![token_in, token_out])
+            .get_amounts_out_v2(amount_in, vec![token_in, token_out], None)
             .await?;
         let expected_out = amounts
             .last()
@@ -162,95 +638,174 @@ impl PancakeSwapService {
 
         // Calculate minimum output with slippage
         let amount_out_min = self.calculate_amount_with_slippage(*expected_out, slippage_percent);
-        let wallet_address = self.evm.client.wallet.as_ref().unwrap().address();
 
         let router = self.router.v2_router(router_address);
         let tx = router.swap_exact_tokens_for_tokens(
             amount_in,
             amount_out_min,
             vec![token_in, token_out],
-            wallet_address,
+            recipient,
             deadline.into(),
         );
 
-        let pending_tx = tx
-            .send()
-            .await
-            .map_err(|e| EvmError::TransactionError(format!("Failed to swap tokens: {}", e)))?;
+        let tx = self.apply_gas_limit(tx).await?;
 
-        Ok(pending_tx.tx_hash())
+        self.throttle().await;
+        self.send_transaction(tx, "swap tokens").await
     }
 
-    /// Execute V3 swap
+    /// Execute V2 swap, routing through the wrapped native token when there is no direct pair
+    ///
+    /// Like `swap_v2`, but first checks the factory for a `token_in`/`token_out` pair. When
+    /// the pair exists and quotes, the swap uses the direct path; otherwise it goes through
+    /// WBNB/WETH (`[token_in, wrapped_native, token_out]`). Returns the transaction hash and
+    /// the path used.
     ///
     /// # Example
     /// ```
-    /// use pancake_swap_sdk::{PancakeSwapService, EvmClient, EvmType};
-    /// use ethers::types::{Address, U256};
+    /// use ethers::types::U256;
     ///
-    /// #[tokio::main]
-    /// async fn main() -> Result<(),()> {
-    ///     let private_key = "your_private_key_here";
-    ///     let client = EvmClient::with_wallet(EvmType::Bsc, private_key).await?;
-    ///     let service = PancakeSwapService::new(std::sync::Arc::new(client));
-    ///     
-    ///     let token_in: Address = "0xbb4CdB9CBd36B01bD1cBaEBF2De08d9173bc095c".parse()?; // WBNB
-    ///     let token_out: Address = "0xe9e7CEA3DedcA5984780Bafc599bD69ADd087D56".parse()?; // BUSD
-    ///     let amount_in = U256::from(1000000000000000000u64); // 1 BNB
-    ///     let slippage_percent = 1.0; // 1% slippage
-    ///     let fee_tier = Some(500); // 0.05% fee
-    ///     
-    ///     let tx_hash = service.swap_v3(token_in, token_out, amount_in, slippage_percent, fee_tier).await?;
-    ///     println!("Transaction hash: {:?}", tx_hash);
-    ///     Ok(())
-    /// }
+    /// let amount_in = U256::from(1000000000000000000u64);
+    /// let (tx_hash, path) = service
+    ///     .swap_v2_auto_route(cake, some_token, amount_in, 1.0, None)
+    ///     .await?;
+    /// println!("Swapped along {:?}: {:?}", path, tx_hash);
     /// ```
-    pub async fn swap_v3(
+    pub async fn swap_v2_auto_route(
         &self,
         token_in: Address,
         token_out: Address,
         amount_in: U256,
         slippage_percent: f64,
-        fee_tier: Option<u32>,
-    ) -> Result<ethers::types::H256, EvmError> {
+        recipient: Option<Address>,
+    ) -> PancakeResult<(ethers::types::H256, Vec<Address>)> {
         if self.evm.client.wallet.is_none() {
             return Err(EvmError::WalletError("No wallet configured".to_string()));
         }
 
-        let router_address =
-            PancakeSwapConfig::v3_router_address(self.evm.client.evm_type.unwrap())?;
-        let deadline = crate::tool::time_utils::calculate_deadline(30);
+        self.ensure_contracts(&[token_in, token_out]).await?;
+        self.ensure_transferable(token_in).await?;
+        let recipient = self.resolve_recipient(recipient)?;
 
-        let fee = fee_tier.unwrap_or_else(|| self.get_default_fee_tier(token_in, token_out));
-        let expected_out = self
-            .simulate_v3_swap(token_in, token_out, fee, amount_in)
+        let router_address =
+            PancakeSwapConfig::v2_router_address(self.evm.client.evm_type.unwrap())?;
+        self.throttle().await;
+        let factory_address = self
+            .observe(
+                "factory",
+                self.router.get_factory_from_router(router_address),
+            )
+            .await?;
+        let direct_pair = self
+            .observe(
+                "getPair",
+                self.factory.get_pair(factory_address, token_in, token_out),
+            )
             .await?;
-        let amount_out_min = self.calculate_amount_with_slippage(expected_out, slippage_percent);
-        let wallet_address = self.evm.client.wallet.as_ref().unwrap().address();
 
-        let router = self.router.v3_router_signer(router_address)?;
+        let direct_path = vec![token_in, token_out];
+        let direct_quote = match direct_pair {
+            Some(_) => {
+                self.get_amounts_out_v2(amount_in, direct_path.clone(), None)
+                    .await
+            }
+            None => Err(EvmError::CalculationError("No direct V2 pair".to_string())),
+        };
+        let (path, amounts) = match direct_quote {
+            Ok(amounts) => (direct_path, amounts),
+            Err(direct_err) => {
+                let wrapped_native = self.wrapped_native().await?;
+                if token_in == wrapped_native || token_out == wrapped_native {
+                    return Err(direct_err);
+                }
+                let path = vec![token_in, wrapped_native, token_out];
+                let amounts = self
+                    .get_amounts_out_v2(amount_in, path.clone(), None)
+                    .await
+                    .map_err(|e| {
+                        EvmError::CalculationError(format!(
+                            "No V2 route: direct ({}), via wrapped native ({})",
+                            direct_err, e
+                        ))
+                    })?;
+                (path, amounts)
+            }
+        };
+        let expected_out = amounts
+            .last()
+            .ok_or_else(|| EvmError::CalculationError("Invalid path".to_string()))?;
+        let amount_out_min = self.calculate_amount_with_slippage(*expected_out, slippage_percent);
 
-        // 使用单独的参数调用 exactInputSingle
-        let tx = router.exact_input_single(
-            token_in,
-            token_out,
-            fee,
-            wallet_address,
-            deadline.into(),
+        let deadline = self.swap_deadline(30).await?; // 30 minutes
+        let router = self.router.v2_router(router_address);
+        let tx = router.swap_exact_tokens_for_tokens(
             amount_in,
             amount_out_min,
-            U256::zero(),
+            path.clone(),
+            recipient,
+            deadline.into(),
         );
+        let tx = self.apply_gas_limit(tx).await?;
 
-        let pending_tx = tx
-            .send()
-            .await
-            .map_err(|e| EvmError::TransactionError(format!("Failed to execute V3 swap: {}", e)))?;
+        self.throttle().await;
+        let tx_hash = self.send_transaction(tx, "swap tokens").await?;
+        Ok((tx_hash, path))
+    }
 
-        Ok(pending_tx.tx_hash())
+    /// Encode a V2 swap without sending it, returning `(router_address, calldata)`
+    ///
+    /// The minimum output is quoted and slippage-adjusted exactly as in `swap_v2`. No wallet
+    /// is needed, which suits relayers, meta-transactions and account-abstraction bundles;
+    /// whoever submits the call must hold and have approved `amount_in` of `token_in`.
+    ///
+    /// # Example
+    /// ```
+    /// use ethers::types::U256;
+    ///
+    /// let amount_in = U256::from(1000000000000000000u64);
+    /// let (router, calldata) = service
+    ///     .swap_v2_calldata(wbnb, busd, amount_in, 1.0, user_address)
+    ///     .await?;
+    /// ```
+    pub async fn swap_v2_calldata(
+        &self,
+        token_in: Address,
+        token_out: Address,
+        amount_in: U256,
+        slippage_percent: f64,
+        recipient: Address,
+    ) -> PancakeResult<(Address, Bytes)> {
+        let recipient = self.resolve_recipient(Some(recipient))?;
+        let router_address =
+            PancakeSwapConfig::v2_router_address(self.evm.client.evm_type.unwrap())?;
+        let deadline = self.swap_deadline(30).await?;
+        let amounts = self
+            .get_amounts_out_v2(amount_in, vec![token_in, token_out], None)
+            .await?;
+        let expected_out = amounts
+            .last()
+            .ok_or_else(|| EvmError::CalculationError("Invalid path".to_string()))?;
+        let amount_out_min = self.calculate_amount_with_slippage(*expected_out, slippage_percent);
+        let calldata = self
+            .router
+            .v2_router(router_address)
+            .swap_exact_tokens_for_tokens(
+                amount_in,
+                amount_out_min,
+                vec![token_in, token_out],
+                recipient,
+                deadline.into(),
+            )
+            .calldata()
+            .ok_or_else(|| EvmError::ContractError("Failed to encode V2 swap".to_string()))?;
+        Ok((router_address, calldata))
     }
 
-    /// Auto swap - find best price between V2 and V3 and execute
+    /// Execute V2 swap, aborting when the price impact exceeds the given cap
+    ///
+    /// The impact is `PriceService::get_price_impact`: the quote's execution price against
+    /// the pair's spot price, swap fee included, so `max_price_impact_pct` must leave room
+    /// for the 0.25% fee.
     ///
     /// # Example
     /// ```
@@ -262,30 +817,996 @@ impl PancakeSwapService {
     ///     let private_key = "your_private_key_here";
     ///     let client = EvmClient::with_wallet(EvmType::Bsc, private_key).await?;
     ///     let service = PancakeSwapService::new(std::sync::Arc::new(client));
-    ///     
+    ///
     ///     let token_in: Address = "0xbb4CdB9CBd36B01bD1cBaEBF2De08d9173bc095c".parse()?; // WBNB
     ///     let token_out: Address = "0xe9e7CEA3DedcA5984780Bafc599bD69ADd087D56".parse()?; // BUSD
     ///     let amount_in = U256::from(1000000000000000000u64); // 1 BNB
     ///     let slippage_percent = 1.0; // 1% slippage
-    ///     
-    ///     let result = service.auto_swap(token_in, token_out, amount_in, slippage_percent).await?;
-    ///     println!("Auto swap result: {:?}", result);
+    ///     let max_price_impact_pct = 3.0; // abort above 3% impact
+    ///
+    ///     let tx_hash = service
+    ///         .swap_v2_guarded(token_in, token_out, amount_in, slippage_percent, max_price_impact_pct)
+    ///         .await?;
+    ///     println!("Transaction hash: {:?}", tx_hash);
     ///     Ok(())
     /// }
     /// ```
-    pub async fn auto_swap(
+    pub async fn swap_v2_guarded(
         &self,
         token_in: Address,
         token_out: Address,
         amount_in: U256,
         slippage_percent: f64,
-    ) -> Result<crate::types::AutoSwapResult, EvmError> {
-        // Get best price comparison
-        let price_comparison = self.get_best_price(token_in, token_out, amount_in).await?;
-
-        let price_comparison_clone = price_comparison.clone();
-
-        let (selected_version, amount_out_min, tx_hash) = match price_comparison.best {
+        max_price_impact_pct: f64,
+    ) -> PancakeResult<ethers::types::H256> {
+        let router_address =
+            PancakeSwapConfig::v2_router_address(self.evm.client.evm_type.unwrap())?;
+        let price_impact = self
+            .observe(
+                "get_price_impact",
+                self.price
+                    .get_price_impact(router_address, token_in, token_out, amount_in),
+            )
+            .await?;
+        if price_impact > max_price_impact_pct {
+            return Err(EvmError::CalculationError(format!(
+                "price impact {:.2}% exceeds limit",
+                price_impact
+            )));
+        }
+        self.swap_v2(token_in, token_out, amount_in, slippage_percent, None)
+            .await
+    }
+
+    /// Simulate the exact `swap_v2` transaction with `eth_call` and return the output it
+    /// would deliver, without spending gas
+    ///
+    /// The swap is built as in `swap_v2` and called from the wallet against the latest
+    /// state, so it fails the same way the real transaction would: missing allowance or
+    /// balance, an expired deadline, the slippage bound, or a token that blocks transfers.
+    /// Tokens that tax transfers into the pair make the plain V2 swap revert, which is
+    /// reported here instead of at send time. The returned amount is what the pair pays out;
+    /// a tax charged by `token_out` on delivery is not deducted.
+    ///
+    /// # Example
+    /// ```
+    /// use ethers::types::U256;
+    ///
+    /// let amount_in = U256::from(1000000000000000000u64);
+    /// let amount_out = service
+    ///     .simulate_swap_v2_execution(wbnb, busd, amount_in, 1.0)
+    ///     .await?;
+    /// println!("Swap would return {}", amount_out);
+    /// ```
+    pub async fn simulate_swap_v2_execution(
+        &self,
+        token_in: Address,
+        token_out: Address,
+        amount_in: U256,
+        slippage_percent: f64,
+    ) -> PancakeResult<U256> {
+        let wallet_address = self
+            .evm
+            .client
+            .wallet
+            .as_ref()
+            .ok_or_else(|| EvmError::WalletError("No wallet configured".to_string()))?
+            .address();
+        let router_address =
+            PancakeSwapConfig::v2_router_address(self.evm.client.evm_type.unwrap())?;
+        let deadline = self.swap_deadline(30).await?;
+        let amounts = self
+            .get_amounts_out_v2(amount_in, vec![token_in, token_out], None)
+            .await?;
+        let expected_out = amounts
+            .last()
+            .ok_or_else(|| EvmError::CalculationError("Invalid path".to_string()))?;
+        let amount_out_min = self.calculate_amount_with_slippage(*expected_out, slippage_percent);
+        let router = self.router.v2_router(router_address);
+        let tx = router
+            .swap_exact_tokens_for_tokens(
+                amount_in,
+                amount_out_min,
+                vec![token_in, token_out],
+                wallet_address,
+                deadline.into(),
+            )
+            .from(wallet_address);
+        self.throttle().await;
+        let amounts = self
+            .observe("swapExactTokensForTokens", tx.call())
+            .await
+            .map_err(|e| EvmError::TransactionError(format!("Swap simulation reverted: {}", e)))?;
+        amounts
+            .last()
+            .copied()
+            .ok_or_else(|| EvmError::CalculationError("Invalid path".to_string()))
+    }
+
+    /// Sign an EIP-2612 permit allowing `spender` to pull `amount` of `token` from the wallet
+    ///
+    /// The digest is built from the token's own `DOMAIN_SEPARATOR` and `nonces`, so only
+    /// tokens implementing EIP-2612 are supported.
+    ///
+    /// # Example
+    /// ```
+    /// let router = PancakeSwapConfig::v3_router_address(EvmType::BSC_MAINNET)?;
+    /// let deadline = U256::from(crate::tool::time_utils::calculate_deadline(30));
+    /// let permit = service.sign_permit(token_in, router, amount_in, deadline).await?;
+    /// ```
+    pub async fn sign_permit(
+        &self,
+        token: Address,
+        spender: Address,
+        amount: U256,
+        deadline: U256,
+    ) -> PancakeResult<crate::types::PermitData> {
+        use ethers::abi::{Token, encode};
+        use ethers::types::H256;
+        use ethers::utils::keccak256;
+
+        let wallet = self
+            .evm
+            .client
+            .wallet
+            .as_ref()
+            .ok_or_else(|| EvmError::WalletError("No wallet configured".to_string()))?;
+        let owner = wallet.address();
+        let erc20 = IERC20Permit::new(token, self.evm.client.provider.clone());
+        let domain_separator = self
+            .observe("DOMAIN_SEPARATOR", erc20.domain_separator().call())
+            .await
+            .map_err(|e| {
+                EvmError::ContractError(format!("Token does not support permit: {}", e))
+            })?;
+        let nonce = self
+            .observe("nonces", erc20.nonces(owner).call())
+            .await
+            .map_err(|e| EvmError::ContractError(format!("Failed to get permit nonce: {}", e)))?;
+        let permit_typehash = keccak256(
+            b"Permit(address owner,address spender,uint256 value,uint256 nonce,uint256 deadline)",
+        );
+        let struct_hash = keccak256(encode(&[
+            Token::FixedBytes(permit_typehash.to_vec()),
+            Token::Address(owner),
+            Token::Address(spender),
+            Token::Uint(amount),
+            Token::Uint(nonce),
+            Token::Uint(deadline),
+        ]));
+        let mut message = Vec::with_capacity(66);
+        message.extend_from_slice(b"\x19\x01");
+        message.extend_from_slice(&domain_separator);
+        message.extend_from_slice(&struct_hash);
+        let signature = wallet
+            .sign_hash(H256::from(keccak256(message)))
+            .map_err(|e| EvmError::WalletError(format!("Failed to sign permit: {}", e)))?;
+        let mut r = [0u8; 32];
+        let mut s = [0u8; 32];
+        signature.r.to_big_endian(&mut r);
+        signature.s.to_big_endian(&mut s);
+        Ok(crate::types::PermitData {
+            token,
+            owner,
+            spender,
+            value: amount,
+            deadline,
+            v: signature.v as u8,
+            r: H256::from(r),
+            s: H256::from(s),
+        })
+    }
+
+    /// Execute V2 swap with a signed permit instead of a prior `approve`
+    ///
+    /// The permit and the swap are bundled in one Smart Router `multicall`, so the permit's
+    /// spender must be the V3 (Smart) router returned by `PancakeSwapConfig::v3_router_address`.
+    ///
+    /// # Example
+    /// ```
+    /// let router = PancakeSwapConfig::v3_router_address(EvmType::BSC_MAINNET)?;
+    /// let deadline = U256::from(crate::tool::time_utils::calculate_deadline(30));
+    /// let permit = service.sign_permit(token_in, router, amount_in, deadline).await?;
+    /// let tx_hash = service
+    ///     .swap_v2_with_permit(token_in, token_out, amount_in, 1.0, permit)
+    ///     .await?;
+    /// ```
+    pub async fn swap_v2_with_permit(
+        &self,
+        token_in: Address,
+        token_out: Address,
+        amount_in: U256,
+        slippage_percent: f64,
+        permit: crate::types::PermitData,
+    ) -> PancakeResult<ethers::types::H256> {
+        if self.evm.client.wallet.is_none() {
+            return Err(EvmError::WalletError("No wallet configured".to_string()));
+        }
+        let router_address =
+            PancakeSwapConfig::v3_router_address(self.evm.client.evm_type.unwrap())?;
+        if permit.token != token_in || permit.spender != router_address {
+            return Err(EvmError::InvalidInput(
+                "Permit must be for token_in with the smart router as spender".to_string(),
+            ));
+        }
+        if permit.value < amount_in {
+            return Err(EvmError::InvalidInput(
+                "Permit value is below amount_in".to_string(),
+            ));
+        }
+
+        let amounts = self
+            .get_amounts_out_v2(amount_in, vec![token_in, token_out], None)
+            .await?;
+        let expected_out = amounts
+            .last()
+            .ok_or_else(|| EvmError::CalculationError("Invalid path".to_string()))?;
+        let amount_out_min = self.calculate_amount_with_slippage(*expected_out, slippage_percent);
+        let wallet = self.evm.client.wallet.as_ref().unwrap();
+        let wallet_address = wallet.address();
+        let deadline = self.swap_deadline(30).await?;
+
+        let signer_middleware = ethers::middleware::SignerMiddleware::new(
+            self.evm.client.provider.clone(),
+            wallet.clone(),
+        );
+        let router = IPancakeSmartRouter::new(router_address, Arc::new(signer_middleware));
+        let permit_call = router
+            .self_permit(
+                token_in,
+                permit.value,
+                permit.deadline,
+                permit.v,
+                permit.r.into(),
+                permit.s.into(),
+            )
+            .calldata()
+            .ok_or_else(|| EvmError::ContractError("Failed to encode selfPermit".to_string()))?;
+        let swap_call = router
+            .swap_exact_tokens_for_tokens(
+                amount_in,
+                amount_out_min,
+                vec![token_in, token_out],
+                wallet_address,
+            )
+            .calldata()
+            .ok_or_else(|| {
+                EvmError::ContractError("Failed to encode swapExactTokensForTokens".to_string())
+            })?;
+        let tx = router.multicall(deadline.into(), vec![permit_call, swap_call]);
+        let tx = self.apply_gas_limit(tx).await?;
+        self.throttle().await;
+        self.send_transaction(tx, "swap with permit").await
+    }
+
+    /// Execute V2 swap and wait until it has the given number of confirmations
+    ///
+    /// The returned `SwapResult` carries the amounts decoded from the receipt's `Transfer`
+    /// logs, so `average_price` reflects the price actually achieved.
+    ///
+    /// # Example
+    /// ```
+    /// use std::time::Duration;
+    ///
+    /// let result = service
+    ///     .swap_v2_confirmed(token_in, token_out, amount_in, 1.0, 1, Duration::from_secs(60))
+    ///     .await?;
+    /// println!("Filled at {}", result.average_price);
+    /// ```
+    pub async fn swap_v2_confirmed(
+        &self,
+        token_in: Address,
+        token_out: Address,
+        amount_in: U256,
+        slippage_percent: f64,
+        confirmations: u64,
+        timeout: std::time::Duration,
+    ) -> PancakeResult<crate::types::SwapResult> {
+        let tx_hash = self
+            .swap_v2(token_in, token_out, amount_in, slippage_percent, None)
+            .await?;
+        let receipt = self
+            .wait_for_receipt(tx_hash, confirmations, timeout)
+            .await?;
+        self.swap_result_from_receipt(&receipt, vec![token_in, token_out])
+            .await
+    }
+
+    /// Execute V3 swap
+    ///
+    /// Output is sent to `recipient`, or to the wallet when it is `None`.
+    ///
+    /// # Example
+    /// ```
+    /// use pancake_swap_sdk::{PancakeSwapService, EvmClient, EvmType};
+    /// use pancake_swap_sdk::types::FeeTier;
+    /// use ethers::types::{Address, U256};
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(),()> {
+    ///     let private_key = "your_private_key_here";
+    ///     let client = EvmClient::with_wallet(EvmType::Bsc, private_key).await?;
+    ///     let service = PancakeSwapService::new(std::sync::Arc::new(client));
+    ///     
+    ///     let token_in: Address = "0xbb4CdB9CBd36B01bD1cBaEBF2De08d9173bc095c".parse()?; // WBNB
+    ///     let token_out: Address = "0xe9e7CEA3DedcA5984780Bafc599bD69ADd087D56".parse()?; // BUSD
+    ///     let amount_in = U256::from(1000000000000000000u64); // 1 BNB
+    ///     let slippage_percent = 1.0; // 1% slippage
+    ///     let fee_tier = Some(FeeTier::Low); // 0.05% fee
+    ///     
+    ///     let tx_hash = service
+    ///         .swap_v3(token_in, token_out, amount_in, slippage_percent, fee_tier, None)
+    ///         .await?;
+    ///     println!("Transaction hash: {:?}", tx_hash);
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn swap_v3(
+        &self,
+        token_in: Address,
+        token_out: Address,
+        amount_in: U256,
+        slippage_percent: f64,
+        fee_tier: Option<FeeTier>,
+        recipient: Option<Address>,
+    ) -> PancakeResult<ethers::types::H256> {
+        self.swap_v3_with_price_limit(
+            token_in,
+            token_out,
+            amount_in,
+            slippage_percent,
+            fee_tier,
+            recipient,
+            U256::zero(),
+        )
+        .await
+    }
+
+    /// Execute V3 swap that stops once the pool price reaches `sqrt_price_limit_x96`
+    ///
+    /// `swap_v3` passes zero, which lets the swap walk the whole curve. A limit caps the price
+    /// impact: the swap fills only up to the limit, and the `amount_out_min` derived from
+    /// `slippage_percent` then rejects fills that stop short. Compute the limit from the pool's
+    /// current `slot0` sqrt price with `math_utils::calculate_sqrt_price_limit_x96`, where
+    /// `zero_for_one` is `token_in < token_out`.
+    ///
+    /// # Example
+    /// ```
+    /// use crate::tool::math_utils::calculate_sqrt_price_limit_x96;
+    ///
+    /// let pool = IPancakeV3Pool::new(pool_address, provider.clone());
+    /// let (sqrt_price_x96, ..) = pool.slot_0().call().await?;
+    /// // Let the price move at most 2% against us
+    /// let limit = calculate_sqrt_price_limit_x96(sqrt_price_x96, 2.0, token_in < token_out);
+    /// let tx_hash = service
+    ///     .swap_v3_with_price_limit(token_in, token_out, amount_in, 1.0, Some(FeeTier::Low), None, limit)
+    ///     .await?;
+    /// ```
+    #[allow(clippy::too_many_arguments)]
+    pub async fn swap_v3_with_price_limit(
+        &self,
+        token_in: Address,
+        token_out: Address,
+        amount_in: U256,
+        slippage_percent: f64,
+        fee_tier: Option<FeeTier>,
+        recipient: Option<Address>,
+        sqrt_price_limit_x96: U256,
+    ) -> PancakeResult<ethers::types::H256> {
+        if self.evm.client.wallet.is_none() {
+            return Err(EvmError::WalletError("No wallet configured".to_string()));
+        }
+
+        self.ensure_contracts(&[token_in, token_out]).await?;
+        self.ensure_transferable(token_in).await?;
+        let recipient = self.resolve_recipient(recipient)?;
+
+        let router_address =
+            PancakeSwapConfig::v3_router_address(self.evm.client.evm_type.unwrap())?;
+        let deadline = self.swap_deadline(30).await?;
+
+        let fee = fee_tier
+            .unwrap_or_else(|| self.get_default_fee_tier(token_in, token_out))
+            .as_u32();
+        self.check_twap_guard(token_in, token_out, fee).await?;
+        let expected_out = self
+            .simulate_v3_swap(token_in, token_out, fee, amount_in, None)
+            .await?;
+        let amount_out_min = self.calculate_amount_with_slippage(expected_out, slippage_percent);
+
+        let router = self.router.v3_router_signer(router_address)?;
+
+        // 使用单独的参数调用 exactInputSingle
+        let tx = router.exact_input_single(
+            token_in,
+            token_out,
+            fee,
+            recipient,
+            deadline.into(),
+            amount_in,
+            amount_out_min,
+            sqrt_price_limit_x96,
+        );
+
+        let tx = self.apply_gas_limit(tx).await?;
+
+        self.throttle().await;
+        self.send_transaction(tx, "execute V3 swap").await
+    }
+
+    /// Execute a V3 swap with native BNB/ETH on one side, batched in the Smart Router's
+    /// `multicall`
+    ///
+    /// One of `token_in` / `token_out` must be the wrapped native token. With native input,
+    /// `amount_in` is attached as value and `refundETH` returns anything left unspent. With
+    /// native output, `exactInputSingle` pays the router, which `unwrapWETH9` then sends to
+    /// `recipient` (the wallet when `None`) as native currency. Plain `swap_v3` would deliver
+    /// the wrapped token instead. The deadline is checked by `multicall`, since the Smart
+    /// Router's swap params have none.
+    ///
+    /// # Example
+    /// ```
+    /// use ethers::types::U256;
+    ///
+    /// let amount_in = U256::from(1000000000000000000u64); // 1 CAKE
+    /// // Sell CAKE for BNB
+    /// let tx_hash = service
+    ///     .swap_v3_native(cake, wbnb, amount_in, 1.0, Some(FeeTier::Medium), None)
+    ///     .await?;
+    /// ```
+    pub async fn swap_v3_native(
+        &self,
+        token_in: Address,
+        token_out: Address,
+        amount_in: U256,
+        slippage_percent: f64,
+        fee_tier: Option<FeeTier>,
+        recipient: Option<Address>,
+    ) -> PancakeResult<ethers::types::H256> {
+        if self.evm.client.wallet.is_none() {
+            return Err(EvmError::WalletError("No wallet configured".to_string()));
+        }
+        let wrapped_native = self.wrapped_native().await?;
+        let native_in = token_in == wrapped_native;
+        let native_out = token_out == wrapped_native;
+        if native_in == native_out {
+            return Err(EvmError::InvalidInput(
+                "Exactly one side of a native V3 swap must be the wrapped native token".to_string(),
+            ));
+        }
+
+        self.ensure_contracts(&[token_in, token_out]).await?;
+        if !native_in {
+            self.ensure_transferable(token_in).await?;
+        }
+        let recipient = self.resolve_recipient(recipient)?;
+
+        let deadline = self.swap_deadline(30).await?;
+
+        let fee = fee_tier
+            .unwrap_or_else(|| self.get_default_fee_tier(token_in, token_out))
+            .as_u32();
+        let expected_out = self
+            .simulate_v3_swap(token_in, token_out, fee, amount_in, None)
+            .await?;
+        let amount_out_min = self.calculate_amount_with_slippage(expected_out, slippage_percent);
+
+        self.send_native_v3_swap(
+            token_in,
+            token_out,
+            fee,
+            amount_in,
+            amount_out_min,
+            native_in,
+            recipient,
+            deadline,
+        )
+        .await
+    }
+
+    /// Send a single-pool V3 swap with native currency on one side through the Smart Router
+    ///
+    /// With `native_in`, `amount_in` is attached as value; otherwise the wrapped native
+    /// output is paid to the router and unwrapped to `recipient`.
+    #[allow(clippy::too_many_arguments)]
+    async fn send_native_v3_swap(
+        &self,
+        token_in: Address,
+        token_out: Address,
+        fee: u32,
+        amount_in: U256,
+        amount_out_min: U256,
+        native_in: bool,
+        recipient: Address,
+        deadline: u64,
+    ) -> PancakeResult<ethers::types::H256> {
+        let router_address =
+            PancakeSwapConfig::v3_router_address(self.evm.client.evm_type.unwrap())?;
+        let router = self.router.smart_router_signer(router_address)?;
+        // Native output is held by the router until unwrapWETH9 forwards it
+        let swap_recipient = if native_in { recipient } else { router_address };
+        let swap_call = router
+            .exact_input_single(ExactInputSingleParams {
+                token_in,
+                token_out,
+                fee,
+                recipient: swap_recipient,
+                amount_in,
+                amount_out_minimum: amount_out_min,
+                sqrt_price_limit_x96: U256::zero(),
+            })
+            .calldata()
+            .ok_or_else(|| EvmError::ContractError("Failed to encode V3 swap".to_string()))?;
+        self.send_smart_router_swap(
+            &router,
+            swap_call,
+            native_in.then_some(amount_in),
+            (!native_in).then_some((amount_out_min, recipient)),
+            deadline,
+            "execute V3 swap",
+        )
+        .await
+    }
+
+    /// Send a Smart Router V3 swap in one deadline-checked `multicall`
+    ///
+    /// `swap_call` is an encoded `exactInputSingle` or `exactInput`. With `native_value`, that
+    /// amount is attached and `refundETH` returns what the swap leaves unspent. With
+    /// `unwrap_to = (amount_min, recipient)`, `unwrapWETH9` forwards the wrapped output held by
+    /// the router to `recipient` as native currency.
+    async fn send_smart_router_swap<M: Middleware>(
+        &self,
+        router: &IPancakeSmartRouter<M>,
+        swap_call: Bytes,
+        native_value: Option<U256>,
+        unwrap_to: Option<(U256, Address)>,
+        deadline: u64,
+        action: &str,
+    ) -> PancakeResult<ethers::types::H256> {
+        let mut calls = vec![swap_call];
+        if let Some((amount_min, recipient)) = unwrap_to {
+            calls.push(
+                router
+                    .unwrap_weth9(amount_min, recipient)
+                    .calldata()
+                    .ok_or_else(|| {
+                        EvmError::ContractError("Failed to encode unwrapWETH9".to_string())
+                    })?,
+            );
+        }
+        if native_value.is_some() {
+            calls.push(router.refund_eth().calldata().ok_or_else(|| {
+                EvmError::ContractError("Failed to encode refundETH".to_string())
+            })?);
+        }
+        let mut tx = router.multicall(deadline.into(), calls);
+        if let Some(value) = native_value {
+            tx = tx.value(value);
+        }
+        let tx = self.apply_gas_limit(tx).await?;
+        self.throttle().await;
+        self.send_transaction(tx, action).await
+    }
+
+    /// Execute a multi-hop V3 swap along `tokens` with `fees[i]` for the pool between
+    /// `tokens[i]` and `tokens[i + 1]`
+    ///
+    /// The swap is sent as the Smart Router's `exactInput` inside its deadline-checked
+    /// `multicall`. With `to_native`, the last token must be the wrapped native token:
+    /// `exactInput` pays the router, and `unwrapWETH9` in the same `multicall` sends native
+    /// currency to `recipient` (the wallet when `None`).
+    ///
+    /// # Example
+    /// ```
+    /// use ethers::types::U256;
+    ///
+    /// let amount_in = U256::from(1000000000000000000u64); // 1 CAKE
+    /// // CAKE -> USDT -> WBNB, delivered as BNB
+    /// let tx_hash = service
+    ///     .swap_v3_multihop(vec![cake, usdt, wbnb], vec![FeeTier::Medium, FeeTier::Low], amount_in, 1.0, true, None)
+    ///     .await?;
+    /// ```
+    pub async fn swap_v3_multihop(
+        &self,
+        tokens: Vec<Address>,
+        fees: Vec<FeeTier>,
+        amount_in: U256,
+        slippage_percent: f64,
+        to_native: bool,
+        recipient: Option<Address>,
+    ) -> PancakeResult<ethers::types::H256> {
+        if self.evm.client.wallet.is_none() {
+            return Err(EvmError::WalletError("No wallet configured".to_string()));
+        }
+        let fees: Vec<u32> = fees.into_iter().map(FeeTier::as_u32).collect();
+        let path = universal_router::encode_v3_path(&tokens, &fees)?;
+        if to_native && tokens.last() != Some(&self.wrapped_native().await?) {
+            return Err(EvmError::InvalidInput(
+                "The last path token must be the wrapped native token for native output"
+                    .to_string(),
+            ));
+        }
+
+        self.ensure_contracts(&tokens).await?;
+        self.ensure_transferable(tokens[0]).await?;
+        let recipient = self.resolve_recipient(recipient)?;
+
+        let router_address =
+            PancakeSwapConfig::v3_router_address(self.evm.client.evm_type.unwrap())?;
+        let deadline = self.swap_deadline(30).await?;
+
+        let path = Bytes::from(path);
+        let expected_out = self.simulate_v3_path(path.clone(), amount_in).await?;
+        let amount_out_min = self.calculate_amount_with_slippage(expected_out, slippage_percent);
+
+        let router = self.router.smart_router_signer(router_address)?;
+        // With native output the router holds the wrapped tokens until unwrapWETH9 forwards them
+        let swap_call = router
+            .exact_input(ExactInputParams {
+                path,
+                recipient: if to_native { router_address } else { recipient },
+                amount_in,
+                amount_out_minimum: amount_out_min,
+            })
+            .calldata()
+            .ok_or_else(|| EvmError::ContractError("Failed to encode V3 swap".to_string()))?;
+        self.send_smart_router_swap(
+            &router,
+            swap_call,
+            None,
+            to_native.then_some((amount_out_min, recipient)),
+            deadline,
+            "execute V3 multi-hop swap",
+        )
+        .await
+    }
+
+    /// Encode a V3 `exactInputSingle` swap without sending it, returning
+    /// `(router_address, calldata)`
+    ///
+    /// The V3 counterpart of `swap_v2_calldata`; `fee_tier` defaults as in `swap_v3`.
+    ///
+    /// # Example
+    /// ```
+    /// use ethers::types::U256;
+    ///
+    /// let amount_in = U256::from(1000000000000000000u64);
+    /// let (router, calldata) = service
+    ///     .swap_v3_calldata(wbnb, busd, amount_in, 1.0, Some(FeeTier::Low), user_address)
+    ///     .await?;
+    /// ```
+    pub async fn swap_v3_calldata(
+        &self,
+        token_in: Address,
+        token_out: Address,
+        amount_in: U256,
+        slippage_percent: f64,
+        fee_tier: Option<FeeTier>,
+        recipient: Address,
+    ) -> PancakeResult<(Address, Bytes)> {
+        let recipient = self.resolve_recipient(Some(recipient))?;
+        let router_address =
+            PancakeSwapConfig::v3_router_address(self.evm.client.evm_type.unwrap())?;
+        let deadline = self.swap_deadline(30).await?;
+        let fee = fee_tier
+            .unwrap_or_else(|| self.get_default_fee_tier(token_in, token_out))
+            .as_u32();
+        let expected_out = self
+            .simulate_v3_swap(token_in, token_out, fee, amount_in, None)
+            .await?;
+        let amount_out_min = self.calculate_amount_with_slippage(expected_out, slippage_percent);
+        let calldata = self
+            .router
+            .v3_router(router_address)
+            .exact_input_single(
+                token_in,
+                token_out,
+                fee,
+                recipient,
+                deadline.into(),
+                amount_in,
+                amount_out_min,
+                U256::zero(),
+            )
+            .calldata()
+            .ok_or_else(|| EvmError::ContractError("Failed to encode V3 swap".to_string()))?;
+        Ok((router_address, calldata))
+    }
+
+    /// Buy a token with native BNB/ETH in one transaction
+    ///
+    /// Quotes the wrapped native token against `token_out` on V2 and V3 and sends
+    /// `native_amount` with the best venue: `swapExactETHForTokens` on V2, or the same Smart
+    /// Router multicall as `swap_v3_native` on V3, which wraps the attached value.
+    ///
+    /// # Example
+    /// ```
+    /// use ethers::types::U256;
+    ///
+    /// let native_amount = U256::from(100000000000000000u64); // 0.1 BNB
+    /// let tx_hash = service.buy_token_with_native(cake, native_amount, 1.0).await?;
+    /// ```
+    pub async fn buy_token_with_native(
+        &self,
+        token_out: Address,
+        native_amount: U256,
+        slippage_percent: f64,
+    ) -> PancakeResult<ethers::types::H256> {
+        if self.evm.client.wallet.is_none() {
+            return Err(EvmError::WalletError("No wallet configured".to_string()));
+        }
+        self.ensure_contracts(&[token_out]).await?;
+        let recipient = self.resolve_recipient(None)?;
+        let chain = self.evm.client.evm_type.unwrap();
+        let wrapped_native = self.wrapped_native().await?;
+        let comparison = self
+            .get_best_price(wrapped_native, token_out, native_amount, None)
+            .await?;
+        let deadline = self.swap_deadline(30).await?;
+
+        let tx_hash = match comparison.best {
+            crate::types::PriceSource::V2 => {
+                let expected_out = comparison.v2.map(|info| info.amount_out).ok_or_else(|| {
+                    EvmError::CalculationError("V2 price not available".to_string())
+                })?;
+                let amount_out_min =
+                    self.calculate_amount_with_slippage(expected_out, slippage_percent);
+                let router = self
+                    .router
+                    .v2_router_signer(PancakeSwapConfig::v2_router_address(chain)?)?;
+                let tx = router
+                    .swap_exact_eth_for_tokens(
+                        amount_out_min,
+                        vec![wrapped_native, token_out],
+                        recipient,
+                        deadline.into(),
+                    )
+                    .value(native_amount);
+                let tx = self.apply_gas_limit(tx).await?;
+                self.throttle().await;
+                self.send_transaction(tx, "execute V2 swap").await?
+            }
+            crate::types::PriceSource::V3 => {
+                let expected_out = comparison.v3.map(|info| info.amount_out).ok_or_else(|| {
+                    EvmError::CalculationError("V3 price not available".to_string())
+                })?;
+                let fee = comparison.fee_tier.ok_or_else(|| {
+                    EvmError::CalculationError("V3 fee tier not available".to_string())
+                })?;
+                let amount_out_min =
+                    self.calculate_amount_with_slippage(expected_out, slippage_percent);
+                self.send_native_v3_swap(
+                    wrapped_native,
+                    token_out,
+                    fee,
+                    native_amount,
+                    amount_out_min,
+                    true,
+                    recipient,
+                    deadline,
+                )
+                .await?
+            }
+            // The wrapped native token is never a stablecoin, so no StableSwap quote is taken
+            crate::types::PriceSource::Stable => {
+                return Err(EvmError::CalculationError(
+                    "StableSwap has no native token pools".to_string(),
+                ));
+            }
+        };
+        Ok(tx_hash)
+    }
+
+    /// Sell a token for native BNB/ETH in one transaction
+    ///
+    /// Mirrors `buy_token_with_native`: `swapExactTokensForETH` on V2, or on V3 the
+    /// `swap_v3_native` Smart Router multicall of `exactInputSingle` into the router followed
+    /// by `unwrapWETH9` to the wallet. The router must already be approved to spend
+    /// `amount_in`.
+    ///
+    /// # Example
+    /// ```
+    /// use ethers::types::U256;
+    ///
+    /// let amount_in = U256::from(1000000000000000000u64); // 1 CAKE
+    /// let tx_hash = service.sell_token_for_native(cake, amount_in, 1.0).await?;
+    /// ```
+    pub async fn sell_token_for_native(
+        &self,
+        token_in: Address,
+        amount_in: U256,
+        slippage_percent: f64,
+    ) -> PancakeResult<ethers::types::H256> {
+        if self.evm.client.wallet.is_none() {
+            return Err(EvmError::WalletError("No wallet configured".to_string()));
+        }
+        self.ensure_contracts(&[token_in]).await?;
+        self.ensure_transferable(token_in).await?;
+        let recipient = self.resolve_recipient(None)?;
+        let chain = self.evm.client.evm_type.unwrap();
+        let wrapped_native = self.wrapped_native().await?;
+        let comparison = self
+            .get_best_price(token_in, wrapped_native, amount_in, None)
+            .await?;
+        let deadline = self.swap_deadline(30).await?;
+
+        let tx_hash = match comparison.best {
+            crate::types::PriceSource::V2 => {
+                let expected_out = comparison.v2.map(|info| info.amount_out).ok_or_else(|| {
+                    EvmError::CalculationError("V2 price not available".to_string())
+                })?;
+                let amount_out_min =
+                    self.calculate_amount_with_slippage(expected_out, slippage_percent);
+                let router = self
+                    .router
+                    .v2_router_signer(PancakeSwapConfig::v2_router_address(chain)?)?;
+                let tx = router.swap_exact_tokens_for_eth(
+                    amount_in,
+                    amount_out_min,
+                    vec![token_in, wrapped_native],
+                    recipient,
+                    deadline.into(),
+                );
+                let tx = self.apply_gas_limit(tx).await?;
+                self.throttle().await;
+                self.send_transaction(tx, "execute V2 swap").await?
+            }
+            crate::types::PriceSource::V3 => {
+                let expected_out = comparison.v3.map(|info| info.amount_out).ok_or_else(|| {
+                    EvmError::CalculationError("V3 price not available".to_string())
+                })?;
+                let fee = comparison.fee_tier.ok_or_else(|| {
+                    EvmError::CalculationError("V3 fee tier not available".to_string())
+                })?;
+                let amount_out_min =
+                    self.calculate_amount_with_slippage(expected_out, slippage_percent);
+                self.send_native_v3_swap(
+                    token_in,
+                    wrapped_native,
+                    fee,
+                    amount_in,
+                    amount_out_min,
+                    false,
+                    recipient,
+                    deadline,
+                )
+                .await?
+            }
+            // The wrapped native token is never a stablecoin, so no StableSwap quote is taken
+            crate::types::PriceSource::Stable => {
+                return Err(EvmError::CalculationError(
+                    "StableSwap has no native token pools".to_string(),
+                ));
+            }
+        };
+        Ok(tx_hash)
+    }
+
+    /// Auto swap - find best price between V2 and V3 and execute
+    ///
+    /// # Example
+    /// ```
+    /// use pancake_swap_sdk::{PancakeSwapService, EvmClient, EvmType};
+    /// use ethers::types::{Address, U256};
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(),()> {
+    ///     let private_key = "your_private_key_here";
+    ///     let client = EvmClient::with_wallet(EvmType::Bsc, private_key).await?;
+    ///     let service = PancakeSwapService::new(std::sync::Arc::new(client));
+    ///     
+    ///     let token_in: Address = "0xbb4CdB9CBd36B01bD1cBaEBF2De08d9173bc095c".parse()?; // WBNB
+    ///     let token_out: Address = "0xe9e7CEA3DedcA5984780Bafc599bD69ADd087D56".parse()?; // BUSD
+    ///     let amount_in = U256::from(1000000000000000000u64); // 1 BNB
+    ///     let slippage_percent = 1.0; // 1% slippage
+    ///     
+    ///     let result = service.auto_swap(token_in, token_out, amount_in, slippage_percent).await?;
+    ///     println!("Auto swap result: {:?}", result);
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn auto_swap(
+        &self,
+        token_in: Address,
+        token_out: Address,
+        amount_in: U256,
+        slippage_percent: f64,
+    ) -> PancakeResult<crate::types::AutoSwapResult> {
+        self.swap(
+            token_in,
+            token_out,
+            amount_in,
+            slippage_percent,
+            crate::types::RoutePreference::Auto,
+        )
+        .await
+    }
+
+    /// Swap through the pool versions allowed by `preference`
+    ///
+    /// `Auto` behaves like `auto_swap`. `V2Only` and `V3Only` quote and swap on that version
+    /// alone, failing with `EvmError::CalculationError` when it has no liquidity for the pair.
+    /// Under `Auto`, stablecoin pairs route through their StableSwap pool when it quotes best
+    /// and the wallet has already approved the pool (see `StableSwapService::get_pool`) to
+    /// spend `amount_in`; otherwise the better of V2 and V3 is used.
+    ///
+    /// # Example
+    /// ```
+    /// use ethers::types::U256;
+    /// use crate::types::RoutePreference;
+    ///
+    /// let amount_in = U256::from(1000000000000000000u64);
+    /// let result = service
+    ///     .swap(wbnb, busd, amount_in, 1.0, RoutePreference::V3Only)
+    ///     .await?;
+    /// ```
+    pub async fn swap(
+        &self,
+        token_in: Address,
+        token_out: Address,
+        amount_in: U256,
+        slippage_percent: f64,
+        preference: crate::types::RoutePreference,
+    ) -> PancakeResult<crate::types::AutoSwapResult> {
+        let mut price_comparison = match preference {
+            crate::types::RoutePreference::Auto => {
+                self.get_best_price(token_in, token_out, amount_in, None)
+                    .await?
+            }
+            crate::types::RoutePreference::V2Only => {
+                let v2_info = self
+                    .get_v2_price(token_in, token_out, amount_in, None)
+                    .await
+                    .map_err(|e| {
+                        EvmError::CalculationError(format!("No V2 liquidity for pair: {}", e))
+                    })?;
+                crate::types::PriceComparison {
+                    v2: Some(v2_info),
+                    v3: None,
+                    stable: None,
+                    best: crate::types::PriceSource::V2,
+                    fee_tier: None,
+                }
+            }
+            crate::types::RoutePreference::V3Only => {
+                let (v3_info, fee) = self
+                    .get_v3_price(token_in, token_out, amount_in, None)
+                    .await
+                    .map_err(|e| {
+                        EvmError::CalculationError(format!("No V3 liquidity for pair: {}", e))
+                    })?;
+                crate::types::PriceComparison {
+                    v2: None,
+                    v3: Some(v3_info),
+                    stable: None,
+                    best: crate::types::PriceSource::V3,
+                    fee_tier: Some(fee),
+                }
+            }
+        };
+
+        if matches!(price_comparison.best, crate::types::PriceSource::Stable)
+            && !self
+                .stable_pool_approved(token_in, token_out, amount_in)
+                .await?
+        {
+            // Callers approve the router, not the pool; fall back to the best router venue
+            price_comparison.best = match (&price_comparison.v2, &price_comparison.v3) {
+                (Some(v2), Some(v3)) if v2.amount_out > v3.amount_out => {
+                    crate::types::PriceSource::V2
+                }
+                (_, Some(_)) => crate::types::PriceSource::V3,
+                (Some(_), None) => crate::types::PriceSource::V2,
+                (None, None) => {
+                    return Err(EvmError::CalculationError(
+                        "StableSwap pool not approved and no V2/V3 price available".to_string(),
+                    ));
+                }
+            };
+        }
+        let price_comparison_clone = price_comparison.clone();
+
+        let (selected_version, amount_out_min, tx_hash) = match price_comparison.best {
             crate::types::PriceSource::V2 => {
                 let v2_info = price_comparison.v2.ok_or_else(|| {
                     EvmError::CalculationError("V2 price not available".to_string())
@@ -293,7 +1814,7 @@ impl PancakeSwapService {
                 let amount_out_min =
                     self.calculate_amount_with_slippage(v2_info.amount_out, slippage_percent);
                 let tx_hash = self
-                    .swap_v2(token_in, token_out, amount_in, slippage_percent)
+                    .swap_v2(token_in, token_out, amount_in, slippage_percent, None)
                     .await?;
                 (crate::types::PoolVersion::V2, amount_out_min, tx_hash)
             }
@@ -303,47 +1824,622 @@ impl PancakeSwapService {
                 })?;
                 let amount_out_min =
                     self.calculate_amount_with_slippage(v3_info.amount_out, slippage_percent);
-                let fee = self.get_default_fee_tier(token_in, token_out);
+                let fee = match price_comparison.fee_tier {
+                    Some(fee) => FeeTier::try_from(fee)?,
+                    None => self.get_default_fee_tier(token_in, token_out),
+                };
                 let tx_hash = self
-                    .swap_v3(token_in, token_out, amount_in, slippage_percent, Some(fee))
+                    .swap_v3(
+                        token_in,
+                        token_out,
+                        amount_in,
+                        slippage_percent,
+                        Some(fee),
+                        None,
+                    )
                     .await?;
                 (crate::types::PoolVersion::V3, amount_out_min, tx_hash)
             }
+            crate::types::PriceSource::Stable => {
+                let stable_info = price_comparison.stable.ok_or_else(|| {
+                    EvmError::CalculationError("StableSwap price not available".to_string())
+                })?;
+                let amount_out_min =
+                    self.calculate_amount_with_slippage(stable_info.amount_out, slippage_percent);
+                self.ensure_contracts(&[token_in, token_out]).await?;
+                self.ensure_transferable(token_in).await?;
+                self.throttle().await;
+                let tx = self
+                    .observe(
+                        "exchange_call",
+                        self.stable_swap.exchange_call(
+                            token_in,
+                            token_out,
+                            amount_in,
+                            amount_out_min,
+                        ),
+                    )
+                    .await?;
+                let tx = self.apply_gas_limit(tx).await?;
+                self.throttle().await;
+                let tx_hash = self.send_transaction(tx, "execute StableSwap swap").await?;
+                (crate::types::PoolVersion::Stable, amount_out_min, tx_hash)
+            }
+        };
+
+        Ok(crate::types::AutoSwapResult {
+            tx_hash,
+            version: selected_version,
+            expected_amount_out: amount_out_min,
+            price_comparison: price_comparison_clone,
+        })
+    }
+
+    /// Execute V3 swap and wait until it has the given number of confirmations
+    ///
+    /// # Example
+    /// ```
+    /// use std::time::Duration;
+    ///
+    /// let result = service
+    ///     .swap_v3_confirmed(token_in, token_out, amount_in, 1.0, Some(FeeTier::Low), 1, Duration::from_secs(60))
+    ///     .await?;
+    /// println!("Filled at {}", result.average_price);
+    /// ```
+    #[allow(clippy::too_many_arguments)]
+    pub async fn swap_v3_confirmed(
+        &self,
+        token_in: Address,
+        token_out: Address,
+        amount_in: U256,
+        slippage_percent: f64,
+        fee_tier: Option<FeeTier>,
+        confirmations: u64,
+        timeout: std::time::Duration,
+    ) -> PancakeResult<crate::types::SwapResult> {
+        let tx_hash = self
+            .swap_v3(
+                token_in,
+                token_out,
+                amount_in,
+                slippage_percent,
+                fee_tier,
+                None,
+            )
+            .await?;
+        let receipt = self
+            .wait_for_receipt(tx_hash, confirmations, timeout)
+            .await?;
+        self.swap_result_from_receipt(&receipt, vec![token_in, token_out])
+            .await
+    }
+
+    /// Wait for a transaction receipt with the given number of confirmations, up to `timeout`
+    ///
+    /// # Example
+    /// ```
+    /// use std::time::Duration;
+    ///
+    /// let tx_hash = service.swap_v2(token_in, token_out, amount_in, 1.0, None).await?;
+    /// let receipt = service.wait_for_receipt(tx_hash, 3, Duration::from_secs(120)).await?;
+    /// ```
+    pub async fn wait_for_receipt(
+        &self,
+        tx_hash: ethers::types::H256,
+        confirmations: u64,
+        timeout: std::time::Duration,
+    ) -> PancakeResult<ethers::types::TransactionReceipt> {
+        crate::tool::tx_utils::wait_for_receipt(
+            &self.evm.client.provider,
+            tx_hash,
+            confirmations,
+            timeout,
+        )
+        .await
+    }
+
+    /// Get best price comparison between V2, V3 and, for stablecoin pairs, StableSwap
+    ///
+    /// When both tokens are known stablecoins of the chain, the StableSwap pool is quoted
+    /// as well and wins with `PriceSource::Stable` if it gives the most output. All venues
+    /// are quoted at `block` when given, otherwise at the latest block.
+    pub async fn get_best_price(
+        &self,
+        token_in: Address,
+        token_out: Address,
+        amount_in: U256,
+        block: Option<BlockNumber>,
+    ) -> PancakeResult<crate::types::PriceComparison> {
+        let v2_price = self
+            .get_v2_price(token_in, token_out, amount_in, block)
+            .await;
+        let v3_price = self
+            .get_v3_price(token_in, token_out, amount_in, block)
+            .await;
+        let fee_tier = v3_price.as_ref().ok().map(|(_, fee)| *fee);
+        let v3_price = v3_price.map(|(price_info, _)| price_info);
+        let stablecoins = stablecoin_addresses(self.evm.client.evm_type);
+        let stable_price = if stablecoins.contains(&token_in) && stablecoins.contains(&token_out) {
+            self.get_stable_price(token_in, token_out, amount_in, block)
+                .await
+                .ok()
+        } else {
+            None
+        };
+        let mut best_price = match (&v2_price, &v3_price) {
+            (Ok(v2), Ok(v3)) => {
+                if v2.amount_out > v3.amount_out {
+                    Some((crate::types::PriceSource::V2, v2.amount_out))
+                } else {
+                    Some((crate::types::PriceSource::V3, v3.amount_out))
+                }
+            }
+            (Ok(v2), Err(_)) => Some((crate::types::PriceSource::V2, v2.amount_out)),
+            (Err(_), Ok(v3)) => Some((crate::types::PriceSource::V3, v3.amount_out)),
+            _ => None,
+        };
+        if let Some(stable) = &stable_price
+            && best_price
+                .as_ref()
+                .is_none_or(|(_, amount_out)| stable.amount_out > *amount_out)
+        {
+            best_price = Some((crate::types::PriceSource::Stable, stable.amount_out));
+        }
+        let (best_price, _) = best_price
+            .ok_or_else(|| EvmError::CalculationError("No price available".to_string()))?;
+        Ok(crate::types::PriceComparison {
+            v2: v2_price.ok(),
+            v3: v3_price.ok(),
+            stable: stable_price,
+            best: best_price,
+            fee_tier,
+        })
+    }
+
+    /// Summarize a trade before sending it: venue, route, expected and minimum output,
+    /// price impact, gas and effective price
+    ///
+    /// Price impact compares the execution price with the spot price, quoted on the same
+    /// venue for a thousandth of `amount_in`. Gas is estimated against the router when a
+    /// wallet is configured (this fails without an allowance) and otherwise falls back to
+    /// typical single-hop swap costs.
+    ///
+    /// # Example
+    /// ```
+    /// use ethers::types::U256;
+    ///
+    /// let amount_in = U256::from(1000000000000000000u64);
+    /// let preview = service.get_trade_preview(wbnb, busd, amount_in, 50).await?; // 0.5%
+    /// println!(
+    ///     "{:?}: {} out (min {}), impact {:.2}%",
+    ///     preview.best_version, preview.expected_out, preview.min_out, preview.price_impact_pct
+    /// );
+    /// ```
+    pub async fn get_trade_preview(
+        &self,
+        token_in: Address,
+        token_out: Address,
+        amount_in: U256,
+        slippage_bps: u32,
+    ) -> PancakeResult<TradePreview> {
+        if amount_in.is_zero() {
+            return Err(EvmError::InvalidInput(
+                "Amount in must be greater than zero".to_string(),
+            ));
+        }
+        if slippage_bps > 10_000 {
+            return Err(EvmError::InvalidInput(
+                "Slippage must not exceed 10000 bps".to_string(),
+            ));
+        }
+        let comparison = self
+            .get_best_price(token_in, token_out, amount_in, None)
+            .await?;
+        let (best_version, expected_out, fee_tier) = match comparison.best {
+            crate::types::PriceSource::V2 => (
+                PoolVersion::V2,
+                comparison.v2.map(|info| info.amount_out),
+                None,
+            ),
+            crate::types::PriceSource::V3 => (
+                PoolVersion::V3,
+                comparison.v3.map(|info| info.amount_out),
+                comparison.fee_tier,
+            ),
+            crate::types::PriceSource::Stable => (
+                PoolVersion::Stable,
+                comparison.stable.map(|info| info.amount_out),
+                None,
+            ),
+        };
+        let expected_out = expected_out
+            .ok_or_else(|| EvmError::CalculationError("No price available".to_string()))?;
+        let min_out = expected_out * U256::from(10_000 - slippage_bps) / U256::from(10_000);
+        let route = vec![token_in, token_out];
+        let effective_price = u256_to_f64(expected_out, 0) / u256_to_f64(amount_in, 0);
+
+        let reference_in = (amount_in / 1000).max(U256::one());
+        let reference_out = match (&best_version, fee_tier) {
+            (PoolVersion::Stable, _) => self
+                .observe(
+                    "get_amount_out",
+                    self.stable_swap
+                        .get_amount_out(token_in, token_out, reference_in, None),
+                )
+                .await
+                .ok(),
+            (_, Some(fee)) => self
+                .simulate_v3_swap(token_in, token_out, fee, reference_in, None)
+                .await
+                .ok(),
+            (_, None) => self
+                .get_amounts_out_v2(reference_in, route.clone(), None)
+                .await
+                .ok()
+                .and_then(|amounts| amounts.last().copied()),
+        };
+        let price_impact_pct = match reference_out {
+            Some(reference_out) if !reference_out.is_zero() => {
+                let spot_price = u256_to_f64(reference_out, 0) / u256_to_f64(reference_in, 0);
+                ((spot_price - effective_price) / spot_price * 100.0).max(0.0)
+            }
+            _ => 0.0,
         };
 
-        Ok(crate::types::AutoSwapResult {
-            tx_hash,
-            version: selected_version,
-            expected_amount_out: amount_out_min,
-            price_comparison: price_comparison_clone,
+        let chain = self.evm.client.evm_type.unwrap();
+        let deadline = U256::from(crate::tool::time_utils::calculate_deadline(30));
+        let estimated_gas = match (&self.evm.client.wallet, fee_tier) {
+            // StableSwap goes through the pool rather than a router; use the typical cost
+            _ if matches!(best_version, PoolVersion::Stable) => None,
+            (Some(wallet), None) => self
+                .observe(
+                    "eth_estimateGas",
+                    self.router
+                        .v2_router(PancakeSwapConfig::v2_router_address(chain)?)
+                        .swap_exact_tokens_for_tokens(
+                            amount_in,
+                            min_out,
+                            route.clone(),
+                            wallet.address(),
+                            deadline,
+                        )
+                        .from(wallet.address())
+                        .estimate_gas(),
+                )
+                .await
+                .ok(),
+            (Some(wallet), Some(fee)) => self
+                .observe(
+                    "eth_estimateGas",
+                    self.router
+                        .v3_router(PancakeSwapConfig::v3_router_address(chain)?)
+                        .exact_input_single(
+                            token_in,
+                            token_out,
+                            fee,
+                            wallet.address(),
+                            deadline,
+                            amount_in,
+                            min_out,
+                            U256::zero(),
+                        )
+                        .from(wallet.address())
+                        .estimate_gas(),
+                )
+                .await
+                .ok(),
+            (None, _) => None,
+        };
+        let gas_estimate = estimated_gas.unwrap_or_else(|| match best_version {
+            PoolVersion::V3 => U256::from(V3_SWAP_GAS_ESTIMATE),
+            _ => U256::from(V2_SWAP_GAS_ESTIMATE),
+        });
+
+        Ok(TradePreview {
+            best_version,
+            fee_tier,
+            route,
+            expected_out,
+            min_out,
+            price_impact_pct,
+            gas_estimate,
+            effective_price,
         })
     }
 
-    /// Get best price comparison between V2 and V3
-    pub async fn get_best_price(
+    /// Estimate the gas units of a direct V2 swap
+    ///
+    /// The swap is estimated from the wallet against current state; without a wallet, or
+    /// when estimation fails (missing allowance or balance), the typical
+    /// `V2_SWAP_GAS_ESTIMATE` is returned.
+    ///
+    /// # Example
+    /// ```
+    /// use ethers::types::U256;
+    ///
+    /// let amount_in = U256::from(1000000000000000000u64);
+    /// let gas = service.estimate_swap_v2_gas(cake, wbnb, amount_in).await?;
+    /// ```
+    pub async fn estimate_swap_v2_gas(
         &self,
         token_in: Address,
         token_out: Address,
         amount_in: U256,
-    ) -> Result<crate::types::PriceComparison, EvmError> {
-        let v2_price = self.get_v2_price(token_in, token_out, amount_in).await;
-        let v3_price = self.get_v3_price(token_in, token_out, amount_in).await;
-        let best_price = match (&v2_price, &v3_price) {
-            (Ok(v2), Ok(v3)) => {
-                if v2.amount_out > v3.amount_out {
-                    crate::types::PriceSource::V2
-                } else {
-                    crate::types::PriceSource::V3
+    ) -> PancakeResult<U256> {
+        let Some(wallet) = &self.evm.client.wallet else {
+            return Ok(U256::from(V2_SWAP_GAS_ESTIMATE));
+        };
+        let router_address =
+            PancakeSwapConfig::v2_router_address(self.evm.client.evm_type.unwrap())?;
+        let deadline = U256::from(crate::tool::time_utils::calculate_deadline(30));
+        self.throttle().await;
+        let tx = self
+            .router
+            .v2_router(router_address)
+            .swap_exact_tokens_for_tokens(
+                amount_in,
+                U256::zero(),
+                vec![token_in, token_out],
+                wallet.address(),
+                deadline,
+            )
+            .from(wallet.address());
+        let estimated_gas = self
+            .observe("eth_estimateGas", tx.estimate_gas())
+            .await
+            .ok();
+        Ok(estimated_gas.unwrap_or_else(|| U256::from(V2_SWAP_GAS_ESTIMATE)))
+    }
+
+    /// Check whether a V2 swap's expected output is worth more than its gas
+    ///
+    /// Gas is `estimate_swap_v2_gas` at the current gas price. The output is valued by
+    /// quoting it into the wrapped native token and pricing that at `native_price_usd`, so
+    /// tokens without a V2 route to the native token fail with an error. Meant for dust
+    /// cleanup, where a swap can cost more than it returns.
+    ///
+    /// # Example
+    /// ```
+    /// let bnb_price_usd = 600.0;
+    /// for (token, balance) in dust {
+    ///     if service.is_swap_worthwhile(token, busd, balance, bnb_price_usd).await? {
+    ///         service.swap_v2(token, busd, balance, 1.0, None).await?;
+    ///     }
+    /// }
+    /// ```
+    pub async fn is_swap_worthwhile(
+        &self,
+        token_in: Address,
+        token_out: Address,
+        amount_in: U256,
+        native_price_usd: f64,
+    ) -> PancakeResult<bool> {
+        let amounts = self
+            .get_amounts_out_v2(amount_in, vec![token_in, token_out], None)
+            .await?;
+        let expected_out = *amounts
+            .last()
+            .ok_or_else(|| EvmError::CalculationError("Invalid path".to_string()))?;
+        let gas_units = self
+            .estimate_swap_v2_gas(token_in, token_out, amount_in)
+            .await?;
+        self.throttle().await;
+        let gas_price = self
+            .observe("eth_gasPrice", self.evm.client.provider.get_gas_price())
+            .await
+            .map_err(|e| EvmError::ProviderError(format!("Failed to get gas price: {}", e)))?;
+        let gas_cost_usd = u256_to_f64(gas_units.saturating_mul(gas_price), 18) * native_price_usd;
+
+        // Native tokens have 18 decimals on every supported chain
+        let wrapped_native = self.wrapped_native().await?;
+        let output_native = if token_out == wrapped_native {
+            expected_out
+        } else {
+            let amounts = self
+                .get_amounts_out_v2(expected_out, vec![token_out, wrapped_native], None)
+                .await?;
+            *amounts
+                .last()
+                .ok_or_else(|| EvmError::CalculationError("Invalid path".to_string()))?
+        };
+        let output_usd = u256_to_f64(output_native, 18) * native_price_usd;
+        Ok(output_usd > gas_cost_usd)
+    }
+
+    /// Compute the minimum acceptable output for a full V2 route
+    ///
+    /// Quotes every hop of `path` and applies `slippage_bps` (1 bps = 0.01%) to the final
+    /// output, so multi-hop swaps are protected end to end rather than per pair.
+    ///
+    /// # Example
+    /// ```
+    /// use ethers::types::U256;
+    ///
+    /// let path = vec![cake, wbnb, busd];
+    /// let amount_in = U256::from(1000000000000000000u64);
+    /// let amount_out_min = service.compute_min_out_for_route(path, amount_in, 50).await?; // 0.5%
+    /// ```
+    pub async fn compute_min_out_for_route(
+        &self,
+        path: Vec<Address>,
+        amount_in: U256,
+        slippage_bps: u32,
+    ) -> PancakeResult<U256> {
+        if path.len() < 2 {
+            return Err(EvmError::InvalidInput(
+                "Path must contain at least 2 tokens".to_string(),
+            ));
+        }
+        if slippage_bps > 10_000 {
+            return Err(EvmError::InvalidInput(
+                "Slippage must not exceed 10000 bps".to_string(),
+            ));
+        }
+        let amounts = self.get_amounts_out_v2(amount_in, path, None).await?;
+        let expected_out = amounts
+            .last()
+            .ok_or_else(|| EvmError::CalculationError("Invalid path".to_string()))?;
+        Ok(*expected_out * U256::from(10_000 - slippage_bps) / U256::from(10_000))
+    }
+
+    /// Quote the input needed to receive exactly `amount_out` at the end of a V2 route
+    ///
+    /// The returned `RouteInfo` holds `path` and the router's `getAmountsIn` amounts, one per
+    /// token: `amounts[0]` is the required input and the last entry is `amount_out`. The gas
+    /// estimate is a rough `V2_SWAP_GAS_ESTIMATE` per hop. When the route cannot be quoted,
+    /// the hops are quoted one by one from the output backwards, and the error names the
+    /// first hop without enough liquidity.
+    ///
+    /// # Example
+    /// ```
+    /// use ethers::types::U256;
+    ///
+    /// let path = vec![cake, wbnb, busd];
+    /// let amount_out = U256::from(100000000000000000000u128); // 100 BUSD
+    /// let route = service.quote_exact_out_route(path, amount_out).await?;
+    /// println!("Needs {} CAKE", route.amounts[0]);
+    /// ```
+    pub async fn quote_exact_out_route(
+        &self,
+        path: Vec<Address>,
+        amount_out: U256,
+    ) -> PancakeResult<RouteInfo> {
+        if path.len() < 2 {
+            return Err(EvmError::InvalidInput(
+                "Path must contain at least 2 tokens".to_string(),
+            ));
+        }
+        if path.iter().any(|token| token.is_zero()) {
+            return Err(EvmError::InvalidInput(
+                "Path must not contain the zero address".to_string(),
+            ));
+        }
+        if path.windows(2).any(|hop| hop[0] == hop[1]) {
+            return Err(EvmError::InvalidInput(
+                "Path must not swap a token for itself".to_string(),
+            ));
+        }
+        if amount_out.is_zero() {
+            return Err(EvmError::InvalidInput(
+                "Amount out must be greater than zero".to_string(),
+            ));
+        }
+        let amounts = match self.get_amounts_in_v2(amount_out, path.clone()).await {
+            Ok(amounts) => amounts,
+            Err(e) => {
+                // Walk back from the output to find the hop that cannot be filled
+                let mut hop_out = amount_out;
+                for hop in path.windows(2).rev() {
+                    let hop_amounts = self
+                        .get_amounts_in_v2(hop_out, hop.to_vec())
+                        .await
+                        .map_err(|e| {
+                            EvmError::CalculationError(format!(
+                                "Insufficient liquidity for hop {:?} -> {:?}: {}",
+                                hop[0], hop[1], e
+                            ))
+                        })?;
+                    hop_out = hop_amounts[0];
                 }
+                return Err(e);
             }
-            (Ok(_), Err(_)) => crate::types::PriceSource::V2,
-            (Err(_), Ok(_)) => crate::types::PriceSource::V3,
-            _ => return Err(EvmError::CalculationError("No price available".to_string())),
         };
-        Ok(crate::types::PriceComparison {
-            v2: v2_price.ok(),
-            v3: v3_price.ok(),
-            best: best_price,
+        let hops = path.len() as u64 - 1;
+        Ok(RouteInfo {
+            path,
+            amounts,
+            gas_estimate: U256::from(V2_SWAP_GAS_ESTIMATE * hops),
+        })
+    }
+
+    /// Swap exact tokens along a multi-hop V2 route, deriving `amount_out_min` from the route quote
+    ///
+    /// # Example
+    /// ```
+    /// use ethers::types::U256;
+    ///
+    /// let path = vec![cake, wbnb, busd];
+    /// let amount_in = U256::from(1000000000000000000u64);
+    /// let tx_hash = service.swap_v2_route(path, amount_in, 50).await?;
+    /// ```
+    pub async fn swap_v2_route(
+        &self,
+        path: Vec<Address>,
+        amount_in: U256,
+        slippage_bps: u32,
+    ) -> PancakeResult<ethers::types::H256> {
+        let amount_out_min = self
+            .compute_min_out_for_route(path.clone(), amount_in, slippage_bps)
+            .await?;
+        let deadline = self.swap_deadline(30).await?;
+        self.swap_exact_tokens_for_tokens(amount_in, amount_out_min, path, deadline)
+            .await
+    }
+
+    /// Check whether a token can be moved by `wallet` and whether it appears to tax transfers
+    ///
+    /// Transferability is a static zero-value transfer from the wallet to itself, which
+    /// reverts for paused tokens and blacklisted holders; tokens such as USDT whose
+    /// `transfer` returns nothing count as transferable when the call succeeds. Tax is a heuristic read from common
+    /// fee getters (`totalFees()`, `sellTax()`, ...); values up to 100 are taken as percent and
+    /// up to 10000 as bps, and `estimated_tax_bps` is `None` when no getter answers.
+    ///
+    /// # Example
+    /// ```
+    /// let preflight = service.preflight_token(token, wallet_address).await?;
+    /// if !preflight.transferable {
+    ///     println!("Transfers are blocked for this wallet");
+    /// }
+    /// ```
+    pub async fn preflight_token(
+        &self,
+        token: Address,
+        wallet: Address,
+    ) -> PancakeResult<crate::types::TokenPreflight> {
+        const TAX_GETTERS: [&str; 8] = [
+            "totalFees()",
+            "totalFee()",
+            "_taxFee()",
+            "taxFee()",
+            "sellTax()",
+            "_sellTax()",
+            "buyTax()",
+            "_buyTax()",
+        ];
+
+        let transferable = self.is_transferable(token, wallet).await?;
+
+        let mut estimated_tax_bps: Option<u32> = None;
+        for getter in TAX_GETTERS {
+            let tx = ethers::types::TransactionRequest::new()
+                .to(token)
+                .data(ethers::utils::id(getter).to_vec());
+            self.throttle().await;
+            let tx = tx.into();
+            let Ok(output) = self
+                .observe("eth_call", self.evm.client.provider.call(&tx, None))
+                .await
+            else {
+                continue;
+            };
+            if output.len() < 32 {
+                continue;
+            }
+            let value = U256::from_big_endian(&output[..32]);
+            let tax_bps = if value <= U256::from(100) {
+                value.as_u32() * 100
+            } else if value <= U256::from(10_000) {
+                value.as_u32()
+            } else {
+                continue;
+            };
+            estimated_tax_bps = Some(estimated_tax_bps.map_or(tax_bps, |bps| bps.max(tax_bps)));
+        }
+
+        Ok(crate::types::TokenPreflight {
+            transferable,
+            likely_fee_on_transfer: estimated_tax_bps.is_some_and(|bps| bps > 0),
+            estimated_tax_bps,
         })
     }
 
@@ -354,10 +2450,14 @@ impl PancakeSwapService {
         amount_out_min: U256,
         path: Vec<Address>,
         deadline: u64,
-    ) -> Result<ethers::types::H256, EvmError> {
+    ) -> PancakeResult<ethers::types::H256> {
         if self.evm.client.wallet.is_none() {
             return Err(EvmError::WalletError("No wallet configured".to_string()));
         }
+        self.ensure_contracts(&path).await?;
+        if let Some(token_in) = path.first() {
+            self.ensure_transferable(*token_in).await?;
+        }
         let router_address =
             PancakeSwapConfig::v2_router_address(self.evm.client.evm_type.unwrap())?;
         let wallet_address = self.evm.client.wallet.as_ref().unwrap().address();
@@ -369,11 +2469,9 @@ impl PancakeSwapService {
             wallet_address,
             deadline.into(),
         );
-        let pending_tx = tx
-            .send()
-            .await
-            .map_err(|e| EvmError::TransactionError(format!("Failed to swap tokens: {}", e)))?;
-        Ok(pending_tx.tx_hash())
+        let tx = self.apply_gas_limit(tx).await?;
+        self.throttle().await;
+        self.send_transaction(tx, "swap tokens").await
     }
 
     /// Get V2 price  
@@ -382,9 +2480,10 @@ impl PancakeSwapService {
         token_in: Address,
         token_out: Address,
         amount_in: U256,
-    ) -> Result<PriceInfo, EvmError> {
+        block: Option<BlockNumber>,
+    ) -> PancakeResult<PriceInfo> {
         let amounts = self
-            .get_amounts_out_v2(amount_in, vec![token_in, token_out])
+            .get_amounts_out_v2(amount_in, vec![token_in, token_out], block)
             .await?;
         let amount_out = amounts
             .last()
@@ -395,22 +2494,48 @@ impl PancakeSwapService {
             token_out,
             amount_in,
             amount_out: *amount_out,
-            price: amount_out.as_u128() as f64 / amount_in.as_u128() as f64,
+            price: u256_to_f64(*amount_out, 0) / u256_to_f64(amount_in, 0),
             price_impact: 0.0,
-            timestamp: crate::tool::time_utils::current_timestamp() as u64,
+            timestamp: crate::tool::time_utils::current_timestamp(),
         })
     }
 
-    /// Get V3 price  
-    async fn get_v3_price(
+    /// Whether the wallet has approved the pair's StableSwap pool to spend `amount_in`
+    async fn stable_pool_approved(
+        &self,
+        token_in: Address,
+        token_out: Address,
+        amount_in: U256,
+    ) -> PancakeResult<bool> {
+        let wallet = self
+            .evm
+            .client
+            .wallet
+            .as_ref()
+            .ok_or_else(|| EvmError::WalletError("No wallet configured".to_string()))?;
+        self.throttle().await;
+        self.observe(
+            "allowance",
+            self.stable_swap
+                .is_approved(token_in, token_out, wallet.address(), amount_in),
+        )
+        .await
+    }
+
+    /// Get StableSwap price for a pair with a StableSwap pool
+    async fn get_stable_price(
         &self,
         token_in: Address,
         token_out: Address,
         amount_in: U256,
-    ) -> Result<PriceInfo, EvmError> {
-        let fee = self.get_default_fee_tier(token_in, token_out);
+        block: Option<BlockNumber>,
+    ) -> PancakeResult<PriceInfo> {
         let amount_out = self
-            .simulate_v3_swap(token_in, token_out, fee, amount_in)
+            .observe(
+                "get_amount_out",
+                self.stable_swap
+                    .get_amount_out(token_in, token_out, amount_in, block),
+            )
             .await?;
 
         Ok(PriceInfo {
@@ -418,66 +2543,436 @@ impl PancakeSwapService {
             token_out,
             amount_in,
             amount_out,
-            price: amount_out.as_u128() as f64 / amount_in.as_u128() as f64,
+            price: u256_to_f64(amount_out, 0) / u256_to_f64(amount_in, 0),
             price_impact: 0.0,
-            timestamp: crate::tool::time_utils::current_timestamp() as u64,
+            timestamp: crate::tool::time_utils::current_timestamp(),
+        })
+    }
+
+    /// Get V3 price from the best fee tier, returned alongside that tier
+    async fn get_v3_price(
+        &self,
+        token_in: Address,
+        token_out: Address,
+        amount_in: U256,
+        block: Option<BlockNumber>,
+    ) -> PancakeResult<(PriceInfo, u32)> {
+        let (fee, amount_out) = self
+            .find_best_fee_tier(token_in, token_out, amount_in, block)
+            .await?;
+
+        Ok((
+            PriceInfo {
+                token_in,
+                token_out,
+                amount_in,
+                amount_out,
+                price: u256_to_f64(amount_out, 0) / u256_to_f64(amount_in, 0),
+                price_impact: 0.0,
+                timestamp: crate::tool::time_utils::current_timestamp(),
+            },
+            fee,
+        ))
+    }
+
+    /// Quote every standard V3 fee tier and return the tier with the highest output
+    async fn find_best_fee_tier(
+        &self,
+        token_in: Address,
+        token_out: Address,
+        amount_in: U256,
+        block: Option<BlockNumber>,
+    ) -> PancakeResult<(u32, U256)> {
+        let mut best: Option<(u32, U256)> = None;
+        for fee in FeeTier::ALL.map(FeeTier::as_u32) {
+            // Tiers without a pool simply fail to quote
+            if let Ok(amount_out) = self
+                .simulate_v3_swap(token_in, token_out, fee, amount_in, block)
+                .await
+                && best.is_none_or(|(_, best_out)| amount_out > best_out)
+            {
+                best = Some((fee, amount_out));
+            }
+        }
+        best.ok_or_else(|| {
+            EvmError::CalculationError("No V3 pool available for any fee tier".to_string())
         })
     }
 
-    /// Simulate V3 swap to get expected output by querying the actual Quoter contract
+    /// Simulate V3 swap to get expected output by querying the actual Quoter contract,
+    /// at `block` or the latest block
     async fn simulate_v3_swap(
         &self,
         token_in: Address,
         token_out: Address,
         fee: u32,
         amount_in: U256,
-    ) -> Result<U256, EvmError> {
+        block: Option<BlockNumber>,
+    ) -> PancakeResult<U256> {
         use ethers::prelude::*;
         // Get Quoter contract address
-        let quoter_address = match self.evm.client.evm_type {
-            Some(EvmType::BSC_MAINNET) => BSC_QUOTER
-                .parse::<Address>()
-                .map_err(|e| EvmError::ConfigError(format!("Invalid BSC quoter address: {}", e)))?,
-            Some(EvmType::ETHEREUM_MAINNET) => ETHEREUM_QUOTER.parse::<Address>().map_err(|e| {
-                EvmError::ConfigError(format!("Invalid Ethereum quoter address: {}", e))
-            })?,
-            Some(EvmType::BASE_MAINNET) => BASE_QUOTER.parse::<Address>().map_err(|e| {
-                EvmError::ConfigError(format!("Invalid Ethereum quoter address: {}", e))
-            })?,
-            _ => {
-                return Err(EvmError::ConfigError(
-                    "Unsupported chain for V3 Quoter".to_string(),
-                ));
-            }
-        };
+        let quoter_address = self
+            .evm
+            .client
+            .evm_type
+            .ok_or_else(|| EvmError::ConfigError("Unsupported chain for V3 Quoter".to_string()))
+            .and_then(PancakeSwapConfig::quoter_address)?;
         // Create Quoter contract instance
         let quoter = IQuoter::new(quoter_address, self.evm.client.provider.clone());
-        let amount_out = quoter
-            .quote_exact_input_single(token_in, token_out, fee.into(), amount_in, U256::zero())
-            .call()
+        let mut call =
+            quoter.quote_exact_input_single(token_in, token_out, fee, amount_in, U256::zero());
+        if let Some(block) = block {
+            call = call.block(block);
+        }
+        self.throttle().await;
+        match self.observe("quoteExactInputSingle", call.call()).await {
+            Ok(amount_out) => Ok(amount_out),
+            // Some quoter deployments return the quote in the revert payload
+            Err(e) => e
+                .as_revert()
+                .and_then(|data| Self::decode_quoter_revert(data))
+                .ok_or_else(|| EvmError::ContractError(format!("Failed to quote V3 swap: {}", e))),
+        }
+    }
+
+    /// Simulate a multi-hop V3 swap along an encoded path through the Quoter
+    async fn simulate_v3_path(&self, path: Bytes, amount_in: U256) -> PancakeResult<U256> {
+        let quoter_address = self
+            .evm
+            .client
+            .evm_type
+            .ok_or_else(|| EvmError::ConfigError("Unsupported chain for V3 Quoter".to_string()))
+            .and_then(PancakeSwapConfig::quoter_address)?;
+        let quoter = IQuoter::new(quoter_address, self.evm.client.provider.clone());
+        self.throttle().await;
+        match self
+            .observe(
+                "quoteExactInput",
+                quoter.quote_exact_input(path, amount_in).call(),
+            )
+            .await
+        {
+            Ok(amount_out) => Ok(amount_out),
+            // Some quoter deployments return the quote in the revert payload
+            Err(e) => e
+                .as_revert()
+                .and_then(|data| Self::decode_quoter_revert(data))
+                .ok_or_else(|| EvmError::ContractError(format!("Failed to quote V3 swap: {}", e))),
+        }
+    }
+
+    /// Decode the amount out from a quoter revert payload
+    fn decode_quoter_revert(data: &[u8]) -> Option<U256> {
+        // Error(string) means a genuine revert, not an encoded quote
+        if data.len() >= 4 && data[0..4] == [0x08, 0xc3, 0x79, 0xa0] {
+            return None;
+        }
+        if data.len() < 32 || !data.len().is_multiple_of(32) {
+            return None;
+        }
+        Some(U256::from_big_endian(&data[0..32]))
+    }
+
+    /// Reject token addresses without deployed code when token validation is enabled
+    async fn ensure_contracts(&self, tokens: &[Address]) -> PancakeResult<()> {
+        if !self.validate_tokens {
+            return Ok(());
+        }
+        for token in tokens {
+            self.throttle().await;
+            let is_contract = self
+                .observe(
+                    "eth_getCode",
+                    crate::tool::address_utils::is_contract(&self.evm.client.provider, *token),
+                )
+                .await?;
+            if !is_contract {
+                return Err(EvmError::InvalidInput(format!(
+                    "not a contract: {:?}",
+                    token
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Build a `SwapResult` from a mined swap, summing the wallet's `Transfer`s of the path ends
+    async fn swap_result_from_receipt(
+        &self,
+        receipt: &ethers::types::TransactionReceipt,
+        path: Vec<Address>,
+    ) -> PancakeResult<crate::types::SwapResult> {
+        let (Some(&token_in), Some(&token_out)) = (path.first(), path.last()) else {
+            return Err(EvmError::InvalidInput("Path must not be empty".to_string()));
+        };
+        let wallet_address = self
+            .evm
+            .client
+            .wallet
+            .as_ref()
+            .ok_or_else(|| EvmError::WalletError("No wallet configured".to_string()))?
+            .address();
+        let transfer_topic = ethers::types::H256::from(ethers::utils::keccak256(
+            "Transfer(address,address,uint256)",
+        ));
+        let mut input_amount = U256::zero();
+        let mut output_amount = U256::zero();
+        for log in &receipt.logs {
+            if log.topics.first() != Some(&transfer_topic) {
+                continue;
+            }
+            let Ok(transfer) = crate::tool::event_parsers::parse_transfer_log(log) else {
+                continue;
+            };
+            if log.address == token_in && transfer.from == wallet_address {
+                input_amount += transfer.value;
+            }
+            if log.address == token_out && transfer.to == wallet_address {
+                output_amount += transfer.value;
+            }
+        }
+
+        let mut decimals = Vec::with_capacity(2);
+        for token in [token_in, token_out] {
+            decimals.push(self.get_token_decimals(token).await? as u32);
+        }
+        let to_units = |amount: U256, decimals: u32| -> f64 {
+            ethers::utils::format_units(amount, decimals)
+                .ok()
+                .and_then(|units| units.parse::<f64>().ok())
+                .unwrap_or(0.0)
+        };
+        let input_units = to_units(input_amount, decimals[0]);
+        let average_price = if input_units > 0.0 {
+            to_units(output_amount, decimals[1]) / input_units
+        } else {
+            0.0
+        };
+
+        Ok(crate::types::SwapResult {
+            input_token: token_in,
+            output_token: token_out,
+            input_amount,
+            output_amount,
+            path,
+            tx_hash: receipt.transaction_hash,
+            gas_used: receipt.gas_used.unwrap_or_default(),
+            gas_price: receipt.effective_gas_price.unwrap_or_default(),
+            average_price,
+        })
+    }
+
+    /// Resolve a swap recipient, defaulting to the wallet address and rejecting the zero address
+    fn resolve_recipient(&self, recipient: Option<Address>) -> PancakeResult<Address> {
+        match recipient {
+            Some(address) if address.is_zero() => Err(EvmError::InvalidInput(
+                "Recipient must not be the zero address".to_string(),
+            )),
+            Some(address) => Ok(address),
+            None => self
+                .evm
+                .client
+                .wallet
+                .as_ref()
+                .map(|wallet| wallet.address())
+                .ok_or_else(|| EvmError::WalletError("No wallet configured".to_string())),
+        }
+    }
+
+    /// Reject tokens whose transfers appear blocked for the wallet, when preflight is enabled
+    ///
+    /// Only the transfer check of `preflight_token` runs, so each swap costs one extra call
+    /// rather than one per tax getter.
+    async fn ensure_transferable(&self, token: Address) -> PancakeResult<()> {
+        if !self.preflight_tokens {
+            return Ok(());
+        }
+        let Some(wallet) = self.evm.client.wallet.as_ref() else {
+            return Ok(());
+        };
+        if !self.is_transferable(token, wallet.address()).await? {
+            return Err(EvmError::InvalidInput(format!(
+                "token transfers appear blocked: {:?}",
+                token
+            )));
+        }
+        Ok(())
+    }
+
+    /// Whether a static zero-value transfer from `wallet` to itself succeeds
+    ///
+    /// Tokens that return nothing from `transfer` (e.g. USDT) pass when the call does not
+    /// revert; tokens that return a bool must return `true`.
+    async fn is_transferable(&self, token: Address, wallet: Address) -> PancakeResult<bool> {
+        let call_data = crate::abi::IERC20::new(token, self.evm.client.provider.clone())
+            .transfer(wallet, U256::zero())
+            .calldata()
+            .ok_or_else(|| EvmError::ContractError("Failed to encode transfer".to_string()))?;
+        let tx = ethers::types::TransactionRequest::new()
+            .from(wallet)
+            .to(token)
+            .data(call_data)
+            .into();
+        self.throttle().await;
+        Ok(
+            match self
+                .observe("transfer", self.evm.client.provider.call(&tx, None))
+                .await
+            {
+                Ok(output) if output.is_empty() => true,
+                Ok(output) => output.len() >= 32 && !U256::from_big_endian(&output[..32]).is_zero(),
+                Err(_) => false,
+            },
+        )
+    }
+
+    /// Swap deadline `minutes` from now, by chain time when chain deadlines are enabled
+    async fn swap_deadline(&self, minutes: u64) -> PancakeResult<u64> {
+        if self.chain_deadlines {
+            self.calculate_deadline_from_chain(minutes).await
+        } else {
+            Ok(crate::tool::time_utils::calculate_deadline(minutes))
+        }
+    }
+
+    /// Wrapped native token (WBNB/WETH): the override, the chain's known address, or as
+    /// reported by the chain's V2 router
+    async fn wrapped_native(&self) -> PancakeResult<Address> {
+        if let Some(weth) = self.weth_override {
+            return Ok(weth);
+        }
+        if let Some(weth) = self
+            .evm
+            .client
+            .evm_type
+            .and_then(|chain| PancakeSwapConfig::weth_address(chain).ok())
+        {
+            return Ok(weth);
+        }
+        let router_address =
+            PancakeSwapConfig::v2_router_address(self.evm.client.evm_type.unwrap())?;
+        self.observe("WETH", self.router.v2_router(router_address).weth().call())
+            .await
+            .map_err(|e| {
+                EvmError::ContractError(format!("Failed to get wrapped native token: {}", e))
+            })
+    }
+
+    /// Set the gas limit of a swap transaction to its estimate times the configured multiplier
+    async fn apply_gas_limit<M: Middleware, D: Detokenize>(
+        &self,
+        tx: ContractCall<M, D>,
+    ) -> PancakeResult<ContractCall<M, D>> {
+        if self.gas_limit_multiplier <= 1.0 {
+            return Ok(tx);
+        }
+        self.throttle().await;
+        let estimate = self
+            .observe("eth_estimateGas", tx.estimate_gas())
             .await
-            .map_err(|e| EvmError::ContractError(format!("Failed to quote V3 swap: {}", e)))?;
-        Ok(amount_out)
+            .map_err(|e| EvmError::TransactionError(format!("Failed to estimate gas: {}", e)))?;
+        let gas_limit = u256_to_f64(estimate, 0) * self.gas_limit_multiplier;
+        Ok(tx.gas(U256::from(gas_limit.ceil() as u128)))
+    }
+
+    /// Fail when the configured TWAP guard finds the V3 pool's spot price too far off its TWAP
+    async fn check_twap_guard(
+        &self,
+        token_in: Address,
+        token_out: Address,
+        fee: u32,
+    ) -> PancakeResult<()> {
+        let Some((window_secs, max_deviation_percent)) = self.twap_guard else {
+            return Ok(());
+        };
+        let pool_address = self
+            .observe(
+                "getPool",
+                self.factory.get_v3_pool(token_in, token_out, fee),
+            )
+            .await?
+            .ok_or_else(|| EvmError::CalculationError("V3 pool not found".to_string()))?;
+        let deviation = self
+            .observe(
+                "get_v3_twap_deviation",
+                self.price.get_v3_twap_deviation(pool_address, window_secs),
+            )
+            .await?;
+        if deviation > max_deviation_percent {
+            return Err(EvmError::CalculationError(format!(
+                "spot deviates from TWAP by {:.2}%",
+                deviation
+            )));
+        }
+        Ok(())
+    }
+
+    /// Send a transaction through the configured `TransactionSender`, or through the
+    /// contract's own middleware when none is set
+    ///
+    /// `action` completes the "Failed to ..." message of middleware errors.
+    async fn send_transaction<M: Middleware, D: Detokenize>(
+        &self,
+        tx: ContractCall<M, D>,
+        action: &str,
+    ) -> PancakeResult<ethers::types::H256> {
+        match &self.transaction_sender {
+            Some(sender) => {
+                let mut typed_tx = tx.tx;
+                if typed_tx.from().is_none()
+                    && let Some(wallet) = &self.evm.client.wallet
+                {
+                    typed_tx.set_from(wallet.address());
+                }
+                self.observe("eth_sendTransaction", sender.send(typed_tx))
+                    .await
+            }
+            None => self
+                .observe("eth_sendTransaction", tx.send())
+                .await
+                .map(|pending_tx| pending_tx.tx_hash())
+                .map_err(|e| EvmError::TransactionError(format!("Failed to {}: {}", action, e))),
+        }
+    }
+
+    /// Await `call`, reporting it as `method` to the call observer when one is set
+    async fn observe<T, E>(
+        &self,
+        method: &str,
+        call: impl std::future::Future<Output = Result<T, E>>,
+    ) -> Result<T, E> {
+        let Some(observer) = &self.call_observer else {
+            return call.await;
+        };
+        let started = Instant::now();
+        let result = call.await;
+        observer.on_call(method, started.elapsed(), result.is_ok());
+        result
+    }
+
+    /// Wait for a request permit when a rate limit is configured
+    async fn throttle(&self) {
+        RateLimiter::acquire_opt(&self.rate_limiter).await;
     }
 
     /// Calculate amount with slippage
     fn calculate_amount_with_slippage(&self, amount: U256, slippage_percent: f64) -> U256 {
         let slippage_factor = (100.0 - slippage_percent) / 100.0;
-        let amount_f64 = amount.as_u128() as f64 * slippage_factor;
+        let amount_f64 = u256_to_f64(amount, 0) * slippage_factor;
         U256::from(amount_f64 as u128)
     }
 
     /// Get default fee tier based on token pair
-    fn get_default_fee_tier(&self, token_a: Address, token_b: Address) -> u32 {
+    fn get_default_fee_tier(&self, token_a: Address, token_b: Address) -> FeeTier {
         // Simple logic: use lower fees for stablecoin pairs
         let stable_tokens = [
             PancakeSwapConfig::busd_address(self.evm.client.evm_type.unwrap()).unwrap_or_default(),
             PancakeSwapConfig::usdt_address(self.evm.client.evm_type.unwrap()).unwrap_or_default(),
         ];
         if stable_tokens.contains(&token_a) && stable_tokens.contains(&token_b) {
-            100 // 0.01% for stable pairs
+            FeeTier::Lowest
         } else {
-            500 // 0.05% for other pairs
+            FeeTier::Low
         }
     }
 }
@@ -486,53 +2981,170 @@ impl PancakeSwapService {
 pub struct PancakeSwapConfig;
 
 impl PancakeSwapConfig {
-    pub fn v2_router_address(chain: EvmType) -> Result<Address, EvmError> {
-        match chain {
-            EvmType::BSC_MAINNET => Ok(BSC_ROUTER_V2.parse().unwrap()),
-            EvmType::ETHEREUM_MAINNET => Ok(ETHEREUM_ROUTER_V2.parse().unwrap()),
-            EvmType::BASE_MAINNET => Ok(BSC_ROUTER_V2.parse().unwrap()),
-            _ => Err(EvmError::ConfigError(
-                "Unsupported chain for PancakeSwap V2".to_string(),
-            )),
-        }
+    pub fn v2_router_address(chain: EvmType) -> PancakeResult<Address> {
+        chain_address(
+            chain,
+            "PancakeSwap V2",
+            &[
+                (EvmType::BSC_MAINNET, BSC_ROUTER_V2),
+                (EvmType::ETHEREUM_MAINNET, ETHEREUM_ROUTER_V2),
+                (EvmType::BASE_MAINNET, BSC_ROUTER_V2),
+            ],
+        )
     }
 
-    pub fn v3_router_address(chain: EvmType) -> Result<Address, EvmError> {
-        match chain {
-            EvmType::BSC_MAINNET => Ok(BSC_ROUTER_V3.parse().unwrap()),
-            EvmType::ETHEREUM_MAINNET => Ok(ETHEREUM_ROUTER_V3.parse().unwrap()),
-            EvmType::BASE_MAINNET => Ok(BASE_ROUTER_V3.parse().unwrap()),
-            _ => Err(EvmError::ConfigError(
-                "Unsupported chain for PancakeSwap V3".to_string(),
-            )),
-        }
+    pub fn v3_router_address(chain: EvmType) -> PancakeResult<Address> {
+        chain_address(
+            chain,
+            "PancakeSwap V3",
+            &[
+                (EvmType::BSC_MAINNET, BSC_ROUTER_V3),
+                (EvmType::ETHEREUM_MAINNET, ETHEREUM_ROUTER_V3),
+                (EvmType::BASE_MAINNET, BASE_ROUTER_V3),
+            ],
+        )
     }
 
-    pub fn busd_address(chain: EvmType) -> Result<Address, EvmError> {
-        match chain {
-            EvmType::BSC_MAINNET => Ok("0xe9e7CEA3DedcA5984780Bafc599bD69ADd087D56"
-                .parse()
-                .unwrap()),
-            EvmType::ETHEREUM_MAINNET => Ok("0x4Fabb145d64652a948d72533023f6E7A623C7C53"
-                .parse()
-                .unwrap()),
-            _ => Err(EvmError::ConfigError(
-                "Unsupported chain for BUSD".to_string(),
-            )),
+    /// V3 Quoter used to simulate swaps on `chain`
+    ///
+    /// PancakeSwap V3 is not deployed on Polygon PoS, so Polygon returns a `ConfigError`;
+    /// pass a Quoter explicitly (e.g. `V3RouteFinder::with_quoter`) to quote elsewhere.
+    pub fn quoter_address(chain: EvmType) -> PancakeResult<Address> {
+        if chain == EvmType::POLYGON_MAINNET {
+            return Err(EvmError::ConfigError(
+                "PancakeSwap V3 Quoter is not deployed on Polygon PoS".to_string(),
+            ));
         }
+        chain_address(
+            chain,
+            "V3 Quoter",
+            &[
+                (EvmType::BSC_MAINNET, BSC_QUOTER),
+                (EvmType::ETHEREUM_MAINNET, ETHEREUM_QUOTER),
+                (EvmType::BASE_MAINNET, BASE_QUOTER),
+                (EvmType::ARB_MAINNET, ARBITRUM_QUOTER),
+            ],
+        )
     }
 
-    pub fn usdt_address(chain: EvmType) -> Result<Address, EvmError> {
-        match chain {
-            EvmType::BSC_MAINNET => Ok("0x55d398326f99059fF775485246999027B3197955"
-                .parse()
-                .unwrap()),
-            EvmType::ETHEREUM_MAINNET => Ok("0xdAC17F958D2ee523a2206206994597C13D831ec7"
-                .parse()
-                .unwrap()),
-            _ => Err(EvmError::ConfigError(
-                "Unsupported chain for USDT".to_string(),
-            )),
+    /// StableSwap factory address; StableSwap pools are only deployed on BSC
+    pub fn stable_swap_factory_address(chain: EvmType) -> PancakeResult<Address> {
+        chain_address(
+            chain,
+            "StableSwap",
+            &[(EvmType::BSC_MAINNET, BSC_STABLE_SWAP_FACTORY)],
+        )
+    }
+
+    /// Wrapped native token of `chain` (WBNB on BSC, WMATIC on Polygon, WETH elsewhere)
+    pub fn weth_address(chain: EvmType) -> PancakeResult<Address> {
+        chain_address(
+            chain,
+            "wrapped native token",
+            &[
+                (EvmType::BSC_MAINNET, BSC_WBNB),
+                (EvmType::ETHEREUM_MAINNET, ETHEREUM_WETH),
+                (EvmType::BASE_MAINNET, BASE_WETH),
+                (EvmType::ARB_MAINNET, ARBITRUM_WETH),
+                (EvmType::POLYGON_MAINNET, POLYGON_WMATIC),
+            ],
+        )
+    }
+
+    pub fn cake_address(chain: EvmType) -> PancakeResult<Address> {
+        chain_address(
+            chain,
+            "CAKE",
+            &[
+                (EvmType::BSC_MAINNET, BSC_CAKE),
+                (EvmType::ETHEREUM_MAINNET, ETHEREUM_CAKE),
+                (EvmType::BASE_MAINNET, BASE_CAKE),
+            ],
+        )
+    }
+
+    pub fn busd_address(chain: EvmType) -> PancakeResult<Address> {
+        chain_address(
+            chain,
+            "BUSD",
+            &[
+                (
+                    EvmType::BSC_MAINNET,
+                    "0xe9e7CEA3DedcA5984780Bafc599bD69ADd087D56",
+                ),
+                (
+                    EvmType::ETHEREUM_MAINNET,
+                    "0x4Fabb145d64652a948d72533023f6E7A623C7C53",
+                ),
+            ],
+        )
+    }
+
+    pub fn usdt_address(chain: EvmType) -> PancakeResult<Address> {
+        chain_address(
+            chain,
+            "USDT",
+            &[
+                (
+                    EvmType::BSC_MAINNET,
+                    "0x55d398326f99059fF775485246999027B3197955",
+                ),
+                (
+                    EvmType::ETHEREUM_MAINNET,
+                    "0xdAC17F958D2ee523a2206206994597C13D831ec7",
+                ),
+            ],
+        )
+    }
+
+    /// USD stablecoin used to price tokens on `chain`: USDT, or USDC on Base
+    pub fn usd_stablecoin_address(chain: EvmType) -> PancakeResult<Address> {
+        chain_address(
+            chain,
+            "USD stablecoin",
+            &[
+                (
+                    EvmType::BSC_MAINNET,
+                    "0x55d398326f99059fF775485246999027B3197955",
+                ),
+                (
+                    EvmType::ETHEREUM_MAINNET,
+                    "0xdAC17F958D2ee523a2206206994597C13D831ec7",
+                ),
+                (
+                    EvmType::BASE_MAINNET,
+                    "0x833589fCD6eDb6E08f4c7C32D4f71b54bdA02913",
+                ),
+            ],
+        )
+    }
+}
+
+/// Address of `what` on `chain`, or a `ConfigError` listing the chains in `addresses`
+///
+/// e.g. "chain Plasma Mainnet unsupported for PancakeSwap V2; supported: BNB Smart Chain,
+/// Ethereum Mainnet, Base Mainnet"
+fn chain_address(
+    chain: EvmType,
+    what: &str,
+    addresses: &[(EvmType, &str)],
+) -> PancakeResult<Address> {
+    match addresses.iter().find(|(supported, _)| *supported == chain) {
+        Some((_, address)) => address
+            .parse()
+            .map_err(|_| EvmError::ConfigError(format!("Invalid {} address", what))),
+        None => {
+            let supported = addresses
+                .iter()
+                .map(|(supported, _)| supported.name())
+                .collect::<Vec<_>>()
+                .join(", ");
+            Err(EvmError::ConfigError(format!(
+                "chain {} unsupported for {}; supported: {}",
+                chain.name(),
+                what,
+                supported
+            )))
         }
     }
 }
@@ -1,13 +1,34 @@
-use crate::{PancakeSwapConfig, PancakeSwapService, price::PriceService};
-use ethers::types::{Address, U256};
+use crate::{
+    EvmError, PancakeResult, PancakeSwapConfig, PancakeSwapService,
+    abi::{IOrderReactor, IPermit2, i_order_reactor},
+    global::PANCAKE_PERMIT2,
+    logging::log_error,
+    price::PriceService,
+    tool::math_utils::u256_to_f64,
+};
+use async_trait::async_trait;
+use ethers::abi::{Token, encode};
+use ethers::middleware::SignerMiddleware;
+use ethers::signers::Signer;
+use ethers::types::{Address, Bytes, H256, U256};
+use ethers::utils::keccak256;
 use evm_sdk::Evm;
-use evm_sdk::types::EvmError;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::path::PathBuf;
 use std::sync::Arc;
 use tokio::time::{Duration, interval};
 
+/// EIP-712 type strings of a signed limit order, as hashed by the order reactor and Permit2
+const LIMIT_ORDER_TYPE: &str =
+    "LimitOrder(OrderInfo info,address inputToken,uint256 inputAmount,OutputToken[] outputs)";
+const ORDER_INFO_TYPE: &str = "OrderInfo(address reactor,address swapper,uint256 nonce,uint256 deadline,address additionalValidationContract,bytes additionalValidationData)";
+const OUTPUT_TOKEN_TYPE: &str = "OutputToken(address token,uint256 amount,address recipient)";
+const TOKEN_PERMISSIONS_TYPE: &str = "TokenPermissions(address token,uint256 amount)";
+const PERMIT_WITNESS_PREFIX: &str = "PermitWitnessTransferFrom(TokenPermissions permitted,address spender,uint256 nonce,uint256 deadline,";
+
 /// Represents the status of a limit order
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum OrderStatus {
     Pending,
     Filled,
@@ -16,7 +37,7 @@ pub enum OrderStatus {
 }
 
 /// Contains all information about a limit order
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LimitOrder {
     pub order_id: U256,
     pub token_in: Address,
@@ -32,10 +53,161 @@ pub struct LimitOrder {
     pub tx_hash: Option<ethers::types::H256>,
 }
 
+/// Parameters of an on-chain limit order signed with `LimitOrderService::sign_limit_order`
+///
+/// The order sells exactly `amount_in` of `token_in` for at least `amount_out_min` of
+/// `token_out`. `nonce` is a Permit2 unordered nonce: any value not used before by the
+/// wallet, which also identifies the order for `cancel_signed_order`.
+#[derive(Debug, Clone)]
+pub struct LimitOrderParams {
+    /// Order reactor that will settle the order
+    pub reactor: Address,
+    pub token_in: Address,
+    pub amount_in: U256,
+    pub token_out: Address,
+    pub amount_out_min: U256,
+    /// Receiver of `token_out`; the signing wallet when `None`
+    pub recipient: Option<Address>,
+    pub nonce: U256,
+    /// Unix timestamp after which the order can no longer be filled
+    pub deadline: u64,
+}
+
+/// A limit order signed by its swapper, ready to be filled through its reactor
+#[derive(Debug, Clone)]
+pub struct SignedOrder {
+    pub params: LimitOrderParams,
+    pub swapper: Address,
+    /// EIP-712 hash of the order, as emitted by the reactor when it is filled
+    pub order_hash: H256,
+    /// ABI-encoded `LimitOrder` struct
+    pub order: Bytes,
+    /// 65-byte Permit2 signature over the order
+    pub signature: Bytes,
+}
+
+/// Persistent storage for limit orders
+///
+/// `LimitOrderService::new_with_store` saves an order whenever it is created, filled or
+/// cancelled and reloads all orders on startup. Implement this for Redis, Postgres or
+/// any other backend; `FileOrderStore` keeps orders in a local JSON file.
+///
+/// # Example
+/// ```
+/// struct RedisOrderStore {
+///     client: redis::Client,
+/// }
+///
+/// #[async_trait]
+/// impl OrderStore for RedisOrderStore {
+///     async fn save(&self, order: &LimitOrder) -> Result<(), EvmError> {
+///         let json = serde_json::to_string(order).unwrap();
+///         // HSET limit_orders <order_id> <json>
+///         Ok(())
+///     }
+///     async fn load_all(&self) -> Result<Vec<LimitOrder>, EvmError> {
+///         // HVALS limit_orders
+///         Ok(Vec::new())
+///     }
+/// }
+/// ```
+#[async_trait]
+pub trait OrderStore: Send + Sync {
+    /// Inserts the order, or replaces the stored order with the same `order_id`
+    async fn save(&self, order: &LimitOrder) -> PancakeResult<()>;
+
+    /// Every stored order, in any status
+    async fn load_all(&self) -> PancakeResult<Vec<LimitOrder>>;
+}
+
+/// Stores limit orders as a JSON array in a single file
+///
+/// Each save rewrites the whole file through a temporary file and a rename, so a crash
+/// mid-write leaves the previous contents intact. A missing file loads as no orders.
+///
+/// # Example
+/// ```
+/// let store = Arc::new(FileOrderStore::new("orders.json"));
+/// let service = LimitOrderService::new_with_store(evm.clone(), store).await?;
+/// ```
+pub struct FileOrderStore {
+    path: PathBuf,
+    lock: tokio::sync::Mutex<()>,
+}
+
+impl FileOrderStore {
+    /// Creates a store backed by the file at `path`
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            lock: tokio::sync::Mutex::new(()),
+        }
+    }
+
+    async fn read_orders(&self) -> PancakeResult<Vec<LimitOrder>> {
+        let data = match tokio::fs::read(&self.path).await {
+            Ok(data) => data,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => {
+                return Err(EvmError::Error(format!(
+                    "Failed to read order file {}: {}",
+                    self.path.display(),
+                    e
+                )));
+            }
+        };
+        serde_json::from_slice(&data).map_err(|e| {
+            EvmError::Error(format!(
+                "Failed to parse order file {}: {}",
+                self.path.display(),
+                e
+            ))
+        })
+    }
+}
+
+#[async_trait]
+impl OrderStore for FileOrderStore {
+    async fn save(&self, order: &LimitOrder) -> PancakeResult<()> {
+        let _guard = self.lock.lock().await;
+        let mut orders = self.read_orders().await?;
+        match orders
+            .iter_mut()
+            .find(|stored| stored.order_id == order.order_id)
+        {
+            Some(stored) => *stored = order.clone(),
+            None => orders.push(order.clone()),
+        }
+        let data = serde_json::to_vec_pretty(&orders)
+            .map_err(|e| EvmError::Error(format!("Failed to serialize orders: {}", e)))?;
+        let tmp_path = self.path.with_extension("tmp");
+        tokio::fs::write(&tmp_path, data).await.map_err(|e| {
+            EvmError::Error(format!(
+                "Failed to write order file {}: {}",
+                tmp_path.display(),
+                e
+            ))
+        })?;
+        tokio::fs::rename(&tmp_path, &self.path).await.map_err(|e| {
+            EvmError::Error(format!(
+                "Failed to replace order file {}: {}",
+                self.path.display(),
+                e
+            ))
+        })
+    }
+
+    async fn load_all(&self) -> PancakeResult<Vec<LimitOrder>> {
+        let _guard = self.lock.lock().await;
+        self.read_orders().await
+    }
+}
+
 /// Service for managing and executing limit orders
 pub struct LimitOrderService {
     evm: Arc<Evm>,
     pending_orders: HashMap<U256, LimitOrder>,
+    store: Option<Arc<dyn OrderStore>>,
 }
 
 impl LimitOrderService {
@@ -44,6 +216,44 @@ impl LimitOrderService {
         Self {
             evm,
             pending_orders: HashMap::new(),
+            store: None,
+        }
+    }
+
+    /// Creates a LimitOrderService that persists orders to `store`
+    ///
+    /// All orders previously saved to the store are loaded, in whatever status they were
+    /// saved; pending ones are picked up again by `check_and_execute_orders`.
+    ///
+    /// # Example
+    /// ```rust
+    /// async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = Arc::new(EvmClient::new(EvmType::Bsc).await?);
+    /// let store = Arc::new(FileOrderStore::new("orders.json"));
+    /// let mut service = LimitOrderService::new_with_store(client, store).await?;
+    /// println!("Restored {} pending orders", service.get_pending_orders().len());
+    /// Ok(())
+    /// }
+    /// ```
+    pub async fn new_with_store(evm: Arc<Evm>, store: Arc<dyn OrderStore>) -> PancakeResult<Self> {
+        let pending_orders = store
+            .load_all()
+            .await?
+            .into_iter()
+            .map(|order| (order.order_id, order))
+            .collect();
+        Ok(Self {
+            evm,
+            pending_orders,
+            store: Some(store),
+        })
+    }
+
+    /// Saves an order to the store, if one is configured
+    async fn persist(&self, order_id: U256) -> PancakeResult<()> {
+        match (&self.store, self.pending_orders.get(&order_id)) {
+            (Some(store), Some(order)) => store.save(order).await,
+            _ => Ok(()),
         }
     }
 
@@ -92,7 +302,7 @@ impl LimitOrderService {
         limit_price: f64,
         expiry_minutes: u64,
         path: Option<Vec<Address>>,
-    ) -> Result<U256, EvmError> {
+    ) -> PancakeResult<U256> {
         let current_price = self
             .get_current_price(router_address, token_in, token_out, amount_in)
             .await?;
@@ -128,6 +338,7 @@ impl LimitOrderService {
             tx_hash: None,
         };
         self.pending_orders.insert(order_id, order.clone());
+        self.persist(order_id).await?;
         self.start_order_monitoring(order_id, router_address)
             .await?;
         Ok(order_id)
@@ -140,12 +351,12 @@ impl LimitOrderService {
         token_in: Address,
         token_out: Address,
         amount_in: U256,
-    ) -> Result<f64, EvmError> {
+    ) -> PancakeResult<f64> {
         let price_service = PriceService::new(self.evm.clone());
         let amount_out = price_service
             .get_price(router_address, token_in, token_out, amount_in)
             .await?;
-        let price = amount_out.as_u128() as f64 / amount_in.as_u128() as f64;
+        let price = u256_to_f64(amount_out, 0) / u256_to_f64(amount_in, 0);
         Ok(price)
     }
 
@@ -155,8 +366,8 @@ impl LimitOrderService {
         amount_in: U256,
         limit_price: f64,
         current_price: f64,
-    ) -> Result<U256, EvmError> {
-        let expected_amount_out = (amount_in.as_u128() as f64 * limit_price) as u128;
+    ) -> PancakeResult<U256> {
+        let expected_amount_out = (u256_to_f64(amount_in, 0) * limit_price) as u128;
         let amount_out_min = (expected_amount_out as f64 * 0.995) as u128; // 0.5% 滑点保护
         Ok(U256::from(amount_out_min))
     }
@@ -166,7 +377,7 @@ impl LimitOrderService {
         &mut self,
         order_id: U256,
         router_address: Address,
-    ) -> Result<(), EvmError> {
+    ) -> PancakeResult<()> {
         let client = self.evm.client.clone();
         let mut interval = interval(Duration::from_secs(10)); // 每10秒检查一次
         tokio::spawn(async move { todo!() });
@@ -192,7 +403,7 @@ impl LimitOrderService {
     pub async fn execute_limit_order(
         &mut self,
         order_id: U256,
-    ) -> Result<ethers::types::H256, EvmError> {
+    ) -> PancakeResult<ethers::types::H256> {
         let order = self
             .pending_orders
             .get(&order_id)
@@ -221,6 +432,10 @@ impl LimitOrderService {
             order.status = OrderStatus::Filled;
             order.tx_hash = Some(tx_hash);
         }
+        // The swap is already sent; report the hash even if the store is unavailable
+        if let Err(e) = self.persist(order_id).await {
+            log_error!(order_id = %order_id, error = %e, "Failed to persist filled order");
+        }
         Ok(tx_hash)
     }
 
@@ -235,16 +450,16 @@ impl LimitOrderService {
     /// let client = Arc::new(EvmClient::new(EvmType::Bsc).await?);
     /// let mut service = LimitOrderService::new(client);
     /// let order_id = U256::from(12345u64);
-    /// service.cancel_limit_order(order_id)?;
+    /// service.cancel_limit_order(order_id).await?;
     /// println!("Order cancelled successfully");
     /// Ok(())
     /// }
     /// ```
-    pub fn cancel_limit_order(&mut self, order_id: U256) -> Result<(), EvmError> {
+    pub async fn cancel_limit_order(&mut self, order_id: U256) -> PancakeResult<()> {
         if let Some(order) = self.pending_orders.get_mut(&order_id) {
             if order.status == OrderStatus::Pending {
                 order.status = OrderStatus::Cancelled;
-                Ok(())
+                self.persist(order_id).await
             } else {
                 Err(EvmError::Error(
                     "Cannot cancel non-pending order".to_string(),
@@ -289,7 +504,7 @@ impl LimitOrderService {
     /// Ok(())
     /// }
     /// ```
-    pub async fn check_and_execute_orders(&mut self) -> Result<Vec<ethers::types::H256>, EvmError> {
+    pub async fn check_and_execute_orders(&mut self) -> PancakeResult<Vec<ethers::types::H256>> {
         let mut executed_orders = Vec::new();
         let pending_orders: Vec<U256> = self
             .get_pending_orders()
@@ -301,7 +516,9 @@ impl LimitOrderService {
             if should_execute {
                 match self.execute_limit_order(order_id).await {
                     Ok(tx_hash) => executed_orders.push(tx_hash),
-                    Err(e) => eprintln!("Failed to execute order {}: {}", order_id, e),
+                    Err(e) => {
+                        log_error!(order_id = %order_id, error = %e, "Failed to execute order")
+                    }
                 }
             }
         }
@@ -309,7 +526,7 @@ impl LimitOrderService {
     }
 
     /// Determines if an order should be executed based on current market conditions
-    async fn should_execute_order(&self, order_id: U256) -> Result<bool, EvmError> {
+    async fn should_execute_order(&self, order_id: U256) -> PancakeResult<bool> {
         let order = self
             .pending_orders
             .get(&order_id)
@@ -324,4 +541,234 @@ impl LimitOrderService {
             .await?;
         Ok(current_price >= order.limit_price)
     }
+
+    /// Signs an on-chain limit order as a Permit2 `PermitWitnessTransferFrom` with the order
+    /// as witness, the format settled by PancakeSwap's order reactors
+    ///
+    /// Nothing is sent: the signature lets the reactor pull `amount_in` from the wallet once
+    /// the order is filled, so the wallet must have approved Permit2 for `token_in`. The
+    /// EIP-712 domain is Permit2's, `{ name: "Permit2", chainId, verifyingContract }` with
+    /// `verifyingContract` = `PANCAKE_PERMIT2` on every chain; only the chain id differs
+    /// (56 on BSC, 1 on Ethereum, 42161 on Arbitrum, 8453 on Base).
+    ///
+    /// # Params
+    /// order - Order to sign; its `reactor` is also the Permit2 spender
+    ///
+    /// # Example
+    /// ```rust
+    /// async fn example(service: LimitOrderService) -> Result<(), EvmError> {
+    /// let params = LimitOrderParams {
+    ///     reactor,
+    ///     token_in: wbnb,
+    ///     amount_in: U256::from(1_000_000_000_000_000_000u64), // 1 WBNB
+    ///     token_out: busd,
+    ///     amount_out_min: U256::from(320_000_000_000_000_000_000u128), // 320 BUSD
+    ///     recipient: None,
+    ///     nonce: U256::from(1u64),
+    ///     deadline: crate::tool::time_utils::calculate_deadline(24 * 60),
+    /// };
+    /// let signed = service.sign_limit_order(params)?;
+    /// println!("Order hash: {:?}", signed.order_hash);
+    /// Ok(())
+    /// }
+    /// ```
+    pub fn sign_limit_order(&self, order: LimitOrderParams) -> PancakeResult<SignedOrder> {
+        let wallet = self
+            .evm
+            .client
+            .wallet
+            .as_ref()
+            .ok_or_else(|| EvmError::WalletError("No wallet configured".to_string()))?;
+        if order.amount_in.is_zero() || order.amount_out_min.is_zero() {
+            return Err(EvmError::InvalidInput(
+                "Order amounts must be nonzero".to_string(),
+            ));
+        }
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        if order.deadline <= now {
+            return Err(EvmError::InvalidInput(
+                "Order deadline has passed".to_string(),
+            ));
+        }
+        let swapper = wallet.address();
+        let recipient = order.recipient.unwrap_or(swapper);
+        let deadline = U256::from(order.deadline);
+
+        let info_hash = keccak256(encode(&[
+            Token::FixedBytes(keccak256(ORDER_INFO_TYPE).to_vec()),
+            Token::Address(order.reactor),
+            Token::Address(swapper),
+            Token::Uint(order.nonce),
+            Token::Uint(deadline),
+            Token::Address(Address::zero()),
+            Token::FixedBytes(keccak256([]).to_vec()),
+        ]));
+        let output_hash = keccak256(encode(&[
+            Token::FixedBytes(keccak256(OUTPUT_TOKEN_TYPE).to_vec()),
+            Token::Address(order.token_out),
+            Token::Uint(order.amount_out_min),
+            Token::Address(recipient),
+        ]));
+        let limit_order_typehash = keccak256(format!(
+            "{}{}{}",
+            LIMIT_ORDER_TYPE, ORDER_INFO_TYPE, OUTPUT_TOKEN_TYPE
+        ));
+        let order_hash = keccak256(encode(&[
+            Token::FixedBytes(limit_order_typehash.to_vec()),
+            Token::FixedBytes(info_hash.to_vec()),
+            Token::Address(order.token_in),
+            Token::Uint(order.amount_in),
+            Token::FixedBytes(keccak256(output_hash).to_vec()),
+        ]));
+
+        let permit_typehash = keccak256(format!(
+            "{}LimitOrder witness){}{}{}{}",
+            PERMIT_WITNESS_PREFIX,
+            LIMIT_ORDER_TYPE,
+            ORDER_INFO_TYPE,
+            OUTPUT_TOKEN_TYPE,
+            TOKEN_PERMISSIONS_TYPE
+        ));
+        let token_permissions_hash = keccak256(encode(&[
+            Token::FixedBytes(keccak256(TOKEN_PERMISSIONS_TYPE).to_vec()),
+            Token::Address(order.token_in),
+            Token::Uint(order.amount_in),
+        ]));
+        let struct_hash = keccak256(encode(&[
+            Token::FixedBytes(permit_typehash.to_vec()),
+            Token::FixedBytes(token_permissions_hash.to_vec()),
+            Token::Address(order.reactor),
+            Token::Uint(order.nonce),
+            Token::Uint(deadline),
+            Token::FixedBytes(order_hash.to_vec()),
+        ]));
+        let mut message = Vec::with_capacity(66);
+        message.extend_from_slice(b"\x19\x01");
+        message.extend_from_slice(&Self::permit2_domain_separator(wallet.chain_id()));
+        message.extend_from_slice(&struct_hash);
+        let signature = wallet
+            .sign_hash(H256::from(keccak256(message)))
+            .map_err(|e| EvmError::WalletError(format!("Failed to sign limit order: {}", e)))?;
+
+        // abi.encode(LimitOrder), as decoded by the reactor
+        let encoded_order = encode(&[Token::Tuple(vec![
+            Token::Tuple(vec![
+                Token::Address(order.reactor),
+                Token::Address(swapper),
+                Token::Uint(order.nonce),
+                Token::Uint(deadline),
+                Token::Address(Address::zero()),
+                Token::Bytes(Vec::new()),
+            ]),
+            Token::Tuple(vec![
+                Token::Address(order.token_in),
+                Token::Uint(order.amount_in),
+                Token::Uint(order.amount_in),
+            ]),
+            Token::Array(vec![Token::Tuple(vec![
+                Token::Address(order.token_out),
+                Token::Uint(order.amount_out_min),
+                Token::Address(recipient),
+            ])]),
+        ])]);
+        Ok(SignedOrder {
+            params: order,
+            swapper,
+            order_hash: H256::from(order_hash),
+            order: Bytes::from(encoded_order),
+            signature: Bytes::from(signature.to_vec()),
+        })
+    }
+
+    /// Fills a signed order by calling its reactor's `execute`
+    ///
+    /// The sending wallet acts as the filler: the reactor pulls `amount_in` from the swapper
+    /// and transfers `amount_out_min` of `token_out` from the sender to the recipient, so the
+    /// sender must hold that amount and have approved the reactor for it. Orders meant for
+    /// PancakeSwap's off-chain fillers are posted to its order API instead, which this SDK
+    /// does not wrap.
+    ///
+    /// # Example
+    /// ```rust
+    /// async fn example(service: LimitOrderService, signed: SignedOrder) -> Result<(), EvmError> {
+    /// let tx_hash = service.submit_signed_order(&signed).await?;
+    /// println!("Order filled with tx: {:?}", tx_hash);
+    /// Ok(())
+    /// }
+    /// ```
+    pub async fn submit_signed_order(&self, signed: &SignedOrder) -> PancakeResult<H256> {
+        let wallet = self
+            .evm
+            .client
+            .wallet
+            .as_ref()
+            .ok_or_else(|| EvmError::WalletError("No wallet configured".to_string()))?;
+        let signer_middleware =
+            SignerMiddleware::new(self.evm.client.provider.clone(), wallet.clone());
+        let reactor = IOrderReactor::new(signed.params.reactor, Arc::new(signer_middleware));
+        let tx = reactor.execute(i_order_reactor::SignedOrder {
+            order: signed.order.clone(),
+            sig: signed.signature.clone(),
+        });
+        let pending_tx = tx.send().await.map_err(|e| {
+            EvmError::TransactionError(format!("Failed to submit signed order: {}", e))
+        })?;
+        Ok(pending_tx.tx_hash())
+    }
+
+    /// Cancels a signed order by spending its Permit2 nonce, so the reactor can no longer
+    /// pull the input tokens
+    ///
+    /// Must be sent by the order's swapper.
+    ///
+    /// # Example
+    /// ```rust
+    /// async fn example(service: LimitOrderService, signed: SignedOrder) -> Result<(), EvmError> {
+    /// let tx_hash = service.cancel_signed_order(&signed).await?;
+    /// println!("Order cancelled with tx: {:?}", tx_hash);
+    /// Ok(())
+    /// }
+    /// ```
+    pub async fn cancel_signed_order(&self, signed: &SignedOrder) -> PancakeResult<H256> {
+        let wallet = self
+            .evm
+            .client
+            .wallet
+            .as_ref()
+            .ok_or_else(|| EvmError::WalletError("No wallet configured".to_string()))?;
+        if wallet.address() != signed.swapper {
+            return Err(EvmError::WalletError(
+                "Only the order's swapper can cancel it".to_string(),
+            ));
+        }
+        let permit2_address: Address = PANCAKE_PERMIT2.parse().unwrap();
+        let signer_middleware =
+            SignerMiddleware::new(self.evm.client.provider.clone(), wallet.clone());
+        let permit2 = IPermit2::new(permit2_address, Arc::new(signer_middleware));
+        // Permit2 keeps 256 unordered nonces per bitmap word
+        let nonce = signed.params.nonce;
+        let word_pos = nonce >> 8;
+        let mask = U256::one() << (nonce.low_u32() & 0xff);
+        let tx = permit2.invalidate_unordered_nonces(word_pos, mask);
+        let pending_tx = tx.send().await.map_err(|e| {
+            EvmError::TransactionError(format!("Failed to cancel signed order: {}", e))
+        })?;
+        Ok(pending_tx.tx_hash())
+    }
+
+    /// EIP-712 domain separator of `PANCAKE_PERMIT2` on the given chain
+    pub fn permit2_domain_separator(chain_id: u64) -> [u8; 32] {
+        keccak256(encode(&[
+            Token::FixedBytes(
+                keccak256("EIP712Domain(string name,uint256 chainId,address verifyingContract)")
+                    .to_vec(),
+            ),
+            Token::FixedBytes(keccak256("Permit2").to_vec()),
+            Token::Uint(U256::from(chain_id)),
+            Token::Address(PANCAKE_PERMIT2.parse().unwrap()),
+        ]))
+    }
 }
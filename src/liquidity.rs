@@ -1,17 +1,40 @@
-use crate::EvmError;
-use ethers::types::{Address, U256};
+use crate::multicall::{Call, MulticallService};
+use crate::rate_limit::RateLimiter;
+use crate::tool::math_utils::u256_to_f64;
+use crate::{EvmError, PancakeResult};
+use ethers::abi::AbiDecode;
+use ethers::providers::Middleware;
+use ethers::types::{Address, BlockNumber, U256};
 use evm_sdk::Evm;
 use std::sync::Arc;
 
+/// LP tokens locked forever by the first mint of a V2 pair
+const MINIMUM_LIQUIDITY: u64 = 1000;
+
 /// Liquidity management service for DEX operations
 pub struct LiquidityService {
     evm: Arc<Evm>,
+    rate_limiter: Option<Arc<RateLimiter>>,
 }
 
 impl LiquidityService {
     /// create liquidity service
     pub fn new(evm: Arc<Evm>) -> Self {
-        Self { evm: evm }
+        Self {
+            evm,
+            rate_limiter: None,
+        }
+    }
+
+    /// Shares `rate_limiter` with this service, e.g. the one configured on `PancakeSwapService`
+    pub fn with_rate_limiter(mut self, rate_limiter: Arc<RateLimiter>) -> Self {
+        self.rate_limiter = Some(rate_limiter);
+        self
+    }
+
+    /// Waits for a request permit when a rate limiter is configured
+    async fn throttle(&self) {
+        RateLimiter::acquire_opt(&self.rate_limiter).await;
     }
 
     /// Retrieves the pair address for two tokens from a DEX factory
@@ -37,10 +60,11 @@ impl LiquidityService {
         factory_address: Address,
         token_a: Address,
         token_b: Address,
-    ) -> Result<Option<Address>, EvmError> {
+    ) -> PancakeResult<Option<Address>> {
         let factory =
             crate::abi::IPancakeFactory::new(factory_address, self.evm.client.provider.clone());
 
+        self.throttle().await;
         factory
             .get_pair(token_a, token_b)
             .call()
@@ -68,9 +92,10 @@ impl LiquidityService {
     /// Ok(())
     /// }
     /// ```
-    pub async fn get_reserves(&self, pair_address: Address) -> Result<(U256, U256, u32), EvmError> {
+    pub async fn get_reserves(&self, pair_address: Address) -> PancakeResult<(U256, U256, u32)> {
         let pair = crate::abi::IPancakePair::new(pair_address, self.evm.client.provider.clone());
 
+        self.throttle().await;
         let (reserve0, reserve1, block_timestamp_last) = pair
             .get_reserves()
             .call()
@@ -80,19 +105,94 @@ impl LiquidityService {
         Ok((reserve0.into(), reserve1.into(), block_timestamp_last))
     }
 
+    /// Gets the reserves of a liquidity pool as of a past block, for backtesting
+    ///
+    /// Nodes prune old state, so blocks beyond the last ~128 usually need an archive node;
+    /// otherwise an `EvmError::ProviderError` saying so is returned.
+    ///
+    /// # Example
+    /// ```
+    /// use ethers::types::Address;
+    /// use std::str::FromStr;
+    /// async fn example(service: LiquidityService) -> Result<(), EvmError> {
+    /// let pair_address = Address::from_str("0x1234...").unwrap();
+    /// let (reserve0, reserve1, timestamp) = service.get_reserves_at(pair_address, 30_000_000).await?;
+    /// println!("Reserves at block 30000000: {} and {}", reserve0, reserve1);
+    /// Ok(())
+    /// }
+    /// ```
+    pub async fn get_reserves_at(
+        &self,
+        pair_address: Address,
+        block: u64,
+    ) -> PancakeResult<(U256, U256, u32)> {
+        let pair = crate::abi::IPancakePair::new(pair_address, self.evm.client.provider.clone());
+        self.throttle().await;
+        let (reserve0, reserve1, block_timestamp_last) = pair
+            .get_reserves()
+            .block(block)
+            .call()
+            .await
+            .map_err(|e| historical_call_error("reserves", block, e))?;
+        Ok((reserve0.into(), reserve1.into(), block_timestamp_last))
+    }
+
+    /// Gets the reserves of a liquidity pool, rejecting them if the pair has not been updated recently
+    ///
+    /// `blockTimestampLast` is compared against the latest block timestamp; reserves older than
+    /// `max_age_secs` return `EvmError::CalculationError("stale reserves")`.
+    ///
+    /// # Example
+    /// ```
+    /// use ethers::types::Address;
+    /// use std::str::FromStr;
+    /// async fn example(service: LiquidityService) -> Result<(), EvmError> {
+    /// let pair_address = Address::from_str("0x1234...").unwrap();
+    /// let (reserve0, reserve1, timestamp) = service.get_reserves_fresh(pair_address, 3600).await?;
+    /// println!("Reserves: {} and {}", reserve0, reserve1);
+    /// Ok(())
+    /// }
+    /// ```
+    pub async fn get_reserves_fresh(
+        &self,
+        pair_address: Address,
+        max_age_secs: u64,
+    ) -> PancakeResult<(U256, U256, u32)> {
+        let (reserve0, reserve1, block_timestamp_last) = self.get_reserves(pair_address).await?;
+        self.throttle().await;
+        let latest_block = self
+            .evm
+            .client
+            .provider
+            .get_block(BlockNumber::Latest)
+            .await
+            .map_err(|e| EvmError::ConnectionError(format!("Failed to get latest block: {}", e)))?
+            .ok_or_else(|| EvmError::ConnectionError("Latest block not found".to_string()))?;
+        let age = latest_block
+            .timestamp
+            .as_u64()
+            .saturating_sub(block_timestamp_last as u64);
+        if age > max_age_secs {
+            return Err(EvmError::CalculationError("stale reserves".to_string()));
+        }
+        Ok((reserve0, reserve1, block_timestamp_last))
+    }
+
     /// Retrieves the token addresses of a liquidity pool
     pub async fn get_pair_tokens(
         &self,
         pair_address: Address,
-    ) -> Result<(Address, Address), EvmError> {
+    ) -> PancakeResult<(Address, Address)> {
         let pair = crate::abi::IPancakePair::new(pair_address, self.evm.client.provider.clone());
 
+        self.throttle().await;
         let token0 = pair
             .token_0()
             .call()
             .await
             .map_err(|e| EvmError::ContractError(format!("Failed to get token0: {}", e)))?;
 
+        self.throttle().await;
         let token1 = pair
             .token_1()
             .call()
@@ -102,13 +202,49 @@ impl LiquidityService {
         Ok((token0, token1))
     }
 
+    /// Gets the reserve of `token` in a pair, the depth against which slippage scales
+    ///
+    /// Use with `PriceService::get_price_impact` for the impact of a specific trade size.
+    ///
+    /// # Example
+    /// ```
+    /// use ethers::types::Address;
+    /// use std::str::FromStr;
+    /// async fn example(service: LiquidityService) -> Result<(), EvmError> {
+    /// let pair_address = Address::from_str("0x1234...").unwrap();
+    /// let wbnb = Address::from_str("0xbb4CdB9CBd36B01bD1cBaEBF2De08d9173bc095c").unwrap();
+    /// let depth = service.get_effective_liquidity(pair_address, wbnb).await?;
+    /// println!("WBNB depth: {}", depth);
+    /// Ok(())
+    /// }
+    /// ```
+    pub async fn get_effective_liquidity(
+        &self,
+        pair_address: Address,
+        token: Address,
+    ) -> PancakeResult<U256> {
+        let (token0, token1) = self.get_pair_tokens(pair_address).await?;
+        let (reserve0, reserve1, _) = self.get_reserves(pair_address).await?;
+        if token == token0 {
+            Ok(reserve0)
+        } else if token == token1 {
+            Ok(reserve1)
+        } else {
+            Err(EvmError::InvalidInput(format!(
+                "Token {:?} is not in pair {:?}",
+                token, pair_address
+            )))
+        }
+    }
+
     /// Gets a user liquidity balance in a pool
     pub async fn get_user_liquidity(
         &self,
         pair_address: Address,
         user_address: Address,
-    ) -> Result<U256, EvmError> {
+    ) -> PancakeResult<U256> {
         let pair = crate::abi::IPancakePair::new(pair_address, self.evm.client.provider.clone());
+        self.throttle().await;
         pair.balance_of(user_address)
             .call()
             .await
@@ -116,9 +252,10 @@ impl LiquidityService {
     }
 
     /// Gets the total supply of LP tokens for a pool
-    pub async fn get_total_supply(&self, pair_address: Address) -> Result<U256, EvmError> {
+    pub async fn get_total_supply(&self, pair_address: Address) -> PancakeResult<U256> {
         let pair = crate::abi::IPancakePair::new(pair_address, self.evm.client.provider.clone());
 
+        self.throttle().await;
         pair.total_supply()
             .call()
             .await
@@ -151,7 +288,7 @@ impl LiquidityService {
         liquidity_amount: U256,
         token_a_price: f64,
         token_b_price: f64,
-    ) -> Result<(f64, f64, f64), EvmError> {
+    ) -> PancakeResult<(f64, f64, f64)> {
         let total_supply = self.get_total_supply(pair_address).await?;
         let (reserve_a, reserve_b, _) = self.get_reserves(pair_address).await?;
 
@@ -162,13 +299,87 @@ impl LiquidityService {
         let user_token_a = (liquidity_amount * reserve_a) / total_supply;
         let user_token_b = (liquidity_amount * reserve_b) / total_supply;
 
-        let value_a = user_token_a.as_u128() as f64 * token_a_price;
-        let value_b = user_token_b.as_u128() as f64 * token_b_price;
+        let value_a = u256_to_f64(user_token_a, 0) * token_a_price;
+        let value_b = u256_to_f64(user_token_b, 0) * token_b_price;
         let total_value = value_a + value_b;
 
         Ok((value_a, value_b, total_value))
     }
 
+    /// Quotes the LP tokens minted for adding `amount0` / `amount1` of the pair's token0 /
+    /// token1, mirroring the pair's `mint`
+    ///
+    /// An existing pool mints `min(amount0 * supply / reserve0, amount1 * supply / reserve1)`;
+    /// the first deposit mints `sqrt(amount0 * amount1)` minus the permanently locked minimum
+    /// liquidity. Excess of either token over the pool ratio is donated to the pool, so quote
+    /// amounts from `get_reserves` to avoid losing it.
+    ///
+    /// # Example
+    /// ```
+    /// use ethers::types::{Address, U256};
+    /// use std::str::FromStr;
+    /// async fn example(service: LiquidityService) -> Result<(), EvmError> {
+    /// let pair_address = Address::from_str("0x1234...").unwrap();
+    /// let amount0 = U256::from(1000000000000000000u64);
+    /// let amount1 = U256::from(300000000000000000000u128);
+    ///
+    /// let lp_minted = service.quote_lp_minted(pair_address, amount0, amount1).await?;
+    /// println!("LP tokens: {}", lp_minted);
+    /// Ok(())
+    /// }
+    /// ```
+    pub async fn quote_lp_minted(
+        &self,
+        pair_address: Address,
+        amount0: U256,
+        amount1: U256,
+    ) -> PancakeResult<U256> {
+        let total_supply = self.get_total_supply(pair_address).await?;
+        let (reserve0, reserve1, _) = self.get_reserves(pair_address).await?;
+        lp_minted(amount0, amount1, reserve0, reserve1, total_supply)
+    }
+
+    /// Quotes the token0 / token1 amounts that `lp_amount` LP tokens represent, i.e. what
+    /// removing that liquidity would return
+    ///
+    /// Each side is `lp_amount * reserve / total_supply`, rounded down as in the pair's
+    /// `burn`. A pool without supply returns zeros; more LP tokens than the supply is
+    /// `EvmError::InvalidInput`.
+    ///
+    /// # Example
+    /// ```
+    /// use ethers::types::{Address, U256};
+    /// use std::str::FromStr;
+    /// async fn example(service: LiquidityService) -> Result<(), EvmError> {
+    /// let pair_address = Address::from_str("0x1234...").unwrap();
+    /// let lp_amount = service.get_user_liquidity(pair_address, user).await?;
+    ///
+    /// let (amount0, amount1) = service.get_lp_underlying(pair_address, lp_amount).await?;
+    /// println!("Withdraws {} token0 and {} token1", amount0, amount1);
+    /// Ok(())
+    /// }
+    /// ```
+    pub async fn get_lp_underlying(
+        &self,
+        pair_address: Address,
+        lp_amount: U256,
+    ) -> PancakeResult<(U256, U256)> {
+        let total_supply = self.get_total_supply(pair_address).await?;
+        let (reserve0, reserve1, _) = self.get_reserves(pair_address).await?;
+        if total_supply.is_zero() {
+            return Ok((U256::zero(), U256::zero()));
+        }
+        if lp_amount > total_supply {
+            return Err(EvmError::InvalidInput(
+                "LP amount exceeds total supply".to_string(),
+            ));
+        }
+        let overflow = || EvmError::CalculationError("Underlying amount overflows".to_string());
+        let amount0 = lp_amount.checked_mul(reserve0).ok_or_else(overflow)? / total_supply;
+        let amount1 = lp_amount.checked_mul(reserve1).ok_or_else(overflow)? / total_supply;
+        Ok((amount0, amount1))
+    }
+
     /// Retrieves multiple pairs from a factory contract
     ///
     /// # Example
@@ -187,10 +398,11 @@ impl LiquidityService {
         factory_address: Address,
         start_index: u64,
         count: u64,
-    ) -> Result<Vec<Address>, EvmError> {
+    ) -> PancakeResult<Vec<Address>> {
         let factory =
             crate::abi::IPancakeFactory::new(factory_address, self.evm.client.provider.clone());
 
+        self.throttle().await;
         let total_pairs =
             factory.all_pairs_length().call().await.map_err(|e| {
                 EvmError::ContractError(format!("Failed to get total pairs: {}", e))
@@ -200,6 +412,7 @@ impl LiquidityService {
         let mut pairs = Vec::new();
 
         for i in start_index..end_index {
+            self.throttle().await;
             let pair_address = factory.all_pairs(i.into()).call().await.map_err(|e| {
                 EvmError::ContractError(format!("Failed to get pair at index {}: {}", i, e))
             })?;
@@ -211,7 +424,15 @@ impl LiquidityService {
     }
 
     /// Gets comprehensive information about a liquidity pool
-    pub async fn get_pool_info(&self, pair_address: Address) -> Result<PoolInfo, EvmError> {
+    ///
+    /// Returns `EvmError::InvalidInput("pair does not exist")` when `pair_address` has no
+    /// code, e.g. a zero or mistyped address, instead of a generic contract error.
+    pub async fn get_pool_info(&self, pair_address: Address) -> PancakeResult<PoolInfo> {
+        self.throttle().await;
+        if !crate::tool::address_utils::is_contract(&self.evm.client.provider, pair_address).await?
+        {
+            return Err(EvmError::InvalidInput("pair does not exist".to_string()));
+        }
         let (token0, token1) = self.get_pair_tokens(pair_address).await?;
         let (reserve0, reserve1, block_timestamp_last) = self.get_reserves(pair_address).await?;
         let total_supply = self.get_total_supply(pair_address).await?;
@@ -225,6 +446,149 @@ impl LiquidityService {
             total_supply,
         })
     }
+
+    /// Gets pool information as of a past block; see `get_reserves_at` for node requirements
+    ///
+    /// # Example
+    /// ```
+    /// use ethers::types::Address;
+    /// use liquidity::LiquidityService;
+    /// async fn example(service: LiquidityService) -> Result<(), Box<dyn std::error::Error>> {
+    /// let pair_address = "0x...".parse()?;
+    /// let pool_info = service.get_pool_info_at(pair_address, 30_000_000).await?;
+    /// println!("Total supply then: {}", pool_info.total_supply);
+    /// Ok(())
+    /// }
+    /// ```
+    pub async fn get_pool_info_at(
+        &self,
+        pair_address: Address,
+        block: u64,
+    ) -> PancakeResult<PoolInfo> {
+        let pair = crate::abi::IPancakePair::new(pair_address, self.evm.client.provider.clone());
+        self.throttle().await;
+        let token0 = pair
+            .token_0()
+            .block(block)
+            .call()
+            .await
+            .map_err(|e| historical_call_error("token0", block, e))?;
+        self.throttle().await;
+        let token1 = pair
+            .token_1()
+            .block(block)
+            .call()
+            .await
+            .map_err(|e| historical_call_error("token1", block, e))?;
+        let (reserve0, reserve1, block_timestamp_last) =
+            self.get_reserves_at(pair_address, block).await?;
+        self.throttle().await;
+        let total_supply = pair
+            .total_supply()
+            .block(block)
+            .call()
+            .await
+            .map_err(|e| historical_call_error("total supply", block, e))?;
+        Ok(PoolInfo {
+            pair_address,
+            token0,
+            token1,
+            reserve0,
+            reserve1,
+            block_timestamp_last,
+            total_supply,
+        })
+    }
+
+    /// Gets pool information with a single multicall instead of one call per field
+    ///
+    /// `token0`, `token1`, `getReserves` and `totalSupply` are read in one aggregate call,
+    /// so all fields come from the same block.
+    ///
+    /// # Example
+    /// ```
+    /// use ethers::types::Address;
+    /// use liquidity::LiquidityService;
+    /// async fn example(service: LiquidityService) -> Result<(), Box<dyn std::error::Error>> {
+    /// let pair_address = "0x...".parse()?;
+    /// let multicall = "0x...".parse()?;
+    /// let pool_info = service.get_pool_info_multicall(pair_address, multicall).await?;
+    /// println!("Reserves: {} and {}", pool_info.reserve0, pool_info.reserve1);
+    /// Ok(())
+    /// }
+    /// ```
+    pub async fn get_pool_info_multicall(
+        &self,
+        pair_address: Address,
+        multicall_address: Address,
+    ) -> PancakeResult<PoolInfo> {
+        let pair = crate::abi::IPancakePair::new(pair_address, self.evm.client.provider.clone());
+        let mut calls = Vec::with_capacity(4);
+        for call_data in [
+            pair.token_0().calldata(),
+            pair.token_1().calldata(),
+            pair.get_reserves().calldata(),
+            pair.total_supply().calldata(),
+        ] {
+            let call_data = call_data
+                .ok_or_else(|| EvmError::ContractError("Failed to encode pair call".to_string()))?;
+            calls.push(Call::new(pair_address, call_data.to_vec()));
+        }
+        self.throttle().await;
+        let results = MulticallService::new(self.evm.clone())
+            .aggregate(multicall_address, calls)
+            .await?;
+        if results.len() != 4 {
+            return Err(EvmError::ContractError(
+                "Unexpected multicall result count".to_string(),
+            ));
+        }
+        let token0 = Address::decode(&results[0].data)
+            .map_err(|e| EvmError::ContractError(format!("Failed to decode token0: {}", e)))?;
+        let token1 = Address::decode(&results[1].data)
+            .map_err(|e| EvmError::ContractError(format!("Failed to decode token1: {}", e)))?;
+        let (reserve0, reserve1, block_timestamp_last) =
+            <(U256, U256, u32)>::decode(&results[2].data).map_err(|e| {
+                EvmError::ContractError(format!("Failed to decode reserves: {}", e))
+            })?;
+        let total_supply = U256::decode(&results[3].data).map_err(|e| {
+            EvmError::ContractError(format!("Failed to decode total supply: {}", e))
+        })?;
+        Ok(PoolInfo {
+            pair_address,
+            token0,
+            token1,
+            reserve0,
+            reserve1,
+            block_timestamp_last,
+            total_supply,
+        })
+    }
+}
+
+/// Maps a failed historical call, telling pruned-state errors apart from contract errors
+fn historical_call_error(field: &str, block: u64, e: impl std::fmt::Display) -> EvmError {
+    let message = e.to_string();
+    let lowered = message.to_lowercase();
+    let pruned = [
+        "missing trie node",
+        "header not found",
+        "pruned",
+        "historical state",
+    ]
+    .iter()
+    .any(|hint| lowered.contains(hint));
+    if pruned {
+        EvmError::ProviderError(format!(
+            "State at block {} is not available; an archive node is required: {}",
+            block, message
+        ))
+    } else {
+        EvmError::ContractError(format!(
+            "Failed to get {} at block {}: {}",
+            field, block, message
+        ))
+    }
 }
 
 /// Comprehensive liquidity pool information
@@ -253,17 +617,225 @@ impl PoolInfo {
     /// Ok(())
     /// }
     /// ```
-    pub fn cal_price(&self, base_token: Address) -> Result<f64, EvmError> {
+    pub fn cal_price(&self, base_token: Address) -> PancakeResult<f64> {
         if self.reserve0.is_zero() || self.reserve1.is_zero() {
             return Err(EvmError::CalculationError("Reserves are zero".to_string()));
         }
 
         if base_token == self.token0 {
-            Ok(self.reserve1.as_u128() as f64 / self.reserve0.as_u128() as f64)
+            Ok(u256_to_f64(self.reserve1, 0) / u256_to_f64(self.reserve0, 0))
         } else if base_token == self.token1 {
-            Ok(self.reserve0.as_u128() as f64 / self.reserve1.as_u128() as f64)
+            Ok(u256_to_f64(self.reserve0, 0) / u256_to_f64(self.reserve1, 0))
         } else {
             Err(EvmError::CalculationError("Invalid base token".to_string()))
         }
     }
+
+    /// Percentage change of `base_token`'s price against reserves estimated 24 hours ago
+    ///
+    /// Pairs sort tokens by address, so `base_token` may be either side, but it must be one
+    /// of them or this fails with `EvmError::InvalidInput`.
+    pub(crate) fn cal_price_change_24h(&self, base_token: Address) -> PancakeResult<f64> {
+        if base_token != self.token0 && base_token != self.token1 {
+            return Err(EvmError::InvalidInput("base token not in pair".to_string()));
+        }
+        let current_price = self.cal_price(base_token)?;
+        let previous_reserve0 = self.reserve0 * U256::from(95) / U256::from(100);
+        let previous_reserve1 = self.reserve1 * U256::from(105) / U256::from(100);
+        let previous_price = if base_token == self.token0 {
+            u256_to_f64(previous_reserve1, 0) / u256_to_f64(previous_reserve0, 0)
+        } else {
+            u256_to_f64(previous_reserve0, 0) / u256_to_f64(previous_reserve1, 0)
+        };
+        Ok(((current_price - previous_price) / previous_price) * 100.0)
+    }
+
+    /// Spot (mid) price of one whole `base_token` in the other token of the pair
+    ///
+    /// This is the fee-less reserve ratio scaled by both tokens' decimals. It is not the
+    /// execution price: any nonzero trade also pays the 0.25% fee and moves the reserves, so
+    /// it receives less than `amount * spot_price`. Quote trades with `get_price_via_pair`.
+    ///
+    /// # Example
+    /// ```
+    /// use ethers::types::Address;
+    /// use std::str::FromStr;
+    /// fn example(pool: PoolInfo) -> Result<(), EvmError> {
+    /// let wbnb = Address::from_str("0xbb4CdB9CBd36B01bD1cBaEBF2De08d9173bc095c").unwrap();
+    /// // WBNB priced in a 6-decimal stablecoin
+    /// let price = pool.spot_price(wbnb, 18, 6)?;
+    /// println!("1 WBNB = {}", price);
+    /// Ok(())
+    /// }
+    /// ```
+    pub fn spot_price(
+        &self,
+        base_token: Address,
+        decimals_base: u8,
+        decimals_quote: u8,
+    ) -> PancakeResult<f64> {
+        let (reserve_base, reserve_quote) = if base_token == self.token0 {
+            (self.reserve0, self.reserve1)
+        } else if base_token == self.token1 {
+            (self.reserve1, self.reserve0)
+        } else {
+            return Err(EvmError::CalculationError("Invalid base token".to_string()));
+        };
+        if reserve_base.is_zero() || reserve_quote.is_zero() {
+            return Err(EvmError::CalculationError("Reserves are zero".to_string()));
+        }
+        Ok(u256_to_f64(reserve_quote, decimals_quote) / u256_to_f64(reserve_base, decimals_base))
+    }
+}
+
+/// LP tokens a V2 pair's `mint` issues for `amount0` / `amount1` at the given reserves and supply
+fn lp_minted(
+    amount0: U256,
+    amount1: U256,
+    reserve0: U256,
+    reserve1: U256,
+    total_supply: U256,
+) -> PancakeResult<U256> {
+    let overflow = || EvmError::CalculationError("LP mint amount overflows".to_string());
+
+    let liquidity = if total_supply.is_zero() {
+        let product = amount0.checked_mul(amount1).ok_or_else(overflow)?;
+        product
+            .integer_sqrt()
+            .saturating_sub(U256::from(MINIMUM_LIQUIDITY))
+    } else {
+        if reserve0.is_zero() || reserve1.is_zero() {
+            return Err(EvmError::CalculationError("Reserves are zero".to_string()));
+        }
+        let liquidity0 = amount0.checked_mul(total_supply).ok_or_else(overflow)? / reserve0;
+        let liquidity1 = amount1.checked_mul(total_supply).ok_or_else(overflow)? / reserve1;
+        liquidity0.min(liquidity1)
+    };
+    if liquidity.is_zero() {
+        return Err(EvmError::CalculationError(
+            "Insufficient liquidity minted".to_string(),
+        ));
+    }
+    Ok(liquidity)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pool(token0: Address, token1: Address) -> PoolInfo {
+        PoolInfo {
+            pair_address: Address::repeat_byte(0xaa),
+            token0,
+            token1,
+            reserve0: U256::from(1_000u64),
+            reserve1: U256::from(2_000u64),
+            block_timestamp_last: 0,
+            total_supply: U256::zero(),
+        }
+    }
+
+    #[test]
+    fn price_change_24h_accepts_base_token_on_either_side() {
+        let (token0, token1) = (Address::repeat_byte(0x01), Address::repeat_byte(0x02));
+        let pool = pool(token0, token1);
+
+        // token0 priced in token1: 2.0 now against 2100 / 950 before
+        let change = pool.cal_price_change_24h(token0).unwrap();
+        assert!((change - -9.5238).abs() < 0.001, "change {}", change);
+
+        // Reversed order, token1 priced in token0: 0.5 now against 950 / 2100 before
+        let change = pool.cal_price_change_24h(token1).unwrap();
+        assert!((change - 10.5263).abs() < 0.001, "change {}", change);
+    }
+
+    #[test]
+    fn price_change_24h_rejects_base_token_outside_the_pair() {
+        let pool = pool(Address::repeat_byte(0x01), Address::repeat_byte(0x02));
+        assert!(matches!(
+            pool.cal_price_change_24h(Address::repeat_byte(0x03)),
+            Err(EvmError::InvalidInput(_))
+        ));
+    }
+
+    #[test]
+    fn first_mint_locks_minimum_liquidity() {
+        // sqrt(4e6 * 9e6) = 6e6, less the 1000 locked forever
+        let minted = lp_minted(
+            U256::from(4_000_000u64),
+            U256::from(9_000_000u64),
+            U256::zero(),
+            U256::zero(),
+            U256::zero(),
+        )
+        .unwrap();
+        assert_eq!(minted, U256::from(5_999_000u64));
+    }
+
+    #[test]
+    fn existing_pool_mints_the_smaller_share() {
+        // 10% of reserve0 but only 5% of reserve1
+        let minted = lp_minted(
+            U256::from(100u64),
+            U256::from(100u64),
+            U256::from(1_000u64),
+            U256::from(2_000u64),
+            U256::from(500u64),
+        )
+        .unwrap();
+        assert_eq!(minted, U256::from(25u64));
+    }
+
+    #[test]
+    fn existing_pool_with_zero_reserves_fails() {
+        assert!(matches!(
+            lp_minted(
+                U256::from(100u64),
+                U256::from(100u64),
+                U256::zero(),
+                U256::from(2_000u64),
+                U256::from(500u64),
+            ),
+            Err(EvmError::CalculationError(_))
+        ));
+    }
+
+    #[test]
+    fn zero_liquidity_minted_fails() {
+        // First deposit below the locked minimum
+        assert!(matches!(
+            lp_minted(
+                U256::from(10u64),
+                U256::from(10u64),
+                U256::zero(),
+                U256::zero(),
+                U256::zero(),
+            ),
+            Err(EvmError::CalculationError(_))
+        ));
+    }
+
+    #[test]
+    fn overflowing_amounts_fail() {
+        assert!(matches!(
+            lp_minted(
+                U256::MAX,
+                U256::from(2u64),
+                U256::zero(),
+                U256::zero(),
+                U256::zero(),
+            ),
+            Err(EvmError::CalculationError(_))
+        ));
+        assert!(matches!(
+            lp_minted(
+                U256::MAX,
+                U256::from(1u64),
+                U256::from(1u64),
+                U256::from(1u64),
+                U256::from(2u64),
+            ),
+            Err(EvmError::CalculationError(_))
+        ));
+    }
 }
@@ -0,0 +1,56 @@
+/// Internal diagnostics macros.
+///
+/// With the `tracing` feature enabled these forward to `tracing::warn!` / `tracing::error!`
+/// so the SDK logs flow through the caller's subscriber. Without it they fall back to stderr,
+/// printing the message followed by its `key=value` fields.
+///
+/// Fields use the tracing syntax and must come before the message:
+/// `log_warn!(pid = pid, error = %e, "Failed to get farm info")`.
+macro_rules! log_warn {
+    ($($arg:tt)+) => {{
+        #[cfg(feature = "tracing")]
+        ::tracing::warn!($($arg)+);
+        #[cfg(not(feature = "tracing"))]
+        $crate::logging::log_stderr!(@fields [] $($arg)+);
+    }};
+}
+
+macro_rules! log_error {
+    ($($arg:tt)+) => {{
+        #[cfg(feature = "tracing")]
+        ::tracing::error!($($arg)+);
+        #[cfg(not(feature = "tracing"))]
+        $crate::logging::log_stderr!(@fields [] $($arg)+);
+    }};
+}
+
+/// Stderr fallback used when the `tracing` feature is disabled
+#[cfg(not(feature = "tracing"))]
+macro_rules! log_stderr {
+    (@fields [$($fields:tt)*] $name:ident = %$value:expr, $($rest:tt)+) => {
+        $crate::logging::log_stderr!(
+            @fields [$($fields)* (stringify!($name), format!("{}", $value))] $($rest)+
+        )
+    };
+    (@fields [$($fields:tt)*] $name:ident = ?$value:expr, $($rest:tt)+) => {
+        $crate::logging::log_stderr!(
+            @fields [$($fields)* (stringify!($name), format!("{:?}", $value))] $($rest)+
+        )
+    };
+    (@fields [$($fields:tt)*] $name:ident = $value:expr, $($rest:tt)+) => {
+        $crate::logging::log_stderr!(
+            @fields [$($fields)* (stringify!($name), format!("{}", $value))] $($rest)+
+        )
+    };
+    (@fields [$(($key:expr, $value:expr))*] $($arg:tt)+) => {{
+        #[allow(unused_mut)]
+        let mut line = format!($($arg)+);
+        $(line.push_str(&format!(" {}={}", $key, $value));)*
+        eprintln!("{}", line);
+    }};
+}
+
+pub(crate) use log_error;
+#[cfg(not(feature = "tracing"))]
+pub(crate) use log_stderr;
+pub(crate) use log_warn;
@@ -1,6 +1,7 @@
 use crate::{
-    EvmError,
+    EvmError, PancakeResult,
     abi::{IERC20, IMulticall, IPancakePair, IPancakeRouter02, i_multicall},
+    logging::log_warn,
 };
 use ethers::{
     abi::AbiDecode,
@@ -49,7 +50,7 @@ impl MulticallService {
         &self,
         multicall_address: Address,
         calls: Vec<Call>,
-    ) -> Result<Vec<MulticallResult>, EvmError> {
+    ) -> PancakeResult<Vec<MulticallResult>> {
         let multicall = IMulticall::new(multicall_address, self.evm.client.provider.clone());
         let call_data: Vec<i_multicall::Call> = calls
             .into_iter()
@@ -73,6 +74,52 @@ impl MulticallService {
             .collect())
     }
 
+    /// Executes a batch of calls, allowing individual calls to revert
+    ///
+    /// Unlike `aggregate`, a reverting call does not fail the batch; its result has
+    /// `success` set to false and carries the revert data.
+    ///
+    /// # Example
+    /// ```
+    /// use ethers::types::{Address, U256};
+    /// use multicall::MulticallService;
+    ///
+    /// async fn example(service: MulticallService, multicall_addr: Address) -> Result<(), Box<dyn std::error::Error>> {
+    /// let calls = vec![Call::new(router_address, get_amounts_out_calldata)];
+    /// for result in service.try_aggregate(multicall_addr, calls).await? {
+    ///     println!("success: {}", result.success);
+    /// }
+    /// Ok(())
+    /// }
+    /// ```
+    pub async fn try_aggregate(
+        &self,
+        multicall_address: Address,
+        calls: Vec<Call>,
+    ) -> PancakeResult<Vec<MulticallResult>> {
+        let multicall = IMulticall::new(multicall_address, self.evm.client.provider.clone());
+        let call_data: Vec<i_multicall::Call> = calls
+            .into_iter()
+            .map(|call| i_multicall::Call {
+                target: call.target,
+                call_data: call.data.into(),
+            })
+            .collect();
+        let return_data = multicall
+            .try_aggregate(false, call_data)
+            .call()
+            .await
+            .map_err(|e| EvmError::ContractError(format!("Multicall failed: {}", e)))?;
+        Ok(return_data
+            .into_iter()
+            .map(|result| MulticallResult {
+                success: result.success,
+                data: result.return_data.to_vec(),
+                gas_used: U256::zero(),
+            })
+            .collect())
+    }
+
     /// Batch fetches token balances for multiple tokens for a single user
     ///
     /// # Example
@@ -92,7 +139,7 @@ impl MulticallService {
         multicall_address: Address,
         token_addresses: Vec<Address>,
         user_address: Address,
-    ) -> Result<HashMap<Address, U256>, EvmError> {
+    ) -> PancakeResult<HashMap<Address, U256>> {
         let mut calls = Vec::new();
         for token_address in &token_addresses {
             let erc20 = IERC20::new(*token_address, self.evm.client.provider.clone());
@@ -113,9 +160,10 @@ impl MulticallService {
                         balances.insert(token_addresses[i], balance);
                     }
                     Err(e) => {
-                        eprintln!(
-                            "Failed to decode balance for token {}: {}",
-                            token_addresses[i], e
+                        log_warn!(
+                            token = ?token_addresses[i],
+                            error = %e,
+                            "Failed to decode token balance"
                         );
                     }
                 }
@@ -142,7 +190,7 @@ impl MulticallService {
         &self,
         multicall_address: Address,
         pair_addresses: Vec<Address>,
-    ) -> Result<HashMap<Address, (U256, U256, u32)>, EvmError> {
+    ) -> PancakeResult<HashMap<Address, (U256, U256, u32)>> {
         let mut calls = Vec::new();
         for pair_address in &pair_addresses {
             let pair = IPancakePair::new(*pair_address, self.evm.client.provider.clone());
@@ -192,7 +240,7 @@ impl MulticallService {
         router_address: Address,
         token_pairs: Vec<(Address, Address)>,
         amount_in: U256,
-    ) -> Result<HashMap<(Address, Address), U256>, EvmError> {
+    ) -> PancakeResult<HashMap<(Address, Address), U256>> {
         let mut calls = Vec::new();
         for (token_in, token_out) in &token_pairs {
             let router = IPancakeRouter02::new(router_address, self.evm.client.provider.clone());
@@ -241,7 +289,7 @@ impl MulticallService {
         multicall_address: Address,
         token_addresses: Vec<Address>,
         user_addresses: Vec<Address>,
-    ) -> Result<HashMap<(Address, Address), U256>, EvmError> {
+    ) -> PancakeResult<HashMap<(Address, Address), U256>> {
         let mut calls = Vec::new();
         for token_address in &token_addresses {
             for user_address in &user_addresses {
@@ -267,9 +315,11 @@ impl MulticallService {
                                 balances.insert((*token_address, *user_address), balance);
                             }
                             Err(e) => {
-                                eprintln!(
-                                    "Failed to decode balance for token {} user {}: {}",
-                                    token_address, user_address, e
+                                log_warn!(
+                                    token = ?token_address,
+                                    user = ?user_address,
+                                    error = %e,
+                                    "Failed to decode token balance"
                                 );
                             }
                         }
@@ -280,6 +330,65 @@ impl MulticallService {
         }
         Ok(balances)
     }
+
+    /// Batch fetches ERC20 allowances for `(token, spender, owner)` triples
+    ///
+    /// Triples whose call fails or cannot be decoded are left out of the map, so treat a
+    /// missing entry as unknown rather than zero.
+    ///
+    /// # Example
+    /// ```
+    /// use ethers::types::{Address, U256};
+    /// use std::collections::HashMap;
+    /// use multicall::MulticallService;
+    ///
+    /// async fn example(service: MulticallService, multicall_addr: Address) -> Result<(), Box<dyn std::error::Error>> {
+    /// let queries = vec![(cake, router, owner), (busd, router, owner)];
+    /// let allowances: HashMap<(Address, Address, Address), U256> = service.get_allowances(multicall_addr, queries).await?;
+    /// Ok(())
+    /// }
+    /// ```
+    pub async fn get_allowances(
+        &self,
+        multicall_address: Address,
+        token_spender_owner: Vec<(Address, Address, Address)>,
+    ) -> PancakeResult<HashMap<(Address, Address, Address), U256>> {
+        let mut calls = Vec::new();
+        for (token_address, spender, owner) in &token_spender_owner {
+            let erc20 = IERC20::new(*token_address, self.evm.client.provider.clone());
+            let call_data = erc20
+                .allowance(*owner, *spender)
+                .calldata()
+                .ok_or_else(|| {
+                    EvmError::ContractError("Failed to encode allowance call".to_string())
+                })?;
+            calls.push(Call {
+                target: *token_address,
+                data: call_data.to_vec(),
+            });
+        }
+        let results = self.try_aggregate(multicall_address, calls).await?;
+        let mut allowances = HashMap::new();
+        for (key, result) in token_spender_owner.into_iter().zip(results) {
+            if result.success && !result.data.is_empty() {
+                match U256::decode(&result.data) {
+                    Ok(allowance) => {
+                        allowances.insert(key, allowance);
+                    }
+                    Err(e) => {
+                        log_warn!(
+                            token = ?key.0,
+                            spender = ?key.1,
+                            owner = ?key.2,
+                            error = %e,
+                            "Failed to decode token allowance"
+                        );
+                    }
+                }
+            }
+        }
+        Ok(allowances)
+    }
 }
 
 #[derive(Debug, Clone)]
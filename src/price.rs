@@ -1,9 +1,23 @@
-use crate::EvmError;
+use crate::logging::log_warn;
+use crate::multicall::{Call, MulticallResult, MulticallService};
+use crate::rate_limit::RateLimiter;
+use crate::tool::math_utils::{self, u256_to_f64};
+use crate::types::PriceInfo;
+use crate::{EvmError, PancakeResult};
+use async_trait::async_trait;
+use ethers::abi::AbiDecode;
 use ethers::types::{Address, U256};
 use evm_client::EvmType;
 use evm_sdk::Evm;
 use std::collections::{HashMap, VecDeque};
 use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::MissedTickBehavior;
+use tokio_stream::wrappers::IntervalStream;
+use tokio_stream::{Stream, StreamExt};
+
+/// Calls per multicall in `get_prices_usd`, to stay under the node's `eth_call` gas cap
+const PRICE_MULTICALL_BATCH_SIZE: usize = 200;
 
 /// Represents historical price data for a token
 #[derive(Debug, Clone)]
@@ -13,10 +27,37 @@ pub struct PriceHistory {
     pub volume: f64,
 }
 
+/// Source of token prices used for valuations
+///
+/// `PriceService` implements this with on-chain router quotes. Implement it for other feeds,
+/// such as Chainlink or an off-chain API, and inject it with
+/// `AnalyticsService::with_price_source`.
+///
+/// # Example
+/// ```
+/// struct FixedPrice(f64);
+///
+/// #[async_trait]
+/// impl PriceSource for FixedPrice {
+///     async fn price(&self, _token: Address, _base: Address) -> Result<f64, EvmError> {
+///         Ok(self.0)
+///     }
+/// }
+///
+/// let analytics = AnalyticsService::new(evm.clone()).with_price_source(Arc::new(FixedPrice(1.0)));
+/// ```
+#[async_trait]
+pub trait PriceSource: Send + Sync {
+    /// Price of one whole `token` denominated in `base`
+    async fn price(&self, token: Address, base: Address) -> PancakeResult<f64>;
+}
+
 /// Service for fetching and managing token prices
 pub struct PriceService {
     evm: Arc<Evm>,
     price_history: HashMap<Address, VecDeque<PriceHistory>>,
+    decimals_overrides: HashMap<Address, u8>,
+    rate_limiter: Option<Arc<RateLimiter>>,
 }
 
 impl PriceService {
@@ -24,6 +65,34 @@ impl PriceService {
         Self {
             evm: evm,
             price_history: HashMap::new(),
+            decimals_overrides: HashMap::new(),
+            rate_limiter: None,
+        }
+    }
+
+    /// Use fixed decimals for the given tokens instead of calling `decimals()`
+    pub fn with_decimals_overrides(mut self, overrides: HashMap<Address, u8>) -> Self {
+        self.decimals_overrides = overrides;
+        self
+    }
+
+    /// Shares `rate_limiter` with this service, e.g. the one configured on `PancakeSwapService`
+    pub fn with_rate_limiter(mut self, rate_limiter: Arc<RateLimiter>) -> Self {
+        self.rate_limiter = Some(rate_limiter);
+        self
+    }
+
+    /// Waits for a request permit when a rate limiter is configured
+    async fn throttle(&self) {
+        RateLimiter::acquire_opt(&self.rate_limiter).await;
+    }
+
+    /// Liquidity service sharing this service's rate limiter
+    fn liquidity_service(&self) -> crate::liquidity::LiquidityService {
+        let service = crate::liquidity::LiquidityService::new(self.evm.clone());
+        match &self.rate_limiter {
+            Some(rate_limiter) => service.with_rate_limiter(rate_limiter.clone()),
+            None => service,
         }
     }
 
@@ -49,10 +118,11 @@ impl PriceService {
         token_in: Address,
         token_out: Address,
         amount_in: U256,
-    ) -> Result<U256, EvmError> {
+    ) -> PancakeResult<U256> {
         let router =
             crate::abi::IPancakeRouter02::new(router_address, self.evm.client.provider.clone());
         let path = vec![token_in, token_out];
+        self.throttle().await;
         let amounts = router
             .get_amounts_out(amount_in, path)
             .call()
@@ -88,7 +158,7 @@ impl PriceService {
         base_token: Address,
         quote_tokens: Vec<Address>,
         amount_in: U256,
-    ) -> Result<HashMap<Address, U256>, EvmError> {
+    ) -> PancakeResult<HashMap<Address, U256>> {
         let mut prices = HashMap::new();
         for quote_token in quote_tokens {
             if base_token == quote_token {
@@ -103,13 +173,195 @@ impl PriceService {
                     prices.insert(quote_token, price);
                 }
                 Err(e) => {
-                    eprintln!("Failed to get price for token {:?}: {}", quote_token, e);
+                    log_warn!(token = ?quote_token, error = %e, "Failed to get price");
                 }
             }
         }
         Ok(prices)
     }
 
+    /// Stream of fresh quotes for a swap, one per `interval`
+    ///
+    /// The first quote is taken immediately. Failed quotes are yielded as errors and the
+    /// stream keeps going; drop it to stop polling.
+    ///
+    /// # Example
+    /// ```
+    /// use ethers::types::{Address, U256};
+    /// use price::PriceService;
+    /// use std::time::Duration;
+    /// use tokio_stream::StreamExt;
+    /// async fn example(price_service: PriceService) -> Result<(), Box<dyn std::error::Error>> {
+    /// let router = "0x10ED43C718714eb63d5aA57B78B54704E256024E".parse()?;
+    /// let token_in = "0x...".parse()?;
+    /// let token_out = "0x...".parse()?;
+    /// let amount = U256::from(10_u64.pow(18));
+    ///
+    /// let stream = price_service.price_stream(router, token_in, token_out, amount, Duration::from_secs(3));
+    /// tokio::pin!(stream);
+    /// while let Some(price) = stream.next().await {
+    ///     println!("Price: {}", price?.price);
+    /// }
+    /// Ok(())
+    /// }
+    /// ```
+    pub fn price_stream(
+        &self,
+        router_address: Address,
+        token_in: Address,
+        token_out: Address,
+        amount_in: U256,
+        interval: Duration,
+    ) -> impl Stream<Item = PancakeResult<PriceInfo>> + '_ {
+        let mut ticker = tokio::time::interval(interval);
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Skip);
+        IntervalStream::new(ticker).then(move |_| async move {
+            let amount_out = self
+                .get_price(router_address, token_in, token_out, amount_in)
+                .await?;
+            Ok(PriceInfo {
+                token_in,
+                token_out,
+                amount_in,
+                amount_out,
+                price: u256_to_f64(amount_out, 0) / u256_to_f64(amount_in, 0),
+                price_impact: 0.0,
+                timestamp: crate::tool::time_utils::current_timestamp(),
+            })
+        })
+    }
+
+    /// Quote a range of input sizes in batched multicalls, for depth / slippage curves
+    ///
+    /// Returns `(amount_in, amount_out)` pairs in the same order as `amounts`. Calls go through
+    /// `tryAggregate`, so a rung the router cannot quote (e.g. more than the reserves) has
+    /// `amount_out` `None` instead of failing the whole curve.
+    ///
+    /// # Example
+    /// ```
+    /// use ethers::types::{Address, U256};
+    /// use price::PriceService;
+    /// async fn example(price_service: PriceService) -> Result<(), Box<dyn std::error::Error>> {
+    /// let router = "0x10ED43C718714eb63d5aA57B78B54704E256024E".parse()?;
+    /// let multicall = "0x...".parse()?;
+    /// let token_in = "0x...".parse()?;
+    /// let token_out = "0x...".parse()?;
+    /// let amounts = (1..=10u64).map(|i| U256::from(i) * U256::exp10(18)).collect();
+    ///
+    /// let depth = price_service.quote_depth(router, token_in, token_out, amounts, multicall).await?;
+    /// for (amount_in, amount_out) in depth {
+    ///     println!("{} -> {:?}", amount_in, amount_out);
+    /// }
+    /// Ok(())
+    /// }
+    /// ```
+    pub async fn quote_depth(
+        &self,
+        router_address: Address,
+        token_in: Address,
+        token_out: Address,
+        amounts: Vec<U256>,
+        multicall_address: Address,
+    ) -> PancakeResult<Vec<(U256, Option<U256>)>> {
+        let router =
+            crate::abi::IPancakeRouter02::new(router_address, self.evm.client.provider.clone());
+        let mut calls = Vec::with_capacity(amounts.len());
+        for amount_in in &amounts {
+            let call_data = router
+                .get_amounts_out(*amount_in, vec![token_in, token_out])
+                .calldata()
+                .ok_or_else(|| {
+                    EvmError::ContractError("Failed to encode getAmountsOut call".to_string())
+                })?;
+            calls.push(Call::new(router_address, call_data.to_vec()));
+        }
+        let multicall = MulticallService::new(self.evm.clone());
+        let results = self
+            .try_aggregate_batched(&multicall, multicall_address, calls)
+            .await?;
+        let mut depth = Vec::with_capacity(amounts.len());
+        for (amount_in, result) in amounts.into_iter().zip(results) {
+            let amount_out = if result.success {
+                Vec::<U256>::decode(&result.data)
+                    .ok()
+                    .and_then(|amounts_out| amounts_out.last().copied())
+            } else {
+                None
+            };
+            depth.push((amount_in, amount_out));
+        }
+        Ok(depth)
+    }
+
+    /// Quote the same swap on several V2-compatible routers and rank the venues
+    ///
+    /// Routers are queried concurrently rather than through one multicall, because a router
+    /// without the pair reverts and would fail the whole batch. Routers that cannot quote are
+    /// left out; the rest are sorted by output, best first.
+    ///
+    /// # Example
+    /// ```
+    /// use ethers::types::{Address, U256};
+    /// use price::PriceService;
+    /// async fn example(price_service: PriceService) -> Result<(), Box<dyn std::error::Error>> {
+    /// let routers = vec![
+    ///     ("PancakeSwap".to_string(), "0x10ED43C718714eb63d5aA57B78B54704E256024E".parse()?),
+    ///     ("Biswap".to_string(), "0x3a6d8cA21D1CF76F653A67577FA0D27453350dD8".parse()?),
+    ///     ("ApeSwap".to_string(), "0xcF0feBd3f17CEf5b47b0cD257aCf6025c5BFf3b7".parse()?),
+    /// ];
+    /// let token_in = "0x...".parse()?;
+    /// let token_out = "0x...".parse()?;
+    /// let amount = U256::from(10_u64.pow(18));
+    ///
+    /// let quotes = price_service.compare_routers(routers, token_in, token_out, amount).await?;
+    /// let (best_venue, best_out) = &quotes[0];
+    /// Ok(())
+    /// }
+    /// ```
+    pub async fn compare_routers(
+        &self,
+        routers: Vec<(String, Address)>,
+        token_in: Address,
+        token_out: Address,
+        amount_in: U256,
+    ) -> PancakeResult<Vec<(String, U256)>> {
+        let mut tasks = Vec::with_capacity(routers.len());
+        for (name, router_address) in routers {
+            self.throttle().await;
+            let provider = self.evm.client.provider.clone();
+            let task = tokio::spawn(async move {
+                crate::abi::IPancakeRouter02::new(router_address, provider)
+                    .get_amounts_out(amount_in, vec![token_in, token_out])
+                    .call()
+                    .await
+            });
+            tasks.push((name, task));
+        }
+        let mut quotes = Vec::with_capacity(tasks.len());
+        for (name, task) in tasks {
+            match task.await {
+                Ok(Ok(amounts)) => {
+                    if let Some(amount_out) = amounts.last() {
+                        quotes.push((name, *amount_out));
+                    }
+                }
+                Ok(Err(e)) => {
+                    log_warn!(router = name, error = %e, "Failed to get router quote");
+                }
+                Err(e) => {
+                    log_warn!(router = name, error = %e, "Router quote task failed");
+                }
+            }
+        }
+        if quotes.is_empty() {
+            return Err(EvmError::CalculationError(
+                "No router returned a quote".to_string(),
+            ));
+        }
+        quotes.sort_by_key(|(_, amount_out)| std::cmp::Reverse(*amount_out));
+        Ok(quotes)
+    }
+
     /// Get token price relative to base token
     ///
     /// # Example
@@ -125,22 +377,21 @@ impl PriceService {
     /// Ok(())
     /// }
     /// ```
-    pub async fn get_token_price(
-        &self,
-        token: Address,
-        base_token: Address,
-    ) -> Result<f64, EvmError> {
+    pub async fn get_token_price(&self, token: Address, base_token: Address) -> PancakeResult<f64> {
         if token == base_token {
             return Ok(1.0);
         }
         let router_address = self.get_default_router()?;
-        let amount_in = U256::from(10_u64.pow(18)); // 1个代币
+        let token_decimals = self.token_decimals(token).await?;
+        let base_decimals = self.token_decimals(base_token).await?;
+        // One whole token; 0-decimal tokens quote a single unit
+        let amount_in = math_utils::parse_units("1", token_decimals)?;
         match self
             .get_price(router_address, token, base_token, amount_in)
             .await
         {
             Ok(amount_out) => {
-                let price = amount_out.as_u128() as f64 / 1e18;
+                let price = u256_to_f64(amount_out, base_decimals);
                 return Ok(price);
             }
             Err(_) => {}
@@ -153,11 +404,12 @@ impl PriceService {
             let path = vec![token, intermediate, base_token];
             let router =
                 crate::abi::IPancakeRouter02::new(router_address, self.evm.client.provider.clone());
+            self.throttle().await;
             match router.get_amounts_out(amount_in, path).call().await {
                 Ok(amounts) => {
                     if amounts.len() >= 3 {
                         let amount_out = amounts[2];
-                        let price = amount_out.as_u128() as f64 / 1e18;
+                        let price = u256_to_f64(amount_out, base_decimals);
                         return Ok(price);
                     }
                 }
@@ -170,7 +422,144 @@ impl PriceService {
         )))
     }
 
-    fn get_default_router(&self) -> Result<Address, EvmError> {
+    /// Get USD prices for many tokens in batched multicalls
+    ///
+    /// Each token is quoted for one whole unit against the chain's USD stablecoin, directly
+    /// and through the common intermediate tokens, like `get_token_price`; the first route
+    /// that quotes wins. Tokens without any route, or without `decimals()`, are left out of
+    /// the result.
+    ///
+    /// # Example
+    /// ```
+    /// use ethers::types::Address;
+    /// use price::PriceService;
+    /// async fn example(price_service: PriceService) -> Result<(), Box<dyn std::error::Error>> {
+    /// let multicall = "0xcA11bde05977b3631167028862bE2a173976CA11".parse()?;
+    /// let tokens = vec!["0x...".parse()?, "0x...".parse()?];
+    ///
+    /// let prices = price_service.get_prices_usd(tokens, multicall).await?;
+    /// for (token, price) in prices {
+    ///     println!("{:?}: ${}", token, price);
+    /// }
+    /// Ok(())
+    /// }
+    /// ```
+    pub async fn get_prices_usd(
+        &self,
+        mut tokens: Vec<Address>,
+        multicall_address: Address,
+    ) -> PancakeResult<HashMap<Address, f64>> {
+        let usd = crate::analytics::stablecoin_addresses(self.evm.client.evm_type)
+            .first()
+            .copied()
+            .ok_or_else(|| EvmError::ConfigError("Unsupported chain".to_string()))?;
+        let router_address = self.get_default_router()?;
+        let multicall = MulticallService::new(self.evm.clone());
+        tokens.sort();
+        tokens.dedup();
+
+        // Decimals of every token and of the stablecoin, in one batch
+        let mut decimals = HashMap::new();
+        let mut decimals_tokens = Vec::with_capacity(tokens.len() + 1);
+        for token in tokens.iter().chain([&usd]) {
+            match self.decimals_overrides.get(token) {
+                Some(value) => {
+                    decimals.insert(*token, *value);
+                }
+                None => decimals_tokens.push(*token),
+            }
+        }
+        let mut calls = Vec::with_capacity(decimals_tokens.len());
+        for token in &decimals_tokens {
+            let call_data = crate::abi::IERC20::new(*token, self.evm.client.provider.clone())
+                .decimals()
+                .calldata()
+                .ok_or_else(|| {
+                    EvmError::ContractError("Failed to encode decimals call".to_string())
+                })?;
+            calls.push(Call::new(*token, call_data.to_vec()));
+        }
+        for (token, result) in decimals_tokens.iter().zip(
+            self.try_aggregate_batched(&multicall, multicall_address, calls)
+                .await?,
+        ) {
+            if result.success
+                && let Ok(value) = u8::decode(&result.data)
+            {
+                decimals.insert(*token, value);
+            }
+        }
+        let usd_decimals = *decimals
+            .get(&usd)
+            .ok_or_else(|| EvmError::ContractError("Failed to get decimals".to_string()))?;
+
+        // Quote probes per token: direct first, then through each intermediate
+        let router =
+            crate::abi::IPancakeRouter02::new(router_address, self.evm.client.provider.clone());
+        let intermediates = self.get_common_intermediate_tokens();
+        let mut prices = HashMap::new();
+        let mut probes: Vec<Address> = Vec::new();
+        let mut calls = Vec::new();
+        for token in &tokens {
+            if *token == usd {
+                prices.insert(*token, 1.0);
+                continue;
+            }
+            let Some(token_decimals) = decimals.get(token) else {
+                continue;
+            };
+            let amount_in = math_utils::parse_units("1", *token_decimals)?;
+            let mut paths = vec![vec![*token, usd]];
+            for intermediate in &intermediates {
+                if intermediate != token && *intermediate != usd {
+                    paths.push(vec![*token, *intermediate, usd]);
+                }
+            }
+            for path in paths {
+                let call_data = router
+                    .get_amounts_out(amount_in, path)
+                    .calldata()
+                    .ok_or_else(|| {
+                        EvmError::ContractError("Failed to encode getAmountsOut call".to_string())
+                    })?;
+                calls.push(Call::new(router_address, call_data.to_vec()));
+                probes.push(*token);
+            }
+        }
+        let results = self
+            .try_aggregate_batched(&multicall, multicall_address, calls)
+            .await?;
+        for (token, result) in probes.into_iter().zip(results) {
+            if prices.contains_key(&token) || !result.success {
+                continue;
+            }
+            if let Ok(amounts) = Vec::<U256>::decode(&result.data)
+                && let Some(amount_out) = amounts.last()
+            {
+                prices.insert(token, u256_to_f64(*amount_out, usd_decimals));
+            }
+        }
+        Ok(prices)
+    }
+
+    /// `MulticallService::try_aggregate` in batches of `PRICE_MULTICALL_BATCH_SIZE` calls
+    async fn try_aggregate_batched(
+        &self,
+        multicall: &MulticallService,
+        multicall_address: Address,
+        calls: Vec<Call>,
+    ) -> PancakeResult<Vec<MulticallResult>> {
+        let mut results = Vec::with_capacity(calls.len());
+        let mut calls = calls.into_iter().peekable();
+        while calls.peek().is_some() {
+            let batch: Vec<Call> = calls.by_ref().take(PRICE_MULTICALL_BATCH_SIZE).collect();
+            self.throttle().await;
+            results.extend(multicall.try_aggregate(multicall_address, batch).await?);
+        }
+        Ok(results)
+    }
+
+    fn get_default_router(&self) -> PancakeResult<Address> {
         match self.evm.client.evm_type {
             Some(EvmType::BSC_MAINNET) => {
                 "0x10ED43C718714eb63d5aA57B78B54704E256024E" // PancakeSwap V2 Router
@@ -186,6 +575,18 @@ impl PriceService {
         }
     }
 
+    async fn token_decimals(&self, token: Address) -> PancakeResult<u8> {
+        if let Some(decimals) = self.decimals_overrides.get(&token) {
+            return Ok(*decimals);
+        }
+        self.throttle().await;
+        crate::abi::IERC20::new(token, self.evm.client.provider.clone())
+            .decimals()
+            .call()
+            .await
+            .map_err(|e| EvmError::ContractError(format!("Failed to get decimals: {}", e)))
+    }
+
     fn get_common_intermediate_tokens(&self) -> Vec<Address> {
         match self.evm.client.evm_type {
             Some(EvmType::BSC_MAINNET) => vec![
@@ -222,6 +623,8 @@ impl PriceService {
 
     /// Get price via liquidity pair
     ///
+    /// The output is computed from the pair's reserves with the PancakeSwap V2 fee of 0.25%.
+    ///
     /// # Example
     /// ```
     /// use ethers::types::{Address, U256};
@@ -240,8 +643,8 @@ impl PriceService {
         pair_address: Address,
         token_in: Address,
         amount_in: U256,
-    ) -> Result<U256, EvmError> {
-        let liquidity_service = crate::liquidity::LiquidityService::new(self.evm.clone());
+    ) -> PancakeResult<U256> {
+        let liquidity_service = self.liquidity_service();
         let pool_info = liquidity_service.get_pool_info(pair_address).await?;
         if pool_info.reserve0.is_zero() || pool_info.reserve1.is_zero() {
             return Err(EvmError::CalculationError("Reserves are zero".to_string()));
@@ -253,15 +656,166 @@ impl PriceService {
         } else {
             return Err(EvmError::CalculationError("Token not in pair".to_string()));
         };
-        let amount_in_with_fee = amount_in * U256::from(997);
-        let numerator = amount_in_with_fee * reserve_out;
-        let denominator = reserve_in * U256::from(1000) + amount_in_with_fee;
-        let amount_out = numerator / denominator;
-        Ok(amount_out)
+        math_utils::calculate_amount_out(
+            amount_in,
+            reserve_in,
+            reserve_out,
+            math_utils::PANCAKE_V2_FEE_BPS,
+        )
+        .map_err(|e| EvmError::CalculationError(format!("Failed to calculate amount out: {}", e)))
+    }
+
+    /// Spot price of `token_in` in the other token of a V3 pool, read from `slot0`
+    ///
+    /// No swap is simulated, so fees and price impact are ignored. Use it for display and
+    /// coarse comparisons; quote through `get_price` before trading.
+    ///
+    /// # Example
+    /// ```
+    /// use ethers::types::Address;
+    /// use price::PriceService;
+    /// async fn example(price_service: PriceService) -> Result<(), Box<dyn std::error::Error>> {
+    /// let pool = "0x...".parse()?;
+    /// let token_in = "0x...".parse()?;
+    ///
+    /// let price = price_service.get_v3_spot_price(pool, token_in).await?;
+    /// println!("Spot price: {}", price);
+    /// Ok(())
+    /// }
+    /// ```
+    pub async fn get_v3_spot_price(
+        &self,
+        pool_address: Address,
+        token_in: Address,
+    ) -> PancakeResult<f64> {
+        let pool = crate::abi::IPancakeV3Pool::new(pool_address, self.evm.client.provider.clone());
+        self.throttle().await;
+        let (sqrt_price_x96, ..) = pool
+            .slot_0()
+            .call()
+            .await
+            .map_err(|e| EvmError::ContractError(format!("Failed to get slot0: {}", e)))?;
+        if sqrt_price_x96.is_zero() {
+            return Err(EvmError::CalculationError(
+                "Pool is not initialized".to_string(),
+            ));
+        }
+        self.throttle().await;
+        let token0 = pool
+            .token_0()
+            .call()
+            .await
+            .map_err(|e| EvmError::ContractError(format!("Failed to get token0: {}", e)))?;
+        self.throttle().await;
+        let token1 = pool
+            .token_1()
+            .call()
+            .await
+            .map_err(|e| EvmError::ContractError(format!("Failed to get token1: {}", e)))?;
+        if token_in != token0 && token_in != token1 {
+            return Err(EvmError::InvalidInput("Token not in pool".to_string()));
+        }
+        let decimals0 = self.token_decimals(token0).await?;
+        let decimals1 = self.token_decimals(token1).await?;
+        // Raw token1 per token0, scaled to whole tokens
+        let price = math_utils::calculate_v3_price(sqrt_price_x96)
+            * 10_f64.powi(decimals0 as i32 - decimals1 as i32);
+        if token_in == token0 {
+            Ok(price)
+        } else {
+            Ok(1.0 / price)
+        }
+    }
+
+    /// Time-weighted average tick of a V3 pool over the last `window_secs` seconds, read
+    /// from the pool's `observe` oracle
+    ///
+    /// The pool must hold observations reaching back `window_secs`, otherwise `observe`
+    /// reverts; pools with a short history need a larger observation cardinality.
+    ///
+    /// # Example
+    /// ```
+    /// use ethers::types::Address;
+    /// use price::PriceService;
+    /// async fn example(price_service: PriceService) -> Result<(), Box<dyn std::error::Error>> {
+    /// let pool = "0x...".parse()?;
+    ///
+    /// let tick = price_service.get_v3_twap_tick(pool, 1800).await?; // 30 minutes
+    /// println!("TWAP tick: {}", tick);
+    /// Ok(())
+    /// }
+    /// ```
+    pub async fn get_v3_twap_tick(
+        &self,
+        pool_address: Address,
+        window_secs: u32,
+    ) -> PancakeResult<i32> {
+        if window_secs == 0 {
+            return Err(EvmError::InvalidInput(
+                "TWAP window must be greater than zero".to_string(),
+            ));
+        }
+        let pool = crate::abi::IPancakeV3Pool::new(pool_address, self.evm.client.provider.clone());
+        self.throttle().await;
+        let (tick_cumulatives, _) = pool
+            .observe(vec![window_secs, 0])
+            .call()
+            .await
+            .map_err(|e| EvmError::ContractError(format!("Failed to observe pool: {}", e)))?;
+        if tick_cumulatives.len() != 2 {
+            return Err(EvmError::ContractError(
+                "Unexpected observe result length".to_string(),
+            ));
+        }
+        let delta = tick_cumulatives[1] - tick_cumulatives[0];
+        // Round towards negative infinity, as the V3 oracle library does
+        let tick = delta.div_euclid(window_secs as i64);
+        Ok(tick as i32)
+    }
+
+    /// Percentage by which a V3 pool's spot price deviates from its TWAP over
+    /// `window_secs` seconds
+    ///
+    /// Both prices come from ticks, so the result does not depend on token order or
+    /// decimals. A large deviation suggests the spot price has just been pushed, e.g. by a
+    /// sandwich attack.
+    ///
+    /// # Example
+    /// ```
+    /// use ethers::types::Address;
+    /// use price::PriceService;
+    /// async fn example(price_service: PriceService) -> Result<(), Box<dyn std::error::Error>> {
+    /// let pool = "0x...".parse()?;
+    ///
+    /// let deviation = price_service.get_v3_twap_deviation(pool, 600).await?;
+    /// println!("Spot is {:.2}% off the 10 minute TWAP", deviation);
+    /// Ok(())
+    /// }
+    /// ```
+    pub async fn get_v3_twap_deviation(
+        &self,
+        pool_address: Address,
+        window_secs: u32,
+    ) -> PancakeResult<f64> {
+        let pool = crate::abi::IPancakeV3Pool::new(pool_address, self.evm.client.provider.clone());
+        self.throttle().await;
+        let (_, spot_tick, ..) = pool
+            .slot_0()
+            .call()
+            .await
+            .map_err(|e| EvmError::ContractError(format!("Failed to get slot0: {}", e)))?;
+        let twap_tick = self.get_v3_twap_tick(pool_address, window_secs).await?;
+        let ratio = 1.0001_f64.powi(spot_tick - twap_tick);
+        Ok((ratio - 1.0).abs() * 100.0)
     }
 
     /// Calculate price impact for a trade
     ///
+    /// Compares the execution price `amount_out / amount_in` of the router quote with the
+    /// spot price `reserve_out / reserve_in` of the `token_in`/`token_out` pair on the
+    /// router's factory, so the result is independent of the trade's units. It includes the
+    /// pair's swap fee.
+    ///
     /// # Example
     /// ```
     /// use ethers::types::{Address, U256};
@@ -283,28 +837,86 @@ impl PriceService {
         token_in: Address,
         token_out: Address,
         amount_in: U256,
-    ) -> Result<f64, EvmError> {
-        let price_service = PriceService::new(self.evm.clone());
-        let current_price = price_service
-            .get_price(
-                router_address,
-                token_in,
-                token_out,
-                U256::from(10).pow(U256::from(18)),
-            )
-            .await?;
-        let execution_price = price_service
+    ) -> PancakeResult<f64> {
+        let router =
+            crate::abi::IPancakeRouter02::new(router_address, self.evm.client.provider.clone());
+        self.throttle().await;
+        let factory_address = router
+            .factory()
+            .call()
+            .await
+            .map_err(|e| EvmError::ContractError(format!("Failed to get factory: {}", e)))?;
+        self.throttle().await;
+        let pair_address =
+            crate::abi::IPancakeFactory::new(factory_address, self.evm.client.provider.clone())
+                .get_pair(token_in, token_out)
+                .call()
+                .await
+                .map_err(|e| EvmError::ContractError(format!("Failed to get pair: {}", e)))?;
+        if pair_address.is_zero() {
+            return Err(EvmError::CalculationError(
+                "Pair does not exist".to_string(),
+            ));
+        }
+        let pool_info = self.liquidity_service().get_pool_info(pair_address).await?;
+        let (reserve_in, reserve_out) = if token_in == pool_info.token0 {
+            (pool_info.reserve0, pool_info.reserve1)
+        } else {
+            (pool_info.reserve1, pool_info.reserve0)
+        };
+        let amount_out = self
             .get_price(router_address, token_in, token_out, amount_in)
             .await?;
-        if current_price.is_zero() {
-            return Err(EvmError::CalculationError(
-                "Current price is zero".to_string(),
+        math_utils::calculate_price_impact(amount_in, amount_out, reserve_in, reserve_out).map_err(
+            |e| EvmError::CalculationError(format!("Failed to calculate price impact: {}", e)),
+        )
+    }
+
+    /// Calculate the percentage lost by buying `token_b` and immediately selling it back
+    ///
+    /// A healthy V2 pool loses roughly twice its fee plus price impact (about 0.5% at 0.25%
+    /// fee for small sizes); a much larger loss points to a thin or broken pool. Both legs are
+    /// `getAmountsOut` quotes from reserves, so transfer taxes are not reflected and a tax
+    /// token shows the same loss as any other.
+    ///
+    /// # Example
+    /// ```
+    /// use ethers::types::{Address, U256};
+    /// use price::PriceService;
+    /// async fn example(price_service: PriceService) -> Result<(), Box<dyn std::error::Error>> {
+    /// let router = "0x10ED43C718714eb63d5aA57B78B54704E256024E".parse()?;
+    /// let token_a = "0x...".parse()?;
+    /// let token_b = "0x...".parse()?;
+    /// let amount = U256::from(10_u64.pow(18));
+    ///
+    /// let loss = price_service.round_trip_loss(router, token_a, token_b, amount).await?;
+    /// println!("Round trip loss: {}%", loss);
+    /// Ok(())
+    /// }
+    /// ```
+    pub async fn round_trip_loss(
+        &self,
+        router_address: Address,
+        token_a: Address,
+        token_b: Address,
+        amount_in: U256,
+    ) -> PancakeResult<f64> {
+        if amount_in.is_zero() {
+            return Err(EvmError::InvalidInput(
+                "Amount in must be greater than zero".to_string(),
             ));
         }
-        let price_impact = (current_price.as_u128() as f64 - execution_price.as_u128() as f64)
-            / current_price.as_u128() as f64
-            * 100.0;
-        Ok(price_impact.abs())
+        let amount_b = self
+            .get_price(router_address, token_a, token_b, amount_in)
+            .await?;
+        let amount_back = self
+            .get_price(router_address, token_b, token_a, amount_b)
+            .await?;
+        // Ratio in parts per million keeps the division in U256 for large amounts
+        let returned_ppm = (amount_back * U256::from(1_000_000) / amount_in)
+            .min(U256::from(u64::MAX))
+            .as_u64();
+        Ok(100.0 - returned_ppm as f64 / 10_000.0)
     }
 
     /// Find optimal trading path
@@ -333,7 +945,7 @@ impl PriceService {
         token_out: Address,
         amount_in: U256,
         intermediate_tokens: Vec<Address>,
-    ) -> Result<(Vec<Address>, U256), EvmError> {
+    ) -> PancakeResult<(Vec<Address>, U256)> {
         let mut best_amount = U256::zero();
         let mut best_path = vec![token_in, token_out];
         match self
@@ -352,6 +964,7 @@ impl PriceService {
             let path = vec![token_in, intermediate, token_out];
             let router =
                 crate::abi::IPancakeRouter02::new(router_address, self.evm.client.provider.clone());
+            self.throttle().await;
             match router.get_amounts_out(amount_in, path.clone()).call().await {
                 Ok(amounts) => {
                     if amounts.len() >= 3 {
@@ -422,21 +1035,26 @@ impl PriceService {
     }
 
     /// Detect price anomalies using standard deviation
-    pub fn detect_price_anomalies(&self, token: Address, threshold: f64) -> Vec<PriceHistory> {
+    ///
+    /// Returns each point whose z-score exceeds `threshold` in absolute value, paired with its
+    /// signed z-score: positive above the mean, negative below. A flat series has no anomalies.
+    pub fn detect_price_anomalies(
+        &self,
+        token: Address,
+        threshold: f64,
+    ) -> Vec<(PriceHistory, f64)> {
         let mut anomalies = Vec::new();
         if let Some(history) = self.price_history.get(&token) {
             if history.len() < 2 {
                 return anomalies;
             }
             let prices: Vec<f64> = history.iter().map(|p| p.price).collect();
-            let mean = prices.iter().sum::<f64>() / prices.len() as f64;
-            let variance =
-                prices.iter().map(|p| (p - mean).powi(2)).sum::<f64>() / prices.len() as f64;
-            let std_dev = variance.sqrt();
-            for data in history {
-                let z_score = (data.price - mean).abs() / std_dev;
-                if z_score > threshold {
-                    anomalies.push(data.clone());
+            let Some(z_scores) = math_utils::z_scores(&prices) else {
+                return anomalies;
+            };
+            for (data, z_score) in history.iter().zip(z_scores) {
+                if z_score.abs() > threshold {
+                    anomalies.push((data.clone(), z_score));
                 }
             }
         }
@@ -498,20 +1116,17 @@ impl PriceService {
         &self,
         pair_address: Address,
         base_token: Address,
-    ) -> Result<f64, EvmError> {
-        let liquidity_service = crate::liquidity::LiquidityService::new(self.evm.clone());
+    ) -> PancakeResult<f64> {
+        let liquidity_service = self.liquidity_service();
         let pool_info = liquidity_service.get_pool_info(pair_address).await?;
-        let current_price = pool_info.cal_price(base_token)?;
-        let (reserve0, reserve1, _) = liquidity_service.get_reserves(pair_address).await?;
-        let previous_reserve0 = reserve0 * U256::from(95) / U256::from(100);
-        let previous_reserve1 = reserve1 * U256::from(105) / U256::from(100);
-        let previous_price = if base_token == pool_info.token0 {
-            previous_reserve1.as_u128() as f64 / previous_reserve0.as_u128() as f64
-        } else {
-            previous_reserve0.as_u128() as f64 / previous_reserve1.as_u128() as f64
-        };
-        let price_change = ((current_price - previous_price) / previous_price) * 100.0;
-        Ok(price_change)
+        pool_info.cal_price_change_24h(base_token)
+    }
+}
+
+#[async_trait]
+impl PriceSource for PriceService {
+    async fn price(&self, token: Address, base: Address) -> PancakeResult<f64> {
+        self.get_token_price(token, base).await
     }
 }
 
@@ -549,6 +1164,87 @@ impl PriceData {
     }
 }
 
+/// Compares a token's USD price across several chains
+///
+/// Services are keyed by chain id because `EvmType` does not implement `Hash`.
+/// Token addresses differ per chain, so callers supply the address to price on each chain.
+pub struct MultiChainPriceService {
+    services: HashMap<u64, Arc<PriceService>>,
+}
+
+impl MultiChainPriceService {
+    /// Creates a service from one `Evm` per chain; entries without an `evm_type` are ignored
+    pub fn new(evms: Vec<Arc<Evm>>) -> Self {
+        let services = evms
+            .into_iter()
+            .filter_map(|evm| {
+                let evm_type = evm.client.evm_type?;
+                Some((evm_type.chain_id(), Arc::new(PriceService::new(evm))))
+            })
+            .collect();
+        Self { services }
+    }
+
+    /// Gets each chain's USD price for a token, querying all chains concurrently
+    ///
+    /// Prices are quoted against the chain's USD stablecoin (USDT, or USDC on Base). Chains that are not configured or cannot
+    /// price the token are left out of the result.
+    ///
+    /// # Example
+    /// ```
+    /// use evm_client::EvmType;
+    /// use price::MultiChainPriceService;
+    /// async fn example(service: MultiChainPriceService) -> Result<(), Box<dyn std::error::Error>> {
+    /// let tokens = vec![
+    ///     (EvmType::BSC_MAINNET, "0x0E09FaBB73Bd3Ade0a17ECC321fD13a19e81cE82".parse()?),
+    ///     (EvmType::ETHEREUM_MAINNET, "0x152649eA73beAb28c5b49B26eb48f7EAD6d4c898".parse()?),
+    /// ];
+    /// let prices = service.compare_price(tokens).await?;
+    /// for (chain_id, price) in prices {
+    ///     println!("chain {}: ${}", chain_id, price);
+    /// }
+    /// Ok(())
+    /// }
+    /// ```
+    pub async fn compare_price(
+        &self,
+        tokens: Vec<(EvmType, Address)>,
+    ) -> PancakeResult<HashMap<u64, f64>> {
+        let mut tasks = Vec::new();
+        for (evm_type, token) in tokens {
+            let Some(price_service) = self.services.get(&evm_type.chain_id()) else {
+                continue;
+            };
+            let price_service = price_service.clone();
+            let task = tokio::spawn(async move {
+                let usd = crate::PancakeSwapConfig::usd_stablecoin_address(evm_type)?;
+                price_service.get_token_price(token, usd).await
+            });
+            tasks.push((evm_type, task));
+        }
+        let mut prices = HashMap::new();
+        for (evm_type, task) in tasks {
+            match task.await {
+                Ok(Ok(price)) => {
+                    prices.insert(evm_type.chain_id(), price);
+                }
+                Ok(Err(e)) => {
+                    log_warn!(chain = evm_type.name(), error = %e, "Failed to get price");
+                }
+                Err(e) => {
+                    log_warn!(chain = evm_type.name(), error = %e, "Price task failed");
+                }
+            }
+        }
+        if prices.is_empty() {
+            return Err(EvmError::CalculationError(
+                "No chain returned a price".to_string(),
+            ));
+        }
+        Ok(prices)
+    }
+}
+
 /// Cache for price data with TTL
 pub struct PriceCache {
     cache: HashMap<(Address, Address), (U256, u64)>,
@@ -0,0 +1,77 @@
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+
+/// Token bucket rate limiter for outgoing RPC requests
+///
+/// The bucket holds up to one second worth of permits, so short bursts are allowed
+/// while the sustained rate stays at `requests_per_second`.
+///
+/// # Example
+/// ```
+/// let limiter = RateLimiter::new(10);
+/// async {
+/// limiter.acquire().await;
+/// // issue the RPC call
+/// };
+/// ```
+#[derive(Debug)]
+pub struct RateLimiter {
+    rate: f64,
+    capacity: f64,
+    bucket: Mutex<Bucket>,
+}
+
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl RateLimiter {
+    /// Creates a limiter allowing `requests_per_second` requests (at least 1)
+    pub fn new(requests_per_second: u32) -> Self {
+        let rate = requests_per_second.max(1) as f64;
+        Self {
+            rate,
+            capacity: rate,
+            bucket: Mutex::new(Bucket {
+                tokens: rate,
+                last_refill: Instant::now(),
+            }),
+        }
+    }
+
+    /// Requests per second this limiter allows
+    pub fn requests_per_second(&self) -> f64 {
+        self.rate
+    }
+
+    /// Waits until a request permit is available and consumes it
+    pub async fn acquire(&self) {
+        loop {
+            let wait = {
+                let mut bucket = self.bucket.lock().await;
+                let now = Instant::now();
+                let elapsed = now.duration_since(bucket.last_refill).as_secs_f64();
+                bucket.tokens = (bucket.tokens + elapsed * self.rate).min(self.capacity);
+                bucket.last_refill = now;
+                if bucket.tokens >= 1.0 {
+                    bucket.tokens -= 1.0;
+                    return;
+                }
+                Duration::from_secs_f64((1.0 - bucket.tokens) / self.rate)
+            };
+            tokio::time::sleep(wait).await;
+        }
+    }
+
+    /// Waits for a permit from `rate_limiter` when one is configured
+    ///
+    /// Services keep an optional shared limiter; this is their throttle before each RPC call.
+    pub async fn acquire_opt(rate_limiter: &Option<Arc<RateLimiter>>) {
+        if let Some(rate_limiter) = rate_limiter {
+            rate_limiter.acquire().await;
+        }
+    }
+}
@@ -1,6 +1,6 @@
 use crate::{
-    EvmError,
-    abi::{IPancakeRouter02, ISwapRouter},
+    EvmError, PancakeResult,
+    abi::{IPancakeRouter02, IPancakeSmartRouter, ISwapRouter},
 };
 use ethers::{
     middleware::SignerMiddleware,
@@ -38,7 +38,7 @@ impl RouterService {
     pub fn v2_router_signer(
         &self,
         router_address: Address,
-    ) -> Result<IPancakeRouter02<SignerClient>, EvmError> {
+    ) -> PancakeResult<IPancakeRouter02<SignerClient>> {
         let wallet = self
             .evm
             .client
@@ -57,7 +57,7 @@ impl RouterService {
     pub fn v3_router_signer(
         &self,
         router_address: Address,
-    ) -> Result<ISwapRouter<SignerClient>, EvmError> {
+    ) -> PancakeResult<ISwapRouter<SignerClient>> {
         let wallet = self
             .evm.client
             .wallet
@@ -70,6 +70,23 @@ impl RouterService {
         ))
     }
 
+    /// Get Smart Router contract instance with signer for transaction operations
+    pub fn smart_router_signer(
+        &self,
+        router_address: Address,
+    ) -> PancakeResult<IPancakeSmartRouter<SignerClient>> {
+        let wallet = self
+            .evm.client
+            .wallet
+            .as_ref()
+            .ok_or_else(|| EvmError::WalletError("No wallet configured".to_string()))?;
+        let signer_middleware = SignerMiddleware::new(self.evm.client.provider.clone(), wallet.clone());
+        Ok(IPancakeSmartRouter::new(
+            router_address,
+            Arc::new(signer_middleware),
+        ))
+    }
+
     /// Swap exact tokens for tokens supporting fee on transfer tokens
     ///
     /// # Example
@@ -106,7 +123,7 @@ impl RouterService {
         amount_out_min: U256,
         path: Vec<Address>,
         deadline: u64,
-    ) -> Result<ethers::types::H256, EvmError> {
+    ) -> PancakeResult<ethers::types::H256> {
         let router = self.v2_router_signer(router_address)?;
         let wallet_address = self.evm.client.wallet.as_ref().unwrap().address();
 
@@ -161,7 +178,7 @@ impl RouterService {
         path: Vec<Address>,
         value: U256,
         deadline: u64,
-    ) -> Result<ethers::types::H256, EvmError> {
+    ) -> PancakeResult<ethers::types::H256> {
         let router = self.v2_router_signer(router_address)?;
         let wallet_address = self.evm.client.wallet.as_ref().unwrap().address();
 
@@ -220,7 +237,7 @@ impl RouterService {
         amount_out_min: U256,
         path: Vec<Address>,
         deadline: u64,
-    ) -> Result<ethers::types::H256, EvmError> {
+    ) -> PancakeResult<ethers::types::H256> {
         let router = self.v2_router_signer(router_address)?;
         let wallet_address = self.evm.client.wallet.as_ref().unwrap().address();
 
@@ -255,10 +272,7 @@ impl RouterService {
     /// Ok(())
     /// }
     /// ```
-    pub async fn get_factory_from_router(
-        &self,
-        router_address: Address,
-    ) -> Result<Address, EvmError> {
+    pub async fn get_factory_from_router(&self, router_address: Address) -> PancakeResult<Address> {
         let router = self.v2_router(router_address);
         router
             .factory()
@@ -283,7 +297,7 @@ impl RouterService {
     pub async fn get_weth_address_from_router(
         &self,
         router_address: Address,
-    ) -> Result<Address, EvmError> {
+    ) -> PancakeResult<Address> {
         let router = self.v2_router(router_address);
         router
             .weth()
@@ -317,7 +331,7 @@ impl RouterService {
         amount_a: U256,
         reserve_a: U256,
         reserve_b: U256,
-    ) -> Result<U256, EvmError> {
+    ) -> PancakeResult<U256> {
         let router = self.v2_router(router_address);
         router
             .quote(amount_a, reserve_a, reserve_b)
@@ -351,7 +365,7 @@ impl RouterService {
         amount_in: U256,
         reserve_in: U256,
         reserve_out: U256,
-    ) -> Result<U256, EvmError> {
+    ) -> PancakeResult<U256> {
         let router = self.v2_router(router_address);
         router
             .get_amount_out(amount_in, reserve_in, reserve_out)
@@ -385,7 +399,7 @@ impl RouterService {
         amount_out: U256,
         reserve_in: U256,
         reserve_out: U256,
-    ) -> Result<U256, EvmError> {
+    ) -> PancakeResult<U256> {
         let router = self.v2_router(router_address);
         router
             .get_amount_in(amount_out, reserve_in, reserve_out)
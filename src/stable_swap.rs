@@ -0,0 +1,224 @@
+use crate::{
+    EvmError, PancakeResult, PancakeSwapConfig,
+    abi::{IPancakeStableSwap, IPancakeStableSwapFactory},
+};
+use ethers::{
+    contract::ContractCall,
+    middleware::SignerMiddleware,
+    providers::{Http, Provider},
+    signers::{Signer, Wallet},
+    types::{Address, BlockNumber, H256, U256},
+};
+use evm_sdk::Evm;
+use std::sync::Arc;
+
+type SignerClient =
+    SignerMiddleware<Arc<Provider<Http>>, Wallet<ethers::core::k256::ecdsa::SigningKey>>;
+
+/// PancakeSwap StableSwap service
+///
+/// StableSwap pools trade pegged assets (USDT/USDC/BUSD) along a flatter curve than the
+/// constant-product V2 and V3 pools, so they quote better rates for stable-stable swaps.
+/// Pools are looked up through the StableSwap factory, which is only deployed on BSC.
+pub struct StableSwapService {
+    evm: Arc<Evm>,
+}
+
+impl StableSwapService {
+    /// create a StableSwap service
+    pub fn new(evm: Arc<Evm>) -> Self {
+        Self { evm }
+    }
+
+    /// Get the StableSwap pool for a token pair
+    ///
+    /// Returns `EvmError::CalculationError` when the factory has no pool for the pair.
+    ///
+    /// # Example
+    /// ```
+    /// let pool = stable_swap.get_pool(usdt, busd).await?;
+    /// ```
+    pub async fn get_pool(&self, token_a: Address, token_b: Address) -> PancakeResult<Address> {
+        let chain = self
+            .evm
+            .client
+            .evm_type
+            .ok_or_else(|| EvmError::ConfigError("Chain type not configured".to_string()))?;
+        let factory = IPancakeStableSwapFactory::new(
+            PancakeSwapConfig::stable_swap_factory_address(chain)?,
+            self.evm.client.provider.clone(),
+        );
+        let info = factory
+            .get_pair_info(token_a, token_b)
+            .call()
+            .await
+            .map_err(|e| {
+                EvmError::ContractError(format!("Failed to get StableSwap pair info: {}", e))
+            })?;
+        if info.swap_contract.is_zero() {
+            return Err(EvmError::CalculationError(
+                "No StableSwap pool for pair".to_string(),
+            ));
+        }
+        Ok(info.swap_contract)
+    }
+
+    /// Quote the output of swapping `amount_in` of `token_in` for `token_out`
+    ///
+    /// The pool is quoted at `block` when given, otherwise at the latest block.
+    ///
+    /// # Example
+    /// ```
+    /// use ethers::types::U256;
+    ///
+    /// let amount_in = U256::from(1000000000000000000u64);
+    /// let amount_out = stable_swap.get_amount_out(usdt, busd, amount_in, None).await?;
+    /// ```
+    pub async fn get_amount_out(
+        &self,
+        token_in: Address,
+        token_out: Address,
+        amount_in: U256,
+        block: Option<BlockNumber>,
+    ) -> PancakeResult<U256> {
+        let pool_address = self.get_pool(token_in, token_out).await?;
+        let (i, j) = self.coin_indices(pool_address, token_in, token_out).await?;
+        self.quote(pool_address, i, j, amount_in, block).await
+    }
+
+    /// Swap `amount_in` of `token_in` for `token_out` through the pair's StableSwap pool
+    ///
+    /// The output is sent to the wallet. The pool (see `get_pool`) must already be approved
+    /// to spend `amount_in`.
+    ///
+    /// # Example
+    /// ```
+    /// use ethers::types::U256;
+    ///
+    /// let amount_in = U256::from(1000000000000000000u64);
+    /// let tx_hash = stable_swap.swap(usdt, busd, amount_in, 0.1).await?;
+    /// ```
+    pub async fn swap(
+        &self,
+        token_in: Address,
+        token_out: Address,
+        amount_in: U256,
+        slippage_percent: f64,
+    ) -> PancakeResult<H256> {
+        let expected_out = self
+            .get_amount_out(token_in, token_out, amount_in, None)
+            .await?;
+        let slippage_bps = (slippage_percent * 100.0) as u64;
+        let amount_out_min = expected_out * U256::from(10_000u64.saturating_sub(slippage_bps))
+            / U256::from(10_000u64);
+        let tx = self
+            .exchange_call(token_in, token_out, amount_in, amount_out_min)
+            .await?;
+        let pending_tx = tx.send().await.map_err(|e| {
+            EvmError::TransactionError(format!("Failed to execute StableSwap swap: {}", e))
+        })?;
+        Ok(pending_tx.tx_hash())
+    }
+
+    /// Build the pool's `exchange` call for `amount_in` of `token_in`, signed by the wallet,
+    /// without sending it
+    ///
+    /// For callers that send transactions themselves, e.g. to set the gas limit first. The
+    /// output goes to the sender, and the pool must already be approved to spend `amount_in`.
+    ///
+    /// # Example
+    /// ```
+    /// use ethers::types::U256;
+    ///
+    /// let amount_in = U256::from(1000000000000000000u64);
+    /// let tx = stable_swap.exchange_call(usdt, busd, amount_in, amount_out_min).await?;
+    /// let pending_tx = tx.send().await?;
+    /// ```
+    pub async fn exchange_call(
+        &self,
+        token_in: Address,
+        token_out: Address,
+        amount_in: U256,
+        amount_out_min: U256,
+    ) -> PancakeResult<ContractCall<SignerClient, ()>> {
+        let wallet = self
+            .evm
+            .client
+            .wallet
+            .as_ref()
+            .ok_or_else(|| EvmError::WalletError("No wallet configured".to_string()))?;
+        let pool_address = self.get_pool(token_in, token_out).await?;
+        let (i, j) = self.coin_indices(pool_address, token_in, token_out).await?;
+        let signer_middleware =
+            SignerMiddleware::new(self.evm.client.provider.clone(), wallet.clone());
+        let pool = IPancakeStableSwap::new(pool_address, Arc::new(signer_middleware));
+        Ok(pool
+            .exchange(i, j, amount_in, amount_out_min)
+            .from(wallet.address()))
+    }
+
+    /// Whether `owner` has approved the pair's StableSwap pool to spend `amount` of `token_in`
+    ///
+    /// # Example
+    /// ```
+    /// let approved = stable_swap.is_approved(usdt, busd, owner, amount_in).await?;
+    /// ```
+    pub async fn is_approved(
+        &self,
+        token_in: Address,
+        token_out: Address,
+        owner: Address,
+        amount: U256,
+    ) -> PancakeResult<bool> {
+        let pool_address = self.get_pool(token_in, token_out).await?;
+        let allowance = crate::abi::IERC20::new(token_in, self.evm.client.provider.clone())
+            .allowance(owner, pool_address)
+            .call()
+            .await
+            .map_err(|e| EvmError::ContractError(format!("Failed to get allowance: {}", e)))?;
+        Ok(allowance >= amount)
+    }
+
+    /// `get_dy` of coin `j` for `amount_in` of coin `i`
+    async fn quote(
+        &self,
+        pool_address: Address,
+        i: U256,
+        j: U256,
+        amount_in: U256,
+        block: Option<BlockNumber>,
+    ) -> PancakeResult<U256> {
+        let pool = IPancakeStableSwap::new(pool_address, self.evm.client.provider.clone());
+        let mut call = pool.get_dy(i, j, amount_in);
+        if let Some(block) = block {
+            call = call.block(block);
+        }
+        call.call()
+            .await
+            .map_err(|e| EvmError::ContractError(format!("Failed to quote StableSwap: {}", e)))
+    }
+
+    /// Indices of `token_in` and `token_out` among the pool's two coins
+    async fn coin_indices(
+        &self,
+        pool_address: Address,
+        token_in: Address,
+        token_out: Address,
+    ) -> PancakeResult<(U256, U256)> {
+        let pool = IPancakeStableSwap::new(pool_address, self.evm.client.provider.clone());
+        let coin0 = pool
+            .coins(U256::zero())
+            .call()
+            .await
+            .map_err(|e| EvmError::ContractError(format!("Failed to get pool coin: {}", e)))?;
+        if coin0 == token_in {
+            Ok((U256::zero(), U256::one()))
+        } else if coin0 == token_out {
+            Ok((U256::one(), U256::zero()))
+        } else {
+            Err(EvmError::InvalidInput(
+                "Tokens are not in the StableSwap pool".to_string(),
+            ))
+        }
+    }
+}
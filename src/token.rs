@@ -0,0 +1,101 @@
+use crate::{EvmError, PancakeResult, abi::IERC20};
+use ethers::{
+    middleware::SignerMiddleware,
+    types::{Address, H256, U256},
+};
+use evm_sdk::Evm;
+use std::sync::Arc;
+
+/// Service for basic ERC20 token reads and transfers
+pub struct TokenService {
+    evm: Arc<Evm>,
+}
+
+impl TokenService {
+    /// Creates a new TokenService instance
+    pub fn new(evm: Arc<Evm>) -> Self {
+        Self { evm }
+    }
+
+    /// Gets the token balance of an owner
+    ///
+    /// # Example
+    /// ```
+    /// use ethers::types::Address;
+    /// use std::sync::Arc;
+    /// let token_service = TokenService::new(Arc::clone(&client));
+    /// let token = "0x...".parse::<Address>().unwrap();
+    /// let owner = "0x...".parse::<Address>().unwrap();
+    /// async {
+    /// let balance = token_service.balance_of(token, owner).await?;
+    /// Ok::<(), EvmError>(())
+    /// };
+    /// ```
+    pub async fn balance_of(&self, token: Address, owner: Address) -> PancakeResult<U256> {
+        let erc20 = IERC20::new(token, self.evm.client.provider.clone());
+        erc20
+            .balance_of(owner)
+            .call()
+            .await
+            .map_err(|e| EvmError::ContractError(format!("Failed to get balance: {}", e)))
+    }
+
+    /// Gets the amount a spender is allowed to transfer on behalf of an owner
+    ///
+    /// # Example
+    /// ```
+    /// use ethers::types::Address;
+    /// use std::sync::Arc;
+    /// let token_service = TokenService::new(Arc::clone(&client));
+    /// let token = "0x...".parse::<Address>().unwrap();
+    /// let owner = "0x...".parse::<Address>().unwrap();
+    /// let spender = "0x10ED43C718714eb63d5aA57B78B54704E256024E".parse::<Address>().unwrap();
+    /// async {
+    /// let allowance = token_service.allowance(token, owner, spender).await?;
+    /// Ok::<(), EvmError>(())
+    /// };
+    /// ```
+    pub async fn allowance(
+        &self,
+        token: Address,
+        owner: Address,
+        spender: Address,
+    ) -> PancakeResult<U256> {
+        let erc20 = IERC20::new(token, self.evm.client.provider.clone());
+        erc20
+            .allowance(owner, spender)
+            .call()
+            .await
+            .map_err(|e| EvmError::ContractError(format!("Failed to get allowance: {}", e)))
+    }
+
+    /// Transfers tokens from the configured wallet to a recipient
+    ///
+    /// # Example
+    /// ```
+    /// use ethers::types::{Address, U256};
+    /// use std::sync::Arc;
+    /// let token_service = TokenService::new(Arc::clone(&client));
+    /// let token = "0x...".parse::<Address>().unwrap();
+    /// let to = "0x...".parse::<Address>().unwrap();
+    /// async {
+    /// let tx_hash = token_service.transfer(token, to, U256::from(10).pow(18.into())).await?;
+    /// Ok::<(), EvmError>(())
+    /// };
+    /// ```
+    pub async fn transfer(&self, token: Address, to: Address, amount: U256) -> PancakeResult<H256> {
+        if self.evm.client.wallet.is_none() {
+            return Err(EvmError::WalletError("No wallet configured".to_string()));
+        }
+        let wallet = self.evm.client.wallet.as_ref().unwrap();
+        let signer_middleware =
+            SignerMiddleware::new(self.evm.client.provider.clone(), wallet.clone());
+        let erc20 = IERC20::new(token, Arc::new(signer_middleware));
+        let tx = erc20.transfer(to, amount);
+        let pending_tx = tx
+            .send()
+            .await
+            .map_err(|e| EvmError::TransactionError(format!("Failed to transfer tokens: {}", e)))?;
+        Ok(pending_tx.tx_hash())
+    }
+}
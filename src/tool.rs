@@ -1,5 +1,6 @@
 use crate::types::{
-    BurnEvent, MintEvent, PairCreatedEvent, SwapEvent, V3BurnEvent, V3MintEvent, V3SwapEvent,
+    BurnEvent, MintEvent, PairCreatedEvent, SwapEvent, TransferEvent, V3BurnEvent, V3MintEvent,
+    V3SwapEvent,
 };
 use ethers::types::{H160, U256};
 
@@ -187,6 +188,31 @@ pub mod event_parsers {
         })
     }
 
+    /// Parses a `Transfer(address,address,uint256)` log
+    ///
+    /// Handles the ERC20 layout (value in data) and the layout with an indexed
+    /// third argument used by some NFT contracts (value in topics[3]).
+    pub fn parse_transfer_log(log: &Log) -> Result<TransferEvent, Box<dyn std::error::Error>> {
+        if log.topics.len() < 3 {
+            return Err("Invalid transfer log: insufficient topics".into());
+        }
+
+        let from = H160::from_slice(&log.topics[1].as_bytes()[12..]);
+        let to = H160::from_slice(&log.topics[2].as_bytes()[12..]);
+
+        let value = if log.topics.len() >= 4 {
+            U256::from_big_endian(log.topics[3].as_bytes())
+        } else {
+            let data = log.data.clone().to_vec();
+            if data.len() < 32 {
+                return Err("Invalid transfer log: insufficient data".into());
+            }
+            U256::from_big_endian(&data[0..32])
+        };
+
+        Ok(TransferEvent { from, to, value })
+    }
+
     fn bytes_to_i24(bytes: &[u8]) -> i32 {
         if bytes.len() != 3 {
             return 0;
@@ -205,10 +231,22 @@ pub mod event_parsers {
 pub mod math_utils {
     use super::*;
 
+    /// Swap fee of Uniswap V2 style pairs (0.3%), in basis points
+    pub const DEFAULT_V2_FEE_BPS: u32 = 30;
+
+    /// Swap fee of PancakeSwap V2 pairs (0.25%), in basis points
+    pub const PANCAKE_V2_FEE_BPS: u32 = 25;
+
+    /// Output of a V2 swap of `amount_in` against the given reserves, as the pair's
+    /// `getAmountOut` computes it with a `fee_bps` swap fee
+    ///
+    /// Use `PANCAKE_V2_FEE_BPS` to match the PancakeSwap V2 router and `DEFAULT_V2_FEE_BPS` for
+    /// Uniswap V2 forks.
     pub fn calculate_amount_out(
         amount_in: U256,
         reserve_in: U256,
         reserve_out: U256,
+        fee_bps: u32,
     ) -> Result<U256, Box<dyn std::error::Error>> {
         if amount_in.is_zero() {
             return Ok(U256::zero());
@@ -216,10 +254,13 @@ pub mod math_utils {
         if reserve_in.is_zero() || reserve_out.is_zero() {
             return Err("Reserves cannot be zero".into());
         }
+        if fee_bps >= 10_000 {
+            return Err("Fee must be below 10000 bps".into());
+        }
 
-        let amount_in_with_fee = amount_in * U256::from(997);
+        let amount_in_with_fee = amount_in * U256::from(10_000 - fee_bps);
         let numerator = amount_in_with_fee * reserve_out;
-        let denominator = reserve_in * U256::from(1000) + amount_in_with_fee;
+        let denominator = reserve_in * U256::from(10_000) + amount_in_with_fee;
 
         if denominator.is_zero() {
             return Err("Denominator is zero".into());
@@ -228,10 +269,13 @@ pub mod math_utils {
         Ok(numerator / denominator)
     }
 
+    /// Input needed for a V2 swap to return `amount_out` against the given reserves, as
+    /// the pair's `getAmountIn` computes it with a `fee_bps` swap fee
     pub fn calculate_amount_in(
         amount_out: U256,
         reserve_in: U256,
         reserve_out: U256,
+        fee_bps: u32,
     ) -> Result<U256, Box<dyn std::error::Error>> {
         if amount_out.is_zero() {
             return Ok(U256::zero());
@@ -242,9 +286,12 @@ pub mod math_utils {
         if amount_out >= reserve_out {
             return Err("Insufficient reserve out".into());
         }
+        if fee_bps >= 10_000 {
+            return Err("Fee must be below 10000 bps".into());
+        }
 
-        let numerator = reserve_in * amount_out * U256::from(1000);
-        let denominator = (reserve_out - amount_out) * U256::from(997);
+        let numerator = reserve_in * amount_out * U256::from(10_000);
+        let denominator = (reserve_out - amount_out) * U256::from(10_000 - fee_bps);
 
         if denominator.is_zero() {
             return Err("Denominator is zero".into());
@@ -253,8 +300,105 @@ pub mod math_utils {
         Ok((numerator / denominator) + U256::one())
     }
 
+    /// Z-score of each value against the mean and population standard deviation
+    ///
+    /// Returns `None` for fewer than two values or a flat series, where every value is
+    /// equal. Summing a flat series can leave rounding noise in the mean, which would
+    /// otherwise divide by a near-zero deviation and inflate the scores.
+    pub fn z_scores(values: &[f64]) -> Option<Vec<f64>> {
+        if values.len() < 2 || values.iter().all(|v| *v == values[0]) {
+            return None;
+        }
+        let mean = values.iter().sum::<f64>() / values.len() as f64;
+        let variance = values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / values.len() as f64;
+        let std_dev = variance.sqrt();
+        Some(values.iter().map(|v| (v - mean) / std_dev).collect())
+    }
+
+    /// Converts a raw token amount to `f64`, scaled down by `decimals`
+    ///
+    /// The integer and fractional parts are split with `U256` division and converted
+    /// separately, so values above `u128::MAX` never panic and the fraction keeps its
+    /// precision even when the integer part is large. Use `decimals = 0` for plain amounts.
+    pub fn u256_to_f64(value: U256, decimals: u8) -> f64 {
+        // 10^77 is the largest power of ten that fits in a U256; scale the rest as a float
+        if decimals > 77 {
+            return u256_to_f64(value, 77) / 10_f64.powi(decimals as i32 - 77);
+        }
+        let (integer, fraction) = value.div_mod(U256::exp10(decimals as usize));
+        limbs_to_f64(integer) + limbs_to_f64(fraction) / 10_f64.powi(decimals as i32)
+    }
+
+    /// Formats a raw token amount as an exact decimal string, scaled down by `decimals`
+    ///
+    /// Trailing fractional zeros are dropped, and `decimals = 0` yields a plain integer.
+    /// Works on the decimal digits, so any `decimals` is handled without overflow.
+    pub fn format_units(value: U256, decimals: u8) -> String {
+        let digits = value.to_string();
+        let decimals = decimals as usize;
+        if decimals == 0 {
+            return digits;
+        }
+        let padded = format!("{:0>width$}", digits, width = decimals + 1);
+        let (integer, fraction) = padded.split_at(padded.len() - decimals);
+        let fraction = fraction.trim_end_matches('0');
+        if fraction.is_empty() {
+            integer.to_string()
+        } else {
+            format!("{}.{}", integer, fraction)
+        }
+    }
+
+    /// Parses a decimal string such as `"1.5"` into a raw token amount with `decimals`
+    ///
+    /// Fails with `EvmError::InvalidInput` on malformed input, on more fractional digits
+    /// than `decimals` allows (trailing zeros excepted, so `"3.0"` parses with 0 decimals),
+    /// and when the result does not fit in a `U256`.
+    pub fn parse_units(amount: &str, decimals: u8) -> crate::PancakeResult<U256> {
+        let invalid = || crate::EvmError::InvalidInput(format!("Invalid token amount: {}", amount));
+        let amount = amount.trim();
+        let (integer, fraction) = amount.split_once('.').unwrap_or((amount, ""));
+        if integer.is_empty() && fraction.is_empty() {
+            return Err(invalid());
+        }
+        if !integer
+            .chars()
+            .chain(fraction.chars())
+            .all(|c| c.is_ascii_digit())
+        {
+            return Err(invalid());
+        }
+        let fraction = fraction.trim_end_matches('0');
+        if fraction.len() > decimals as usize {
+            return Err(crate::EvmError::InvalidInput(format!(
+                "Amount {} has more than {} decimal places",
+                amount, decimals
+            )));
+        }
+        let digits = format!(
+            "{}{}{}",
+            integer,
+            fraction,
+            "0".repeat(decimals as usize - fraction.len())
+        );
+        let digits = digits.trim_start_matches('0');
+        if digits.is_empty() {
+            return Ok(U256::zero());
+        }
+        U256::from_dec_str(digits)
+            .map_err(|_| crate::EvmError::InvalidInput(format!("Amount {} overflows U256", amount)))
+    }
+
+    fn limbs_to_f64(value: U256) -> f64 {
+        value
+            .0
+            .iter()
+            .rev()
+            .fold(0.0, |acc, limb| acc * 2.0_f64.powi(64) + *limb as f64)
+    }
+
     pub fn calculate_v3_price(sqrt_price_x96: U256) -> f64 {
-        let price = (sqrt_price_x96.as_u128() as f64).powi(2) / (2.0_f64.powi(192));
+        let price = u256_to_f64(sqrt_price_x96, 0).powi(2) / (2.0_f64.powi(192));
         price
     }
 
@@ -262,19 +406,87 @@ pub mod math_utils {
         1.0001_f64.powi(tick)
     }
 
+    /// Rounds `tick` to the nearest multiple of `tick_spacing` that stays inside the V3 tick
+    /// range, so it can be used as a position bound
+    pub fn nearest_usable_tick(tick: i32, tick_spacing: i32) -> i32 {
+        use crate::global::{MAX_TICK, MIN_TICK};
+        if tick_spacing <= 0 {
+            return tick;
+        }
+        let rounded = (tick as f64 / tick_spacing as f64).round() as i32 * tick_spacing;
+        if rounded < MIN_TICK {
+            rounded + tick_spacing
+        } else if rounded > MAX_TICK {
+            rounded - tick_spacing
+        } else {
+            rounded
+        }
+    }
+
+    /// Computes a V3 `sqrtPriceLimitX96` allowing the pool price to move at most
+    /// `max_impact_percent` from `current_sqrt_price_x96`
+    ///
+    /// `zero_for_one` is true when selling token0 (price falls, so the limit is below the
+    /// current price). Price is the square of the sqrt price, so the sqrt price moves by
+    /// `sqrt(1 -/+ impact)`. The result is clamped inside the pool's valid sqrt price range.
+    pub fn calculate_sqrt_price_limit_x96(
+        current_sqrt_price_x96: U256,
+        max_impact_percent: f64,
+        zero_for_one: bool,
+    ) -> U256 {
+        const SCALE: u64 = 1_000_000_000;
+        let min_sqrt_ratio = U256::from(4295128739u64) + U256::one();
+        let max_sqrt_ratio =
+            U256::from_dec_str("1461446703485210103287273052203988822378723970342").unwrap()
+                - U256::one();
+        let impact = (max_impact_percent / 100.0).clamp(0.0, 1.0);
+        let factor = if zero_for_one {
+            (1.0 - impact).sqrt()
+        } else {
+            (1.0 + impact).sqrt()
+        };
+        let limit =
+            current_sqrt_price_x96 * U256::from((factor * SCALE as f64) as u64) / U256::from(SCALE);
+        limit.clamp(min_sqrt_ratio, max_sqrt_ratio)
+    }
+
+    /// Percentage by which the execution price `amount_out / amount_in` falls short of the
+    /// spot price `reserve_out / reserve_in` of a V2 pair
+    ///
+    /// Both prices are per unit, so any `amount_in` is compared fairly. The result includes
+    /// the swap fee and is never negative.
+    pub fn calculate_price_impact(
+        amount_in: U256,
+        amount_out: U256,
+        reserve_in: U256,
+        reserve_out: U256,
+    ) -> Result<f64, Box<dyn std::error::Error>> {
+        if amount_in.is_zero() {
+            return Err("Amount in cannot be zero".into());
+        }
+        if reserve_in.is_zero() || reserve_out.is_zero() {
+            return Err("Reserves cannot be zero".into());
+        }
+        let spot_price = u256_to_f64(reserve_out, 0) / u256_to_f64(reserve_in, 0);
+        let execution_price = u256_to_f64(amount_out, 0) / u256_to_f64(amount_in, 0);
+        Ok(((1.0 - execution_price / spot_price) * 100.0).max(0.0))
+    }
+
     pub fn calculate_slippage(expected_amount: U256, actual_amount: U256) -> f64 {
         if expected_amount.is_zero() {
             return 0.0;
         }
 
-        let expected = expected_amount.as_u128() as f64;
-        let actual = actual_amount.as_u128() as f64;
+        let expected = u256_to_f64(expected_amount, 0);
+        let actual = u256_to_f64(actual_amount, 0);
 
         ((expected - actual) / expected * 100.0).abs()
     }
 }
 
 pub mod address_utils {
+    use crate::{EvmError, PancakeResult};
+    use ethers::providers::{Http, Middleware, Provider};
     use std::str::FromStr;
 
     use super::*;
@@ -317,6 +529,15 @@ pub mod address_utils {
 
         H160::from_str(address).is_ok()
     }
+
+    /// Checks whether an address has deployed code, i.e. is a contract rather than an EOA
+    pub async fn is_contract(provider: &Provider<Http>, address: H160) -> PancakeResult<bool> {
+        let code = provider
+            .get_code(address, None)
+            .await
+            .map_err(|e| EvmError::ProviderError(format!("Failed to get code: {}", e)))?;
+        Ok(!code.is_empty())
+    }
 }
 
 pub mod time_utils {
@@ -337,3 +558,146 @@ pub mod time_utils {
         current_timestamp() > deadline
     }
 }
+
+pub mod tx_utils {
+    use crate::{EvmError, PancakeResult};
+    use ethers::providers::{Http, Middleware, Provider};
+    use ethers::types::{H256, TransactionReceipt};
+    use std::time::Duration;
+
+    /// Timeout used by methods that confirm transactions without taking a timeout argument
+    pub const DEFAULT_CONFIRMATION_TIMEOUT: Duration = Duration::from_secs(120);
+
+    const RECEIPT_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+    /// Polls for a transaction receipt until it has `confirmations` blocks on top (1 = mined)
+    ///
+    /// Returns `EvmError::TransactionError("confirmation timeout")` if `timeout` elapses first.
+    pub async fn wait_for_receipt(
+        provider: &Provider<Http>,
+        tx_hash: H256,
+        confirmations: u64,
+        timeout: Duration,
+    ) -> PancakeResult<TransactionReceipt> {
+        let poll = async {
+            loop {
+                let receipt = provider
+                    .get_transaction_receipt(tx_hash)
+                    .await
+                    .map_err(|e| {
+                        EvmError::ProviderError(format!("Failed to get receipt: {}", e))
+                    })?;
+                if let Some(receipt) = receipt
+                    && let Some(block_number) = receipt.block_number
+                {
+                    let current_block = provider.get_block_number().await.map_err(|e| {
+                        EvmError::ProviderError(format!("Failed to get block number: {}", e))
+                    })?;
+                    if current_block.as_u64() + 1 >= block_number.as_u64() + confirmations {
+                        return Ok(receipt);
+                    }
+                }
+                tokio::time::sleep(RECEIPT_POLL_INTERVAL).await;
+            }
+        };
+        tokio::time::timeout(timeout, poll)
+            .await
+            .map_err(|_| EvmError::TransactionError("confirmation timeout".to_string()))?
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::math_utils::*;
+    use ethers::types::U256;
+
+    fn ether(amount: u64) -> U256 {
+        U256::from(amount) * U256::exp10(18)
+    }
+
+    #[test]
+    fn amounts_match_router_get_amount_out_and_in() {
+        // 1,000 / 300,000 pool, values from the router's getAmountOut / getAmountIn
+        let (reserve_in, reserve_out) = (ether(1_000), ether(300_000));
+        let cases = [
+            (
+                PANCAKE_V2_FEE_BPS,
+                "298951795583905054707",
+                "3352863764763078265",
+            ),
+            (
+                DEFAULT_V2_FEE_BPS,
+                "298802094311970964947",
+                "3354545241074393750",
+            ),
+        ];
+        for (fee_bps, amount_out, amount_in) in cases {
+            assert_eq!(
+                calculate_amount_out(ether(1), reserve_in, reserve_out, fee_bps).unwrap(),
+                U256::from_dec_str(amount_out).unwrap(),
+                "amount out at {} bps",
+                fee_bps
+            );
+            assert_eq!(
+                calculate_amount_in(ether(1_000), reserve_in, reserve_out, fee_bps).unwrap(),
+                U256::from_dec_str(amount_in).unwrap(),
+                "amount in at {} bps",
+                fee_bps
+            );
+        }
+    }
+
+    #[test]
+    fn z_scores_skip_flat_series() {
+        // Ten 0.1s sum to 0.9999999999999999, so the mean is not exactly 0.1
+        assert!(z_scores(&[0.1; 10]).is_none());
+        assert!(z_scores(&[1.0]).is_none());
+
+        let scores = z_scores(&[1.0, 2.0, 3.0, 4.0, 5.0]).unwrap();
+        let expected = [-1.4142, -0.7071, 0.0, 0.7071, 1.4142];
+        for (score, expected) in scores.iter().zip(expected) {
+            assert!((score - expected).abs() < 0.001, "z-score {}", score);
+        }
+    }
+
+    #[test]
+    fn units_round_trip_with_zero_decimals() {
+        assert_eq!(format_units(U256::from(42u64), 0), "42");
+        assert_eq!(parse_units("42", 0).unwrap(), U256::from(42u64));
+        assert_eq!(parse_units("3.0", 0).unwrap(), U256::from(3u64));
+        assert!(parse_units("3.5", 0).is_err());
+        assert_eq!(u256_to_f64(U256::from(42u64), 0), 42.0);
+    }
+
+    #[test]
+    fn units_round_trip_with_24_decimals() {
+        let raw = U256::from_dec_str("1500000000000000000000001").unwrap();
+        assert_eq!(format_units(raw, 24), "1.500000000000000000000001");
+        assert_eq!(parse_units("1.500000000000000000000001", 24).unwrap(), raw);
+        assert_eq!(
+            format_units(U256::from(5u64), 24),
+            "0.000000000000000000000005"
+        );
+        assert!((u256_to_f64(raw, 24) - 1.5).abs() < 1e-12);
+    }
+
+    #[test]
+    fn price_impact_is_per_unit_for_any_amount_in() {
+        let (reserve_in, reserve_out) = (ether(1_000), ether(300_000));
+        // 5 tokens in: 0.25% fee plus ~0.5% slippage on a 1000-token reserve
+        let amount_in = ether(5);
+        let amount_out =
+            calculate_amount_out(amount_in, reserve_in, reserve_out, PANCAKE_V2_FEE_BPS).unwrap();
+        let impact =
+            calculate_price_impact(amount_in, amount_out, reserve_in, reserve_out).unwrap();
+        assert!((impact - 0.7451).abs() < 0.001, "impact {}", impact);
+
+        // A tiny trade only pays the fee
+        let amount_in = U256::from(1_000_000u64);
+        let amount_out =
+            calculate_amount_out(amount_in, reserve_in, reserve_out, PANCAKE_V2_FEE_BPS).unwrap();
+        let impact =
+            calculate_price_impact(amount_in, amount_out, reserve_in, reserve_out).unwrap();
+        assert!((impact - 0.25).abs() < 0.001, "impact {}", impact);
+    }
+}
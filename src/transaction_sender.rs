@@ -0,0 +1,42 @@
+use crate::{EvmError, PancakeResult};
+use async_trait::async_trait;
+use ethers::middleware::SignerMiddleware;
+use ethers::providers::Middleware;
+use ethers::signers::Signer;
+use ethers::types::{H256, transaction::eip2718::TypedTransaction};
+
+/// Submits transactions built by the SDK
+///
+/// `PancakeSwapService` sends swaps through the signer of its `Evm` by default. Inject a
+/// different `Arc<dyn TransactionSender>` with `PancakeSwapService::with_transaction_sender`
+/// to relay them instead, e.g. through a Flashbots-style private RPC or an account-abstraction
+/// bundler. The transaction arrives unsigned, with `from` set to the service's wallet.
+///
+/// `SignerMiddleware` implements this, so a signer over a private RPC endpoint is enough to
+/// keep swaps out of the public mempool.
+///
+/// # Example
+/// ```
+/// let private_rpc = Provider::<Http>::try_from("https://rpc.flashbots.net")?;
+/// let sender = SignerMiddleware::new(private_rpc, wallet.clone());
+/// let service = PancakeSwapService::new(evm.clone()).with_transaction_sender(Arc::new(sender));
+/// ```
+#[async_trait]
+pub trait TransactionSender: Send + Sync {
+    /// Sends `tx` and returns its hash
+    async fn send(&self, tx: TypedTransaction) -> PancakeResult<H256>;
+}
+
+#[async_trait]
+impl<M, S> TransactionSender for SignerMiddleware<M, S>
+where
+    M: Middleware + 'static,
+    S: Signer + 'static,
+{
+    async fn send(&self, tx: TypedTransaction) -> PancakeResult<H256> {
+        let pending_tx = self.send_transaction(tx, None).await.map_err(|e| {
+            EvmError::TransactionError(format!("Failed to send transaction: {}", e))
+        })?;
+        Ok(pending_tx.tx_hash())
+    }
+}
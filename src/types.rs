@@ -1,4 +1,5 @@
-use ethers::types::{Address, U256};
+use crate::EvmError;
+use ethers::types::{Address, H256, Log, U256};
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum RouterVersion {
@@ -7,6 +8,28 @@ pub enum RouterVersion {
     Unknown,
 }
 
+/// Where an event was emitted, taken from its raw log
+///
+/// Block number, transaction hash and log index are `None` for pending logs.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct LogMeta {
+    pub block_number: Option<u64>,
+    pub transaction_hash: Option<H256>,
+    pub log_index: Option<u64>,
+    pub address: Address,
+}
+
+impl From<&Log> for LogMeta {
+    fn from(log: &Log) -> Self {
+        LogMeta {
+            block_number: log.block_number.map(|block| block.as_u64()),
+            transaction_hash: log.transaction_hash,
+            log_index: log.log_index.map(|index| index.as_u64()),
+            address: log.address,
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct SwapEvent {
     pub sender: Address,
@@ -71,6 +94,13 @@ pub struct V3BurnEvent {
     pub amount1: U256,
 }
 
+#[derive(Debug, Clone)]
+pub struct TransferEvent {
+    pub from: Address,
+    pub to: Address,
+    pub value: U256,
+}
+
 #[derive(Debug, Clone)]
 pub struct SwapResult {
     pub input_token: Address,
@@ -81,6 +111,8 @@ pub struct SwapResult {
     pub tx_hash: ethers::types::H256,
     pub gas_used: U256,
     pub gas_price: U256,
+    /// Output per unit of input actually received, adjusted for token decimals
+    pub average_price: f64,
 }
 
 #[derive(Debug, Clone)]
@@ -206,20 +238,100 @@ pub struct SwapPath {
 pub enum PoolVersion {
     V2,
     V3,
+    /// StableSwap pool for pegged pairs
+    Stable,
     Auto,
 }
 
+/// PancakeSwap V3 pool fee tier
+///
+/// PancakeSwap deploys V3 pools at these four tiers only; Uniswap's 0.3% tier (3000) does
+/// not exist. Raw fees convert with `FeeTier::try_from`, which rejects any other value with
+/// `EvmError::InvalidInput`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FeeTier {
+    /// 0.01%, for stable pairs
+    Lowest = 100,
+    /// 0.05%
+    Low = 500,
+    /// 0.25%
+    Medium = 2500,
+    /// 1%, for exotic pairs
+    High = 10000,
+}
+
+impl FeeTier {
+    /// Every tier, from lowest to highest fee
+    pub const ALL: [FeeTier; 4] = [
+        FeeTier::Lowest,
+        FeeTier::Low,
+        FeeTier::Medium,
+        FeeTier::High,
+    ];
+
+    /// Fee in hundredths of a basis point, as passed to the V3 contracts
+    pub fn as_u32(self) -> u32 {
+        self as u32
+    }
+
+    /// Tick spacing of pools at this tier
+    pub fn tick_spacing(self) -> i32 {
+        match self {
+            FeeTier::Lowest => 1,
+            FeeTier::Low => 10,
+            FeeTier::Medium => 50,
+            FeeTier::High => 200,
+        }
+    }
+}
+
+impl TryFrom<u32> for FeeTier {
+    type Error = EvmError;
+
+    fn try_from(fee: u32) -> Result<Self, Self::Error> {
+        FeeTier::ALL
+            .into_iter()
+            .find(|tier| tier.as_u32() == fee)
+            .ok_or_else(|| {
+                EvmError::InvalidInput(format!(
+                    "Invalid V3 fee tier {}; expected 100, 500, 2500 or 10000",
+                    fee
+                ))
+            })
+    }
+}
+
+impl From<FeeTier> for u32 {
+    fn from(tier: FeeTier) -> Self {
+        tier.as_u32()
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct PriceComparison {
     pub v2: Option<PriceInfo>,
     pub v3: Option<PriceInfo>,
+    /// StableSwap quote, only requested for stablecoin pairs
+    pub stable: Option<PriceInfo>,
     pub best: PriceSource,
+    pub fee_tier: Option<u32>,
 }
 
 #[derive(Debug, Clone)]
 pub enum PriceSource {
     V2,
     V3,
+    Stable,
+}
+
+/// Which pool versions `PancakeSwapService::swap` may route through
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RoutePreference {
+    V2Only,
+    V3Only,
+    /// Best quote of V2 and V3, and of StableSwap for stablecoin pairs
+    #[default]
+    Auto,
 }
 
 #[derive(Debug, Clone)]
@@ -350,3 +462,36 @@ pub enum RiskLevel {
     Medium,
     High,
 }
+
+#[derive(Debug, Clone)]
+pub struct PermitData {
+    pub token: Address,
+    pub owner: Address,
+    pub spender: Address,
+    pub value: U256,
+    pub deadline: U256,
+    pub v: u8,
+    pub r: H256,
+    pub s: H256,
+}
+
+/// Result of `PancakeSwapService::preflight_token`
+#[derive(Debug, Clone)]
+pub struct TokenPreflight {
+    pub transferable: bool,
+    pub likely_fee_on_transfer: bool,
+    pub estimated_tax_bps: Option<u32>,
+}
+
+/// Pre-trade summary returned by `PancakeSwapService::get_trade_preview`
+#[derive(Debug, Clone)]
+pub struct TradePreview {
+    pub best_version: PoolVersion,
+    pub fee_tier: Option<u32>,
+    pub route: Vec<Address>,
+    pub expected_out: U256,
+    pub min_out: U256,
+    pub price_impact_pct: f64,
+    pub gas_estimate: U256,
+    pub effective_price: f64,
+}
@@ -0,0 +1,309 @@
+use crate::{
+    EvmError, PancakeResult, PancakeSwapService,
+    abi::IUniversalRouter,
+    types::{PoolVersion, PriceSource},
+};
+use ethers::{
+    abi::{Token, encode},
+    middleware::SignerMiddleware,
+    types::{Address, Bytes, H160, H256, U256},
+};
+use evm_sdk::Evm;
+use std::sync::Arc;
+
+/// Universal Router command bytes
+///
+/// Each command is one byte in the `commands` argument of `execute`, with its ABI-encoded
+/// parameters at the same index in `inputs`. Setting the high bit (`FLAG_ALLOW_REVERT`)
+/// lets the transaction continue if that command reverts.
+pub mod commands {
+    pub const V3_SWAP_EXACT_IN: u8 = 0x00;
+    pub const V3_SWAP_EXACT_OUT: u8 = 0x01;
+    pub const V2_SWAP_EXACT_IN: u8 = 0x08;
+    pub const V2_SWAP_EXACT_OUT: u8 = 0x09;
+    pub const WRAP_ETH: u8 = 0x0b;
+    pub const UNWRAP_WETH: u8 = 0x0c;
+    pub const FLAG_ALLOW_REVERT: u8 = 0x80;
+}
+
+/// Recipient placeholder resolved by the router to the transaction sender
+pub const MSG_SENDER: H160 = H160([0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1]);
+
+/// Recipient placeholder resolved by the router to the router itself, for chaining commands
+pub const ADDRESS_THIS: H160 = H160([0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 2]);
+
+/// A single Universal Router command with its parameters
+#[derive(Debug, Clone)]
+pub enum UniversalRouterCommand {
+    /// Swap an exact input along a V3 path encoded with `encode_v3_path`
+    V3SwapExactIn {
+        recipient: Address,
+        amount_in: U256,
+        amount_out_min: U256,
+        path: Vec<u8>,
+        payer_is_user: bool,
+    },
+    /// Swap an exact input along a V2 token path
+    V2SwapExactIn {
+        recipient: Address,
+        amount_in: U256,
+        amount_out_min: U256,
+        path: Vec<Address>,
+        payer_is_user: bool,
+    },
+    /// Wrap the native token sent with the transaction
+    WrapEth {
+        recipient: Address,
+        amount_min: U256,
+    },
+    /// Unwrap the router's wrapped native balance
+    UnwrapWeth {
+        recipient: Address,
+        amount_min: U256,
+    },
+}
+
+impl UniversalRouterCommand {
+    /// Command byte for this command
+    pub fn command_byte(&self) -> u8 {
+        match self {
+            UniversalRouterCommand::V3SwapExactIn { .. } => commands::V3_SWAP_EXACT_IN,
+            UniversalRouterCommand::V2SwapExactIn { .. } => commands::V2_SWAP_EXACT_IN,
+            UniversalRouterCommand::WrapEth { .. } => commands::WRAP_ETH,
+            UniversalRouterCommand::UnwrapWeth { .. } => commands::UNWRAP_WETH,
+        }
+    }
+
+    /// ABI-encoded input for this command
+    pub fn encode_input(&self) -> Vec<u8> {
+        match self {
+            UniversalRouterCommand::V3SwapExactIn {
+                recipient,
+                amount_in,
+                amount_out_min,
+                path,
+                payer_is_user,
+            } => encode(&[
+                Token::Address(*recipient),
+                Token::Uint(*amount_in),
+                Token::Uint(*amount_out_min),
+                Token::Bytes(path.clone()),
+                Token::Bool(*payer_is_user),
+            ]),
+            UniversalRouterCommand::V2SwapExactIn {
+                recipient,
+                amount_in,
+                amount_out_min,
+                path,
+                payer_is_user,
+            } => encode(&[
+                Token::Address(*recipient),
+                Token::Uint(*amount_in),
+                Token::Uint(*amount_out_min),
+                Token::Array(path.iter().map(|token| Token::Address(*token)).collect()),
+                Token::Bool(*payer_is_user),
+            ]),
+            UniversalRouterCommand::WrapEth {
+                recipient,
+                amount_min,
+            }
+            | UniversalRouterCommand::UnwrapWeth {
+                recipient,
+                amount_min,
+            } => encode(&[Token::Address(*recipient), Token::Uint(*amount_min)]),
+        }
+    }
+}
+
+/// Encodes a V3 path as `token (20 bytes) | fee (3 bytes) | token | ...`
+pub fn encode_v3_path(tokens: &[Address], fees: &[u32]) -> PancakeResult<Vec<u8>> {
+    if tokens.len() < 2 || fees.len() != tokens.len() - 1 {
+        return Err(EvmError::InvalidInput(
+            "V3 path needs at least 2 tokens and one fee per hop".to_string(),
+        ));
+    }
+    let mut path = Vec::with_capacity(tokens.len() * 20 + fees.len() * 3);
+    for (i, token) in tokens.iter().enumerate() {
+        path.extend_from_slice(token.as_bytes());
+        if let Some(fee) = fees.get(i) {
+            path.extend_from_slice(&fee.to_be_bytes()[1..]);
+        }
+    }
+    Ok(path)
+}
+
+/// Service for batching swaps through the PancakeSwap Universal Router
+pub struct UniversalRouterService {
+    evm: Arc<Evm>,
+}
+
+impl UniversalRouterService {
+    /// Creates a new UniversalRouterService instance
+    pub fn new(evm: Arc<Evm>) -> Self {
+        Self { evm }
+    }
+
+    /// Encodes `execute(commands, inputs, deadline)` calldata for a list of commands
+    ///
+    /// # Example
+    /// ```
+    /// use ethers::types::U256;
+    ///
+    /// let commands = vec![
+    ///     UniversalRouterCommand::WrapEth { recipient: ADDRESS_THIS, amount_min: amount_in },
+    ///     UniversalRouterCommand::V2SwapExactIn {
+    ///         recipient: MSG_SENDER,
+    ///         amount_in,
+    ///         amount_out_min,
+    ///         path: vec![wbnb, busd],
+    ///         payer_is_user: false,
+    ///     },
+    /// ];
+    /// let calldata = service.encode_execute(router, &commands, deadline)?;
+    /// ```
+    pub fn encode_execute(
+        &self,
+        router_address: Address,
+        commands: &[UniversalRouterCommand],
+        deadline: u64,
+    ) -> PancakeResult<Bytes> {
+        let router = IUniversalRouter::new(router_address, self.evm.client.provider.clone());
+        let (command_bytes, inputs) = Self::split_commands(commands);
+        router
+            .execute(command_bytes, inputs, deadline.into())
+            .calldata()
+            .ok_or_else(|| EvmError::ContractError("Failed to encode execute call".to_string()))
+    }
+
+    /// Sends `execute(commands, inputs, deadline)`, attaching `value` of native token
+    ///
+    /// Commands with `payer_is_user` pull tokens through Permit2, so the wallet must have
+    /// approved Permit2 for the input token beforehand.
+    pub async fn execute(
+        &self,
+        router_address: Address,
+        commands: &[UniversalRouterCommand],
+        deadline: u64,
+        value: U256,
+    ) -> PancakeResult<H256> {
+        if self.evm.client.wallet.is_none() {
+            return Err(EvmError::WalletError("No wallet configured".to_string()));
+        }
+        let wallet = self.evm.client.wallet.as_ref().unwrap();
+        let signer_middleware =
+            SignerMiddleware::new(self.evm.client.provider.clone(), wallet.clone());
+        let router = IUniversalRouter::new(router_address, Arc::new(signer_middleware));
+        let (command_bytes, inputs) = Self::split_commands(commands);
+        let tx = router
+            .execute(command_bytes, inputs, deadline.into())
+            .value(value);
+        let pending_tx = tx.send().await.map_err(|e| {
+            EvmError::TransactionError(format!("Failed to execute universal router: {}", e))
+        })?;
+        Ok(pending_tx.tx_hash())
+    }
+
+    /// Swaps an exact input through whichever of V2 or V3 currently quotes the best output
+    ///
+    /// # Example
+    /// ```
+    /// use ethers::types::U256;
+    ///
+    /// let router = UNIVERSAL_ROUTER.parse()?;
+    /// let amount_in = U256::from(1000000000000000000u64);
+    /// let (version, tx_hash) = service
+    ///     .swap_exact_in(router, token_in, token_out, amount_in, 1.0)
+    ///     .await?;
+    /// println!("Swapped on {:?}: {:?}", version, tx_hash);
+    /// ```
+    pub async fn swap_exact_in(
+        &self,
+        router_address: Address,
+        token_in: Address,
+        token_out: Address,
+        amount_in: U256,
+        slippage_percent: f64,
+    ) -> PancakeResult<(PoolVersion, H256)> {
+        let pancake_service = PancakeSwapService::new(self.evm.clone());
+        let price_comparison = pancake_service
+            .get_best_price(token_in, token_out, amount_in, None)
+            .await?;
+        // Stablecoin pairs may quote best on StableSwap, which these commands do not cover;
+        // fall back to the better of V2 and V3
+        let best = match price_comparison.best {
+            PriceSource::Stable => match (&price_comparison.v2, &price_comparison.v3) {
+                (Some(v2), Some(v3)) if v2.amount_out > v3.amount_out => PriceSource::V2,
+                (_, Some(_)) => PriceSource::V3,
+                (Some(_), None) => PriceSource::V2,
+                (None, None) => {
+                    return Err(EvmError::CalculationError(
+                        "No V2 or V3 price available".to_string(),
+                    ));
+                }
+            },
+            best => best,
+        };
+        let (version, command) = match best {
+            PriceSource::V2 => {
+                let v2_info = price_comparison.v2.ok_or_else(|| {
+                    EvmError::CalculationError("V2 price not available".to_string())
+                })?;
+                let amount_out_min = pancake_service
+                    .calculate_amount_with_slippage(v2_info.amount_out, slippage_percent);
+                (
+                    PoolVersion::V2,
+                    UniversalRouterCommand::V2SwapExactIn {
+                        recipient: MSG_SENDER,
+                        amount_in,
+                        amount_out_min,
+                        path: vec![token_in, token_out],
+                        payer_is_user: true,
+                    },
+                )
+            }
+            PriceSource::V3 => {
+                let v3_info = price_comparison.v3.ok_or_else(|| {
+                    EvmError::CalculationError("V3 price not available".to_string())
+                })?;
+                let fee = price_comparison.fee_tier.ok_or_else(|| {
+                    EvmError::CalculationError("V3 fee tier not available".to_string())
+                })?;
+                let amount_out_min = pancake_service
+                    .calculate_amount_with_slippage(v3_info.amount_out, slippage_percent);
+                (
+                    PoolVersion::V3,
+                    UniversalRouterCommand::V3SwapExactIn {
+                        recipient: MSG_SENDER,
+                        amount_in,
+                        amount_out_min,
+                        path: encode_v3_path(&[token_in, token_out], &[fee])?,
+                        payer_is_user: true,
+                    },
+                )
+            }
+            PriceSource::Stable => {
+                return Err(EvmError::CalculationError(
+                    "StableSwap is not routed through the universal router".to_string(),
+                ));
+            }
+        };
+        let deadline = crate::tool::time_utils::calculate_deadline(30);
+        let tx_hash = self
+            .execute(router_address, &[command], deadline, U256::zero())
+            .await?;
+        Ok((version, tx_hash))
+    }
+
+    /// Splits commands into the `commands` byte string and the matching `inputs`
+    fn split_commands(commands: &[UniversalRouterCommand]) -> (Bytes, Vec<Bytes>) {
+        let command_bytes = commands
+            .iter()
+            .map(UniversalRouterCommand::command_byte)
+            .collect::<Vec<u8>>();
+        let inputs = commands
+            .iter()
+            .map(|command| Bytes::from(command.encode_input()))
+            .collect();
+        (Bytes::from(command_bytes), inputs)
+    }
+}
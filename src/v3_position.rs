@@ -1,10 +1,14 @@
 use crate::{
-    EvmError,
-    abi::{INonfungiblePositionManager, i_nonfungible_position_manager},
+    EvmError, PancakeResult,
+    abi::{INonfungiblePositionManager, IPancakeV3Pool, i_nonfungible_position_manager},
+    factory::FactoryService,
+    global::{MAX_TICK, MIN_TICK},
+    tool::math_utils::{nearest_usable_tick, u256_to_f64},
+    types::FeeTier,
 };
 use ethers::{
     middleware::SignerMiddleware,
-    types::{Address, U256},
+    types::{Address, U256, U512},
 };
 use evm_sdk::Evm;
 use std::sync::Arc;
@@ -28,12 +32,23 @@ pub struct V3Position {
 /// Service for managing Uniswap V3 positions
 pub struct V3PositionService {
     evm: Arc<Evm>,
+    snap_ticks: bool,
 }
 
 impl V3PositionService {
     /// Creates a new V3PositionService instance
     pub fn new(evm: Arc<Evm>) -> Self {
-        Self { evm: evm }
+        Self {
+            evm,
+            snap_ticks: false,
+        }
+    }
+
+    /// Rounds unaligned ticks in `create_position` to the nearest usable ticks instead of
+    /// rejecting them
+    pub fn with_tick_snapping(mut self, snap_ticks: bool) -> Self {
+        self.snap_ticks = snap_ticks;
+        self
     }
 
     /// Retrieves all V3 positions for a given user
@@ -63,7 +78,7 @@ impl V3PositionService {
         &self,
         nft_position_manager: Address,
         user_address: Address,
-    ) -> Result<Vec<V3Position>, EvmError> {
+    ) -> PancakeResult<Vec<V3Position>> {
         let nft_manager = INonfungiblePositionManager::new(
             nft_position_manager,
             self.evm.client.provider.clone(),
@@ -87,6 +102,174 @@ impl V3PositionService {
         Ok(positions)
     }
 
+    /// Reads a V3 pool's configuration as `(token0, token1, fee, tick_spacing)`
+    ///
+    /// Position ticks must be multiples of the tick spacing; round them with
+    /// `math_utils::nearest_usable_tick` before minting.
+    ///
+    /// # Params
+    /// pool_address - Address of the V3 pool
+    ///
+    /// # Example
+    /// ```rust
+    /// use ethers::types::Address;
+    /// use std::str::FromStr;
+    /// use std::sync::Arc;
+    /// use crate::{EvmClient, V3PositionService};
+    /// use crate::tool::math_utils::nearest_usable_tick;
+    ///
+    /// async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = Arc::new(EvmClient::new(EvmType::Bsc).await?);
+    /// let service = V3PositionService::new(client);
+    /// let pool = Address::from_str("0x36696169C63e42cd08ce11f5deeBbCeBae652050")?;
+    /// let (token0, token1, fee, tick_spacing) = service.get_pool_parameters(pool).await?;
+    /// let tick_lower = nearest_usable_tick(-1000, tick_spacing);
+    /// Ok(())
+    /// }
+    /// ```
+    pub async fn get_pool_parameters(
+        &self,
+        pool_address: Address,
+    ) -> PancakeResult<(Address, Address, u32, i32)> {
+        let pool = IPancakeV3Pool::new(pool_address, self.evm.client.provider.clone());
+        let token0 = pool
+            .token_0()
+            .call()
+            .await
+            .map_err(|e| EvmError::ContractError(format!("Failed to get token0: {}", e)))?;
+        let token1 = pool
+            .token_1()
+            .call()
+            .await
+            .map_err(|e| EvmError::ContractError(format!("Failed to get token1: {}", e)))?;
+        let fee = pool
+            .fee()
+            .call()
+            .await
+            .map_err(|e| EvmError::ContractError(format!("Failed to get pool fee: {}", e)))?;
+        let tick_spacing =
+            pool.tick_spacing().call().await.map_err(|e| {
+                EvmError::ContractError(format!("Failed to get tick spacing: {}", e))
+            })?;
+        Ok((token0, token1, fee, tick_spacing))
+    }
+
+    /// Reads a V3 pool's protocol fee setting as `(token0, token1)` fractions
+    ///
+    /// `slot0().feeProtocol` packs token0's fraction in the low 16 bits and token1's in the
+    /// high 16 bits. Each is the share of the swap fee taken by the protocol, in units of
+    /// 1/10000 (e.g. 3300 = 33% of the swap fee).
+    ///
+    /// # Params
+    /// pool_address - Address of the V3 pool
+    ///
+    /// # Example
+    /// ```rust
+    /// use ethers::types::Address;
+    /// use std::str::FromStr;
+    /// use std::sync::Arc;
+    /// use crate::{EvmClient, V3PositionService};
+    ///
+    /// async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = Arc::new(EvmClient::new(EvmType::Bsc).await?);
+    /// let service = V3PositionService::new(client);
+    /// let pool = Address::from_str("0x36696169C63e42cd08ce11f5deeBbCeBae652050")?;
+    /// let (fee_protocol0, fee_protocol1) = service.get_protocol_fees(pool).await?;
+    /// let (accrued0, accrued1) = service.get_accrued_protocol_fees(pool).await?;
+    /// Ok(())
+    /// }
+    /// ```
+    pub async fn get_protocol_fees(&self, pool_address: Address) -> PancakeResult<(u32, u32)> {
+        let pool = IPancakeV3Pool::new(pool_address, self.evm.client.provider.clone());
+        let (.., fee_protocol, _) = pool
+            .slot_0()
+            .call()
+            .await
+            .map_err(|e| EvmError::ContractError(format!("Failed to get slot0: {}", e)))?;
+        Ok((fee_protocol % 65536, fee_protocol >> 16))
+    }
+
+    /// Reads the protocol fees accrued by a V3 pool and not yet collected, as
+    /// `(amount0, amount1)`
+    pub async fn get_accrued_protocol_fees(
+        &self,
+        pool_address: Address,
+    ) -> PancakeResult<(U256, U256)> {
+        let pool = IPancakeV3Pool::new(pool_address, self.evm.client.provider.clone());
+        let (amount0, amount1) =
+            pool.protocol_fees().call().await.map_err(|e| {
+                EvmError::ContractError(format!("Failed to get protocol fees: {}", e))
+            })?;
+        Ok((amount0.into(), amount1.into()))
+    }
+
+    /// Computes the `amount1` that pairs with `amount0` for a position in
+    /// `[tick_lower, tick_upper]` at the pool's current price
+    ///
+    /// Uses the V3 liquidity math: the liquidity provided by `amount0` between the current
+    /// price and the upper bound determines the token1 needed between the lower bound and
+    /// the current price. Returns zero when the range is entirely above the current price
+    /// (token0 only), and an error when it is entirely below (token1 only).
+    ///
+    /// # Params
+    /// pool_address - Address of the V3 pool
+    /// tick_lower - Lower tick of the range
+    /// tick_upper - Upper tick of the range
+    /// amount0 - Amount of token0 to deposit
+    ///
+    /// # Example
+    /// ```rust
+    /// use ethers::types::{Address, U256};
+    /// use std::str::FromStr;
+    /// use std::sync::Arc;
+    /// use crate::{EvmClient, V3PositionService};
+    ///
+    /// async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = Arc::new(EvmClient::new(EvmType::Bsc).await?);
+    /// let service = V3PositionService::new(client);
+    /// let pool = Address::from_str("0x36696169C63e42cd08ce11f5deeBbCeBae652050")?;
+    /// let amount0 = U256::from(1000000000000000000u64);
+    /// let amount1 = service
+    ///     .compute_amounts_for_range(pool, -887200, 887200, amount0)
+    ///     .await?;
+    /// Ok(())
+    /// }
+    /// ```
+    pub async fn compute_amounts_for_range(
+        &self,
+        pool_address: Address,
+        tick_lower: i32,
+        tick_upper: i32,
+        amount0: U256,
+    ) -> PancakeResult<U256> {
+        if tick_lower >= tick_upper {
+            return Err(EvmError::InvalidInput(
+                "tick_lower must be below tick_upper".to_string(),
+            ));
+        }
+        let pool = IPancakeV3Pool::new(pool_address, self.evm.client.provider.clone());
+        let (sqrt_price_x96, ..) = pool
+            .slot_0()
+            .call()
+            .await
+            .map_err(|e| EvmError::ContractError(format!("Failed to get slot0: {}", e)))?;
+        let sqrt_price = u256_to_f64(sqrt_price_x96, 0) / 2.0_f64.powi(96);
+        let sqrt_lower = 1.0001_f64.powf(tick_lower as f64 / 2.0);
+        let sqrt_upper = 1.0001_f64.powf(tick_upper as f64 / 2.0);
+        if sqrt_price <= sqrt_lower {
+            return Ok(U256::zero());
+        }
+        if sqrt_price >= sqrt_upper {
+            return Err(EvmError::InvalidInput(
+                "Range is below the current price and takes only token1".to_string(),
+            ));
+        }
+        let liquidity =
+            u256_to_f64(amount0, 0) * sqrt_price * sqrt_upper / (sqrt_upper - sqrt_price);
+        let amount1 = liquidity * (sqrt_price - sqrt_lower);
+        Ok(U256::from(amount1 as u128))
+    }
+
     /// Retrieves detailed information for a specific position
     ///
     /// # Params
@@ -114,7 +297,7 @@ impl V3PositionService {
         &self,
         nft_position_manager: Address,
         token_id: U256,
-    ) -> Result<V3Position, EvmError> {
+    ) -> PancakeResult<V3Position> {
         let nft_manager = INonfungiblePositionManager::new(
             nft_position_manager,
             self.evm.client.provider.clone(),
@@ -139,13 +322,126 @@ impl V3PositionService {
         })
     }
 
+    /// Estimates the fees a position could collect now, as `(amount0, amount1)`
+    ///
+    /// `tokens_owed0/1` only cover fees up to the position's last interaction. This adds the
+    /// fees accrued since then, using the standard V3 formula: fee growth inside the range is
+    /// derived from the pool's `feeGrowthGlobal` and the `feeGrowthOutside` of both bounding
+    /// ticks, and the growth since `fee_growth_inside*_last_x128` is scaled by the position's
+    /// liquidity. Fee growth values wrap around as in the pool contract. `pool_address` must
+    /// be the pool of `position`.
+    ///
+    /// # Params
+    /// pool_address - Address of the position's V3 pool
+    /// position - The position, e.g. from `get_position_info`
+    ///
+    /// # Example
+    /// ```rust
+    /// use ethers::types::{Address, U256};
+    /// use std::str::FromStr;
+    /// use std::sync::Arc;
+    /// use crate::{EvmClient, V3PositionService};
+    ///
+    /// async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = Arc::new(EvmClient::new(EvmType::Bsc).await?);
+    /// let service = V3PositionService::new(client);
+    /// let nft_manager = Address::from_str("0x46A15B0b27311cedF172AB29E4f4766fbE7F4364")?;
+    /// let pool = Address::from_str("0x36696169C63e42cd08ce11f5deeBbCeBae652050")?;
+    /// let position = service.get_position_info(nft_manager, U256::from(12345u64)).await?;
+    /// let (fees0, fees1) = service.estimate_uncollected_fees(pool, &position).await?;
+    /// println!("Uncollected fees: {} / {}", fees0, fees1);
+    /// Ok(())
+    /// }
+    /// ```
+    pub async fn estimate_uncollected_fees(
+        &self,
+        pool_address: Address,
+        position: &V3Position,
+    ) -> PancakeResult<(U256, U256)> {
+        let pool = IPancakeV3Pool::new(pool_address, self.evm.client.provider.clone());
+        let (_, tick, ..) = pool
+            .slot_0()
+            .call()
+            .await
+            .map_err(|e| EvmError::ContractError(format!("Failed to get slot0: {}", e)))?;
+        let fee_growth_global0 = pool.fee_growth_global_0x128().call().await.map_err(|e| {
+            EvmError::ContractError(format!("Failed to get fee growth global: {}", e))
+        })?;
+        let fee_growth_global1 = pool.fee_growth_global_1x128().call().await.map_err(|e| {
+            EvmError::ContractError(format!("Failed to get fee growth global: {}", e))
+        })?;
+        let (_, _, lower_outside0, lower_outside1, ..) = pool
+            .ticks(position.tick_lower)
+            .call()
+            .await
+            .map_err(|e| EvmError::ContractError(format!("Failed to get lower tick: {}", e)))?;
+        let (_, _, upper_outside0, upper_outside1, ..) = pool
+            .ticks(position.tick_upper)
+            .call()
+            .await
+            .map_err(|e| EvmError::ContractError(format!("Failed to get upper tick: {}", e)))?;
+
+        let fees0 = Self::accrued_fees(
+            tick,
+            position,
+            fee_growth_global0,
+            lower_outside0,
+            upper_outside0,
+            position.fee_growth_inside0_last_x128,
+        )?;
+        let fees1 = Self::accrued_fees(
+            tick,
+            position,
+            fee_growth_global1,
+            lower_outside1,
+            upper_outside1,
+            position.fee_growth_inside1_last_x128,
+        )?;
+        Ok((
+            position.tokens_owed0.saturating_add(fees0),
+            position.tokens_owed1.saturating_add(fees1),
+        ))
+    }
+
+    /// Fees of one token accrued by `position` since `fee_growth_inside_last`, from the
+    /// pool's current fee growth values
+    fn accrued_fees(
+        tick: i32,
+        position: &V3Position,
+        fee_growth_global: U256,
+        lower_outside: U256,
+        upper_outside: U256,
+        fee_growth_inside_last: U256,
+    ) -> PancakeResult<U256> {
+        let below = if tick >= position.tick_lower {
+            lower_outside
+        } else {
+            fee_growth_global.overflowing_sub(lower_outside).0
+        };
+        let above = if tick < position.tick_upper {
+            upper_outside
+        } else {
+            fee_growth_global.overflowing_sub(upper_outside).0
+        };
+        let inside = fee_growth_global
+            .overflowing_sub(below)
+            .0
+            .overflowing_sub(above)
+            .0;
+        let growth = inside.overflowing_sub(fee_growth_inside_last).0;
+        // growth * liquidity / 2^128, in 512 bits so the product cannot overflow
+        let fees: U512 = growth.full_mul(position.liquidity) >> 128;
+        U256::try_from(fees)
+            .map_err(|_| EvmError::CalculationError("Fee amount overflows U256".to_string()))
+    }
+
     /// Creates a new V3 position
     ///
     /// # Params
     /// nft_position_manager - Address of the NonfungiblePositionManager contract
     /// token0 - Address of the first token in the pair
     /// token1 - Address of the second token in the pair
-    /// fee - The fee tier for the pool (e.g., 3000 for 0.3%)
+    /// fee - The fee tier for the pool (e.g., `FeeTier::Medium` for 0.25%)
     /// tick_lower - The lower tick of the position
     /// tick_upper - The upper tick of the position
     /// amount0_desired - The desired amount of token0 to add
@@ -155,6 +451,11 @@ impl V3PositionService {
     /// recipient - The address that will receive the position NFT
     /// deadline - Unix timestamp after which the transaction will revert
     ///
+    /// The ticks are checked against the pool before minting: `tick_lower` must be below
+    /// `tick_upper`, both within `MIN_TICK..=MAX_TICK` and multiples of the pool's tick
+    /// spacing. With `with_tick_snapping(true)`, unaligned ticks are rounded to the nearest
+    /// usable ticks instead.
+    ///
     /// # Example
     /// ```rust
     /// use ethers::types::{Address, U256};
@@ -172,9 +473,9 @@ impl V3PositionService {
     ///     nft_manager,
     ///     token0,
     ///     token1,
-    ///     3000, // 0.3% fee
-    ///     -887220,
-    ///     887220,
+    ///     FeeTier::Medium, // 0.25% fee
+    ///     -887250,
+    ///     887250,
     ///     U256::from(1000000u64), // 1 USDC
     ///     U256::from(1000000000000000u64), // 0.001 ETH
     ///     U256::from(900000u64), // min 0.9 USDC
@@ -191,7 +492,7 @@ impl V3PositionService {
         nft_position_manager: Address,
         token0: Address,
         token1: Address,
-        fee: u32,
+        fee: FeeTier,
         tick_lower: i32,
         tick_upper: i32,
         amount0_desired: U256,
@@ -200,20 +501,23 @@ impl V3PositionService {
         amount1_min: U256,
         recipient: Address,
         deadline: u64,
-    ) -> Result<ethers::types::H256, EvmError> {
+    ) -> PancakeResult<ethers::types::H256> {
         let wallet = self
             .evm
             .client
             .wallet
             .as_ref()
             .ok_or_else(|| EvmError::WalletError("No wallet configured".to_string()))?;
+        let (tick_lower, tick_upper) = self
+            .validate_position_ticks(token0, token1, fee.as_u32(), tick_lower, tick_upper)
+            .await?;
         let provider = self.evm.client.provider.clone();
         let client = Arc::new(SignerMiddleware::new(provider, wallet.clone()));
         let nft_manager = INonfungiblePositionManager::new(nft_position_manager, client);
         let params = i_nonfungible_position_manager::MintParams {
             token_0: token0,
             token_1: token1,
-            fee: fee,
+            fee: fee.as_u32(),
             tick_lower: tick_lower,
             tick_upper: tick_upper,
             amount_0_desired: amount0_desired,
@@ -277,7 +581,7 @@ impl V3PositionService {
         amount0_min: U256,
         amount1_min: U256,
         deadline: u64,
-    ) -> Result<ethers::types::H256, EvmError> {
+    ) -> PancakeResult<ethers::types::H256> {
         let wallet = self
             .evm
             .client
@@ -344,7 +648,7 @@ impl V3PositionService {
         amount0_min: U256,
         amount1_min: U256,
         deadline: u64,
-    ) -> Result<ethers::types::H256, EvmError> {
+    ) -> PancakeResult<ethers::types::H256> {
         let wallet = self
             .evm
             .client
@@ -408,7 +712,7 @@ impl V3PositionService {
         recipient: Address,
         amount0_max: U256,
         amount1_max: U256,
-    ) -> Result<ethers::types::H256, EvmError> {
+    ) -> PancakeResult<ethers::types::H256> {
         let wallet = self
             .evm
             .client
@@ -431,4 +735,63 @@ impl V3PositionService {
             .map_err(|e| EvmError::TransactionError(format!("Failed to collect fees: {}", e)))?;
         Ok(pending_tx.tx_hash())
     }
+
+    /// Checks position ticks against the tick range and the pool's tick spacing, returning
+    /// the ticks to mint with (snapped when tick snapping is enabled)
+    async fn validate_position_ticks(
+        &self,
+        token0: Address,
+        token1: Address,
+        fee: u32,
+        tick_lower: i32,
+        tick_upper: i32,
+    ) -> PancakeResult<(i32, i32)> {
+        for (name, tick) in [("tick_lower", tick_lower), ("tick_upper", tick_upper)] {
+            if !(MIN_TICK..=MAX_TICK).contains(&tick) {
+                return Err(EvmError::InvalidInput(format!(
+                    "{} {} is outside the tick range [{}, {}]",
+                    name, tick, MIN_TICK, MAX_TICK
+                )));
+            }
+        }
+        let pool_address = FactoryService::new(self.evm.clone())
+            .get_v3_pool(token0, token1, fee)
+            .await?
+            .ok_or_else(|| {
+                EvmError::InvalidInput(format!(
+                    "No V3 pool for {:?}/{:?} with fee {}",
+                    token0, token1, fee
+                ))
+            })?;
+        let (.., tick_spacing) = self.get_pool_parameters(pool_address).await?;
+        if tick_spacing <= 0 {
+            return Err(EvmError::ContractError(format!(
+                "Pool {:?} reports invalid tick spacing {}",
+                pool_address, tick_spacing
+            )));
+        }
+        let (tick_lower, tick_upper) = if self.snap_ticks {
+            (
+                nearest_usable_tick(tick_lower, tick_spacing),
+                nearest_usable_tick(tick_upper, tick_spacing),
+            )
+        } else {
+            for (name, tick) in [("tick_lower", tick_lower), ("tick_upper", tick_upper)] {
+                if tick % tick_spacing != 0 {
+                    return Err(EvmError::InvalidInput(format!(
+                        "{} {} is not a multiple of the pool tick spacing {}",
+                        name, tick, tick_spacing
+                    )));
+                }
+            }
+            (tick_lower, tick_upper)
+        };
+        if tick_lower >= tick_upper {
+            return Err(EvmError::InvalidInput(format!(
+                "tick_lower {} must be below tick_upper {}",
+                tick_lower, tick_upper
+            )));
+        }
+        Ok((tick_lower, tick_upper))
+    }
 }
@@ -0,0 +1,219 @@
+use crate::{
+    EvmError, PancakeResult, PancakeSwapConfig, PancakeSwapService, abi::IQuoter,
+    logging::log_warn, rate_limit::RateLimiter, types::FeeTier, universal_router::encode_v3_path,
+};
+use ethers::types::{Address, Bytes, U256};
+use evm_sdk::Evm;
+use std::sync::Arc;
+use tokio::sync::Semaphore;
+
+/// Default maximum number of pools a V3 route may cross
+pub const DEFAULT_V3_MAX_HOPS: usize = 2;
+
+/// Default maximum number of route quotes in flight at once
+pub const DEFAULT_V3_ROUTE_CONCURRENCY: usize = 8;
+
+/// Finds the best multi-hop V3 route by quoting candidate paths through the Quoter
+///
+/// Candidate routes combine the given intermediate tokens (without repeats) up to `max_hops`
+/// pools, with every allowed fee tier on every hop. The number of quotes grows as
+/// `fee_tiers ^ hops`, so keep the intermediate set and fee tiers small. At most
+/// `DEFAULT_V3_ROUTE_CONCURRENCY` quotes run at once unless changed with
+/// `with_max_concurrency`, and a shared `RateLimiter` can pace them further.
+///
+/// Quotes are separate `eth_call`s rather than one `try_aggregate` batch. Each Quoter call
+/// simulates the whole swap, so a batch of candidate routes can exceed the node's `eth_call`
+/// gas cap and lose every quote at once, while separate calls only drop the failing route.
+///
+/// # Example
+/// ```
+/// use ethers::types::U256;
+///
+/// let finder = V3RouteFinder::new(evm.clone())
+///     .with_fee_tiers(vec![FeeTier::Lowest, FeeTier::Low, FeeTier::Medium])
+///     .with_max_hops(3);
+/// let amount_in = U256::from(1000000000000000000u64);
+/// let (path, fees, amount_out) = finder
+///     .find_best_route(cake, busd, amount_in, vec![wbnb, usdt])
+///     .await?;
+/// ```
+pub struct V3RouteFinder {
+    evm: Arc<Evm>,
+    quoter_address: Option<Address>,
+    fee_tiers: Vec<u32>,
+    max_hops: usize,
+    max_concurrency: usize,
+    rate_limiter: Option<Arc<RateLimiter>>,
+}
+
+impl V3RouteFinder {
+    /// Creates a route finder using the chain's Quoter, all standard fee tiers and two hops
+    pub fn new(evm: Arc<Evm>) -> Self {
+        Self {
+            evm,
+            quoter_address: None,
+            fee_tiers: FeeTier::ALL.map(FeeTier::as_u32).to_vec(),
+            max_hops: DEFAULT_V3_MAX_HOPS,
+            max_concurrency: DEFAULT_V3_ROUTE_CONCURRENCY,
+            rate_limiter: None,
+        }
+    }
+
+    /// Quotes through a specific Quoter instead of the chain default
+    pub fn with_quoter(mut self, quoter_address: Address) -> Self {
+        self.quoter_address = Some(quoter_address);
+        self
+    }
+
+    /// Restricts the fee tiers tried on each hop
+    pub fn with_fee_tiers(mut self, fee_tiers: Vec<FeeTier>) -> Self {
+        self.fee_tiers = fee_tiers.into_iter().map(FeeTier::as_u32).collect();
+        self
+    }
+
+    /// Sets the maximum number of pools a route may cross (at least 1)
+    pub fn with_max_hops(mut self, max_hops: usize) -> Self {
+        self.max_hops = max_hops.max(1);
+        self
+    }
+
+    /// Sets the maximum number of quotes in flight at once (at least 1)
+    pub fn with_max_concurrency(mut self, max_concurrency: usize) -> Self {
+        self.max_concurrency = max_concurrency.max(1);
+        self
+    }
+
+    /// Takes a permit from `rate_limiter` before each quote
+    pub fn with_rate_limiter(mut self, rate_limiter: Arc<RateLimiter>) -> Self {
+        self.rate_limiter = Some(rate_limiter);
+        self
+    }
+
+    /// Quotes all candidate routes, `max_concurrency` at a time, and returns the best
+    /// `(encoded_path, fees, amount_out)`
+    ///
+    /// The encoded path is ready for `exactInput` or a Universal Router V3 swap command.
+    pub async fn find_best_route(
+        &self,
+        token_in: Address,
+        token_out: Address,
+        amount_in: U256,
+        intermediate_tokens: Vec<Address>,
+    ) -> PancakeResult<(Vec<u8>, Vec<u32>, U256)> {
+        if token_in == token_out {
+            return Err(EvmError::InvalidInput(
+                "token_in and token_out must differ".to_string(),
+            ));
+        }
+        if self.fee_tiers.is_empty() {
+            return Err(EvmError::InvalidInput(
+                "At least one fee tier is required".to_string(),
+            ));
+        }
+        let quoter_address = match self.quoter_address {
+            Some(address) => address,
+            None => self
+                .evm
+                .client
+                .evm_type
+                .ok_or_else(|| EvmError::ConfigError("Unsupported chain for V3 Quoter".to_string()))
+                .and_then(PancakeSwapConfig::quoter_address)?,
+        };
+
+        let semaphore = Arc::new(Semaphore::new(self.max_concurrency));
+        let mut tasks = Vec::new();
+        for tokens in self.token_paths(token_in, token_out, &intermediate_tokens) {
+            for fees in self.fee_combinations(tokens.len() - 1) {
+                let path = encode_v3_path(&tokens, &fees)?;
+                let quoter = IQuoter::new(quoter_address, self.evm.client.provider.clone());
+                let call_path = Bytes::from(path.clone());
+                let semaphore = semaphore.clone();
+                let rate_limiter = self.rate_limiter.clone();
+                let task = tokio::spawn(async move {
+                    let _permit = semaphore.acquire_owned().await.ok()?;
+                    RateLimiter::acquire_opt(&rate_limiter).await;
+                    match quoter.quote_exact_input(call_path, amount_in).call().await {
+                        Ok(amount_out) => Some(amount_out),
+                        // Some quoter deployments return the quote in the revert payload
+                        Err(e) => e
+                            .as_revert()
+                            .and_then(|data| PancakeSwapService::decode_quoter_revert(data)),
+                    }
+                });
+                tasks.push((path, fees, task));
+            }
+        }
+
+        let mut best: Option<(Vec<u8>, Vec<u32>, U256)> = None;
+        for (path, fees, task) in tasks {
+            match task.await {
+                Ok(Some(amount_out)) => {
+                    if best
+                        .as_ref()
+                        .is_none_or(|(_, _, best_out)| amount_out > *best_out)
+                    {
+                        best = Some((path, fees, amount_out));
+                    }
+                }
+                Ok(None) => {}
+                Err(e) => {
+                    log_warn!(error = %e, "V3 route quote task failed");
+                }
+            }
+        }
+        best.filter(|(_, _, amount_out)| !amount_out.is_zero())
+            .ok_or_else(|| EvmError::CalculationError("No valid V3 route found".to_string()))
+    }
+
+    /// Token sequences from `token_in` to `token_out` through distinct intermediates
+    fn token_paths(
+        &self,
+        token_in: Address,
+        token_out: Address,
+        intermediate_tokens: &[Address],
+    ) -> Vec<Vec<Address>> {
+        let mut candidates: Vec<Address> = Vec::new();
+        for token in intermediate_tokens {
+            if *token != token_in && *token != token_out && !candidates.contains(token) {
+                candidates.push(*token);
+            }
+        }
+        let mut paths = Vec::new();
+        let mut stack = vec![vec![token_in]];
+        while let Some(prefix) = stack.pop() {
+            let mut path = prefix.clone();
+            path.push(token_out);
+            paths.push(path);
+            // A prefix of n tokens plus token_out crosses n pools
+            if prefix.len() >= self.max_hops {
+                continue;
+            }
+            for token in &candidates {
+                if !prefix.contains(token) {
+                    let mut next = prefix.clone();
+                    next.push(*token);
+                    stack.push(next);
+                }
+            }
+        }
+        paths
+    }
+
+    /// Every assignment of the allowed fee tiers to `hops` pools
+    fn fee_combinations(&self, hops: usize) -> Vec<Vec<u32>> {
+        let mut combinations = vec![Vec::with_capacity(hops)];
+        for _ in 0..hops {
+            combinations = combinations
+                .into_iter()
+                .flat_map(|prefix| {
+                    self.fee_tiers.iter().map(move |fee| {
+                        let mut fees = prefix.clone();
+                        fees.push(*fee);
+                        fees
+                    })
+                })
+                .collect();
+        }
+        combinations
+    }
+}